@@ -13,11 +13,11 @@ use ark_bls12_381::Bls12_381;
 use ark_ec::{PairingEngine, TEModelParameters};
 use ark_ed_on_bls12_381::EdwardsParameters;
 use ark_ff::{FftField, PrimeField};
-use blake2;
 use core::marker::PhantomData;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use plonk::commitment::{HomomorphicCommitment, IPA, KZG10};
+use plonk::commitment::{IPA, KZG10};
 use plonk::prelude::*;
+use plonk::proof_system::ProverCommitment;
 use rand_core::OsRng;
 
 /// Benchmark Circuit
@@ -88,7 +88,7 @@ fn constraint_system_benchmark<F, P, HC>(name: &str, c: &mut Criterion)
 where
     F: PrimeField,
     P: TEModelParameters<BaseField = F>,
-    HC: HomomorphicCommitment<F>,
+    HC: ProverCommitment<F>,
 {
     let label = b"ark".as_slice();
 
@@ -127,7 +127,7 @@ where
             &degree,
             |b, _| {
                 b.iter(|| {
-                    circuit.gen_proof::<HC>(&pp, pk_p.clone(), &label).unwrap()
+                    circuit.gen_proof::<HC>(&pp, pk_p.clone(), label).unwrap()
                 })
             },
         );
@@ -140,18 +140,20 @@ where
         let (pk_p, (vk, _pi_pos)) =
             circuit.compile(&pp).expect("Unable to compile circuit.");
         let (proof, pi) =
-            circuit.gen_proof::<HC>(&pp, pk_p.clone(), &label).unwrap();
+            circuit.gen_proof::<HC>(&pp, pk_p.clone(), label).unwrap();
+        let (_, opening_key) =
+            HC::trim(&pp, vk.padded_circuit_size(), 0, None).unwrap();
         verifying_benchmarks.bench_with_input(
             BenchmarkId::from_parameter(degree),
             &degree,
             |b, _| {
                 b.iter(|| {
                     plonk::circuit::verify_proof::<F, P, HC>(
-                        &pp,
+                        &opening_key,
                         vk.clone(),
                         &proof,
                         &pi,
-                        &label,
+                        label,
                     )
                     .expect("Unable to verify benchmark circuit.");
                 })
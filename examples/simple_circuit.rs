@@ -121,8 +121,11 @@ fn main() -> Result<(), Error> {
 
     // Verifier POV
     let verifier_data = VerifierData::new(vk, pi);
+    let (_, opening_key) =
+        PC::trim(&pp, verifier_data.key.padded_circuit_size(), 0, None)
+            .map_err(to_pc_error::<BlsScalar, PC>)?;
     verify_proof::<BlsScalar, JubJubParameters, PC>(
-        &pp,
+        &opening_key,
         verifier_data.key,
         &proof,
         &verifier_data.pi,
@@ -11,7 +11,8 @@ use crate::{
     error::{to_pc_error, Error},
     prelude::StandardComposer,
     proof_system::{
-        pi::PublicInputs, Proof, Prover, ProverKey, Verifier, VerifierKey,
+        pi::PublicInputs, Proof, Prover, ProverCommitment, ProverKey, Verifier,
+        VerifierKey,
     },
 };
 use ark_ec::models::TEModelParameters;
@@ -195,9 +196,12 @@ where
 ///
 /// assert!(deserialized_verifier_data == verifier_data);
 ///
-/// // Verifier POV
+/// // Verifier POV, which only needs the opening key of the SRS
+/// let (_, opening_key) =
+///     PC::trim(&pp, verifier_data.key.padded_circuit_size(), 0, None)
+///         .map_err(to_pc_error::<BlsScalar, PC>)?;
 /// verify_proof::<BlsScalar, JubJubParameters, PC>(
-///     &pp,
+///     &opening_key,
 ///     verifier_data.key,
 ///     &proof,
 ///     &verifier_data.pi,
@@ -229,7 +233,7 @@ where
     ) -> Result<(ProverKey<F>, (VerifierKey<F, PC>, Vec<usize>)), Error>
     where
         F: PrimeField,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // Setup PublicParams
         let circuit_size = self.padded_circuit_size();
@@ -270,7 +274,7 @@ where
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let circuit_size = self.padded_circuit_size();
         let (ck, _) = PC::trim(u_params, circuit_size, 0, None)
@@ -292,8 +296,14 @@ where
 
 /// Verifies a proof using the provided `CircuitInputs` & `VerifierKey`
 /// instances.
+///
+/// Only the opening key of the commitment scheme is needed, so verifiers
+/// don't have to carry the committer key, which grows with the circuit size.
+/// It is obtained together with the committer key when trimming the
+/// universal parameters to
+/// [`padded_circuit_size`](VerifierKey::padded_circuit_size).
 pub fn verify_proof<F, P, PC>(
-    u_params: &PC::UniversalParams,
+    pc_verifier_key: &PC::VerifierKey,
     plonk_verifier_key: VerifierKey<F, PC>,
     proof: &Proof<F, PC>,
     public_inputs: &PublicInputs<F>,
@@ -305,12 +315,8 @@ where
     PC: HomomorphicCommitment<F>,
 {
     let mut verifier: Verifier<F, P, PC> = Verifier::new(transcript_init);
-    let padded_circuit_size = plonk_verifier_key.padded_circuit_size();
     verifier.verifier_key = Some(plonk_verifier_key);
-    let (_, vk) = PC::trim(u_params, padded_circuit_size, 0, None)
-        .map_err(to_pc_error::<F, PC>)?;
-
-    verifier.verify(proof, &vk, public_inputs)
+    verifier.verify(proof, pc_verifier_key, public_inputs)
 }
 
 #[cfg(test)]
@@ -393,8 +399,9 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
         VerifierData<F, PC>: PartialEq,
+        VerifierKey<F, PC>: PartialEq,
     {
         // Generate CRS
         let pp = PC::setup(1 << 10, None, &mut OsRng)
@@ -405,6 +412,21 @@ mod test {
         // Compile the circuit
         let (pk, (vk, _pi_pos)) = circuit.compile::<PC>(&pp)?;
 
+        // The proving and verifying services only hold their own key, so
+        // test that each one round-trips through serialisation on its own.
+        let mut pk_bytes = Vec::new();
+        pk.serialize(&mut pk_bytes).unwrap();
+        let deserialized_pk =
+            ProverKey::<F>::deserialize(pk_bytes.as_slice()).unwrap();
+        assert!(deserialized_pk == pk);
+
+        let mut vk_bytes = Vec::new();
+        vk.serialize(&mut vk_bytes).unwrap();
+        let deserialized_vk =
+            VerifierKey::<F, PC>::deserialize(vk_bytes.as_slice()).unwrap();
+        assert!(deserialized_vk == vk);
+        let (pk, vk) = (deserialized_pk, deserialized_vk);
+
         let (x, y) = P::AFFINE_GENERATOR_COEFFS;
         let generator: GroupAffine<P> = GroupAffine::new(x, y);
         let point_f_pi: GroupAffine<P> = AffineCurve::mul(
@@ -447,11 +469,14 @@ mod test {
 
         assert!(deserialized_verifier_data == verifier_data);
 
-        // Verifier POV
-
-        // TODO: non-ideal hack for a first functional version.
+        // Verifier POV, holding the opening key but not the committer key nor
+        // the universal parameters.
+        let (_, opening_key) =
+            PC::trim(&pp, verifier_data.key.padded_circuit_size(), 0, None)
+                .map_err(to_pc_error::<F, PC>)?;
+        drop(pp);
         assert!(verify_proof::<F, P, PC>(
-            &pp,
+            &opening_key,
             verifier_data.key,
             &proof,
             &verifier_data.pi,
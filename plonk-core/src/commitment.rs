@@ -1,8 +1,13 @@
 //! Useful commitment stuff
+use crate::error::{to_pc_error, Error};
 use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine};
 use ark_ff::{Field, PrimeField};
-use ark_poly::univariate::DensePolynomial;
-use ark_poly_commit::{sonic_pc::SonicKZG10, PolynomialCommitment};
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
+use ark_poly_commit::{
+    sonic_pc::SonicKZG10, LabeledCommitment, LabeledPolynomial,
+    PolynomialCommitment,
+};
+use rand_core::RngCore;
 
 /// A homomorphic polynomial commitment
 pub trait HomomorphicCommitment<F>:
@@ -16,6 +21,167 @@ where
         commitments: &[Self::Commitment],
         scalars: &[F],
     ) -> Self::Commitment;
+
+    /// Combines the openings of `commitments` to `values` at a common point
+    /// into the single opening that [`PolynomialCommitment::check`] verifies
+    /// for the given opening `challenge`.
+    ///
+    /// By default the openings are combined with the powers of `challenge`.
+    fn combine_openings(
+        commitments: &[Self::Commitment],
+        values: &[F],
+        challenge: F,
+    ) -> (Self::Commitment, F) {
+        linear_combination::<F, Self>(values, commitments, challenge)
+    }
+
+    /// Computes the commitment to `polynomial` that
+    /// [`PolynomialCommitment::commit`] returns without hiding nor degree
+    /// bound, with multi-scalar multiplications over at most `chunk`
+    /// coefficients at a time whose partial sums are accumulated.
+    ///
+    /// This bounds the memory used by the multi-scalar multiplication, which
+    /// otherwise grows with the degree of `polynomial`, for devices where it
+    /// is limited. By default the commitment is computed in one go.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk` is zero.
+    fn commit_chunked_msm(
+        commit_key: &Self::CommitterKey,
+        polynomial: &DensePolynomial<F>,
+        chunk: usize,
+    ) -> Result<Self::Commitment, Error> {
+        assert!(chunk > 0, "Chunks must hold at least one coefficient");
+        let labeled_poly = LabeledPolynomial::new(
+            String::new(),
+            polynomial.clone(),
+            None,
+            None,
+        );
+        let (mut commitments, _) =
+            Self::commit(commit_key, &[labeled_poly], None)
+                .map_err(to_pc_error::<F, Self>)?;
+        Ok(commitments.remove(0).commitment().clone())
+    }
+
+    /// Commits to `polynomial` with a blinding term so that the commitment is
+    /// hiding, independently of the polynomial being committed to.
+    ///
+    /// The blinding factors are sampled from `rng` up to `hiding_bound`,
+    /// which requires the commit key to have been trimmed with a supported
+    /// hiding bound of at least `hiding_bound`. The returned randomness must
+    /// be passed to [`PolynomialCommitment::open`] alongside the commitment,
+    /// together with an `rng`, so that the opening accounts for the blinding.
+    /// See [`HomomorphicCommitment::commit_with_supplied_blinding`] to commit
+    /// with blinding factors chosen by the caller.
+    fn commit_with_blinding<R>(
+        commit_key: &Self::CommitterKey,
+        label: &str,
+        polynomial: &DensePolynomial<F>,
+        hiding_bound: usize,
+        rng: &mut R,
+    ) -> Result<(LabeledCommitment<Self::Commitment>, Self::Randomness), Error>
+    where
+        R: RngCore,
+    {
+        let labeled_poly = LabeledPolynomial::new(
+            label.to_owned(),
+            polynomial.clone(),
+            None,
+            Some(hiding_bound),
+        );
+        let (mut commitments, mut rands) =
+            Self::commit(commit_key, &[labeled_poly], Some(rng))
+                .map_err(to_pc_error::<F, Self>)?;
+        Ok((commitments.remove(0), rands.remove(0)))
+    }
+
+    /// Commits to `polynomial` with the blinding factors of `randomness`
+    /// instead of sampling them, so that committing with the randomness
+    /// returned by [`HomomorphicCommitment::commit_with_blinding`] reproduces
+    /// its commitment.
+    ///
+    /// The randomness is opened like the sampled one. Returns
+    /// [`Error::PolynomialDegreeTooLarge`] if `polynomial` or the blinding
+    /// factors don't fit in `commit_key`.
+    ///
+    /// [`PolynomialCommitment`] only lets schemes sample their blinding, so by
+    /// default this returns [`Error::SuppliedBlindingUnsupported`]. The KZG
+    /// and IPA schemes of this crate support it.
+    fn commit_with_supplied_blinding(
+        _commit_key: &Self::CommitterKey,
+        _label: &str,
+        _polynomial: &DensePolynomial<F>,
+        _randomness: &Self::Randomness,
+    ) -> Result<LabeledCommitment<Self::Commitment>, Error> {
+        Err(Error::SuppliedBlindingUnsupported)
+    }
+
+    /// Returns the commitment to the polynomial `X` under `commit_key`, which
+    /// binds the keys trimmed from an SRS to that SRS, see
+    /// [`HomomorphicCommitment::check_srs_binding`].
+    fn srs_binding(
+        commit_key: &Self::CommitterKey,
+    ) -> Result<Self::Commitment, Error> {
+        let x =
+            DensePolynomial::from_coefficients_vec(vec![F::zero(), F::one()]);
+        Self::commit_chunked_msm(commit_key, &x, 2)
+    }
+
+    /// Checks that `verifier_key` was trimmed from the same SRS as the
+    /// committer key `binding` was computed from with
+    /// [`HomomorphicCommitment::srs_binding`].
+    fn check_srs_binding(
+        verifier_key: &Self::VerifierKey,
+        binding: &Self::Commitment,
+    ) -> bool;
+}
+
+/// Computes `sum scalars_i * bases_i` with a single multi-scalar
+/// multiplication.
+///
+/// Returns [`Error::PolynomialDegreeTooLarge`] if there are more scalars than
+/// bases.
+fn msm<G>(
+    bases: &[G],
+    scalars: &[G::ScalarField],
+) -> Result<G::Projective, Error>
+where
+    G: AffineCurve,
+{
+    if scalars.len() > bases.len() {
+        return Err(Error::PolynomialDegreeTooLarge);
+    }
+    let scalars_repr = scalars
+        .iter()
+        .map(<G::ScalarField as PrimeField>::into_repr)
+        .collect::<Vec<_>>();
+    Ok(VariableBaseMSM::multi_scalar_mul(bases, &scalars_repr))
+}
+
+/// Computes `sum scalars_i * bases_i` with multi-scalar multiplications over
+/// at most `chunk` terms at a time.
+///
+/// Returns [`Error::PolynomialDegreeTooLarge`] if there are more scalars than
+/// bases.
+fn chunked_msm<G>(
+    bases: &[G],
+    scalars: &[G::ScalarField],
+    chunk: usize,
+) -> Result<G::Projective, Error>
+where
+    G: AffineCurve,
+{
+    assert!(chunk > 0, "Chunks must hold at least one coefficient");
+    if scalars.len() > bases.len() {
+        return Err(Error::PolynomialDegreeTooLarge);
+    }
+    bases
+        .chunks(chunk)
+        .zip(scalars.chunks(chunk))
+        .map(|(bases, scalars)| msm(bases, scalars))
+        .sum()
 }
 
 /// The Default KZG-style commitment scheme
@@ -46,6 +212,44 @@ where
                 .into(),
         )
     }
+
+    fn commit_chunked_msm(
+        commit_key: &Self::CommitterKey,
+        polynomial: &DensePolynomial<E::Fr>,
+        chunk: usize,
+    ) -> Result<KZG10Commitment<E>, Error> {
+        let commitment =
+            chunked_msm(&commit_key.powers_of_g, &polynomial.coeffs, chunk)?;
+        Ok(ark_poly_commit::kzg10::Commitment::<E>(commitment.into()))
+    }
+
+    /// The blinding polynomial is committed to with the powers of
+    /// `gamma * g`.
+    fn commit_with_supplied_blinding(
+        commit_key: &Self::CommitterKey,
+        label: &str,
+        polynomial: &DensePolynomial<E::Fr>,
+        randomness: &Self::Randomness,
+    ) -> Result<LabeledCommitment<KZG10Commitment<E>>, Error> {
+        let blinding = &randomness.blinding_polynomial.coeffs;
+        let commitment = msm(&commit_key.powers_of_g, &polynomial.coeffs)?
+            + msm(&commit_key.powers_of_gamma_g, blinding)?;
+        Ok(LabeledCommitment::new(
+            label.to_owned(),
+            ark_poly_commit::kzg10::Commitment::<E>(commitment.into()),
+            None,
+        ))
+    }
+
+    /// The binding is `[beta]_1`, so it must hold that
+    /// `e([beta]_1, h) = e(g, [beta]_2)` for the `beta_h` of `verifier_key`.
+    fn check_srs_binding(
+        verifier_key: &Self::VerifierKey,
+        binding: &KZG10Commitment<E>,
+    ) -> bool {
+        E::pairing(binding.0, verifier_key.h)
+            == E::pairing(verifier_key.g, verifier_key.beta_h)
+    }
 }
 
 /// Shortened type for Inner Product Argument polynomial commitment schemes
@@ -88,6 +292,57 @@ where
             shifted_comm: None, // TODO: support degree bounds?
         }
     }
+
+    fn commit_chunked_msm(
+        commit_key: &Self::CommitterKey,
+        polynomial: &DensePolynomial<<G as ark_ec::AffineCurve>::ScalarField>,
+        chunk: usize,
+    ) -> Result<IPACommitment<G, D>, Error> {
+        let commitment =
+            chunked_msm(&commit_key.comm_key, &polynomial.coeffs, chunk)?;
+        Ok(IPACommitment::<G, D> {
+            comm: commitment.into(),
+            shifted_comm: None,
+        })
+    }
+
+    /// The blinding factor multiplies the hiding generator `s`.
+    fn commit_with_supplied_blinding(
+        commit_key: &Self::CommitterKey,
+        label: &str,
+        polynomial: &DensePolynomial<<G as ark_ec::AffineCurve>::ScalarField>,
+        randomness: &Self::Randomness,
+    ) -> Result<LabeledCommitment<IPACommitment<G, D>>, Error> {
+        let commitment = msm(&commit_key.comm_key, &polynomial.coeffs)?
+            + commit_key.s.mul(randomness.rand);
+        Ok(LabeledCommitment::new(
+            label.to_owned(),
+            IPACommitment::<G, D> {
+                comm: commitment.into(),
+                shifted_comm: None,
+            },
+            None,
+        ))
+    }
+
+    /// The verifier key holds the whole commitment key, whose second
+    /// generator must be the binding.
+    fn check_srs_binding(
+        verifier_key: &Self::VerifierKey,
+        binding: &IPACommitment<G, D>,
+    ) -> bool {
+        verifier_key.comm_key.get(1) == Some(&binding.comm)
+    }
+
+    fn combine_openings(
+        commitments: &[IPACommitment<G, D>],
+        values: &[<G as ark_ec::AffineCurve>::ScalarField],
+        challenge: <G as ark_ec::AffineCurve>::ScalarField,
+    ) -> (IPACommitment<G, D>, <G as ark_ec::AffineCurve>::ScalarField) {
+        // Every other power of the challenge is reserved for the shifted
+        // commitments, which aren't used.
+        linear_combination::<_, Self>(values, commitments, challenge.square())
+    }
 }
 
 /// Computes a linear combination of the polynomial evaluations and polynomial
@@ -115,6 +370,87 @@ where
     (combined_commitment, combined_eval)
 }
 
+/// Splits the polynomial with coefficients `coeffs` into pieces of
+/// `piece_size` coefficients each, so that vectors longer than the commit key
+/// supports can still be committed to with [`commit_pieces`].
+///
+/// The polynomial is recovered as `p(X) = sum X^(i * piece_size) * p_i(X)`,
+/// in the same way the quotient polynomial is split by the prover.
+///
+/// # Panics
+///
+/// Panics if `piece_size` is zero.
+pub fn split_coefficients<F>(
+    coeffs: &[F],
+    piece_size: usize,
+) -> Vec<DensePolynomial<F>>
+where
+    F: PrimeField,
+{
+    assert!(piece_size > 0, "Pieces must hold at least one coefficient");
+    let mut pieces = coeffs
+        .chunks(piece_size)
+        .map(DensePolynomial::from_coefficients_slice)
+        .collect::<Vec<_>>();
+    if pieces.is_empty() {
+        pieces.push(DensePolynomial::from_coefficients_vec(vec![]));
+    }
+    pieces
+}
+
+/// Commits to every piece produced by [`split_coefficients`], labelling the
+/// `i`-th one `"{label}_{i}"`. See [`fold_split_commitments`] to open the
+/// pieces as a whole.
+pub fn commit_pieces<F, PC>(
+    commit_key: &PC::CommitterKey,
+    label: &str,
+    pieces: &[DensePolynomial<F>],
+) -> Result<Vec<LabeledCommitment<PC::Commitment>>, Error>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    let labeled_pieces = pieces
+        .iter()
+        .enumerate()
+        .map(|(i, piece)| {
+            LabeledPolynomial::new(
+                format!("{}_{}", label, i),
+                piece.clone(),
+                None,
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+    let (commitments, _) = PC::commit(commit_key, &labeled_pieces, None)
+        .map_err(to_pc_error::<F, PC>)?;
+    Ok(commitments)
+}
+
+/// Folds the commitments to the pieces produced by [`commit_pieces`],
+/// together with their evaluations at `point`, into a commitment to
+/// `sum point^(i * piece_size) * p_i(X)` and its evaluation at `point`, which
+/// is the evaluation of the full polynomial.
+///
+/// Opening the folded commitment at `point` therefore proves the evaluation
+/// of the full polynomial with a single opening.
+pub fn fold_split_commitments<F, PC>(
+    commitments: &[PC::Commitment],
+    evaluations: &[F],
+    point: F,
+    piece_size: usize,
+) -> (PC::Commitment, F)
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    linear_combination::<F, PC>(
+        evaluations,
+        commitments,
+        point.pow([piece_size as u64]),
+    )
+}
+
 /// Aggregate polynomials
 pub fn aggregate_polynomials<F: Field>(
     polynomials: &[DensePolynomial<F>],
@@ -127,3 +463,253 @@ pub fn aggregate_polynomials<F: Field>(
         .map(|(challenge, poly)| poly * challenge)
         .fold(Zero::zero(), Add::add)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::batch_test;
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::TEModelParameters;
+    use ark_poly::Polynomial;
+    use ark_poly_commit::PCRandomness;
+    use ark_std::test_rng;
+
+    /// Commits twice to the same polynomial with blinding and checks that the
+    /// commitments differ but both open correctly.
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_commit_with_blinding<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let rng = &mut test_rng();
+        let pp = PC::setup(32, None, rng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let (ck, vk) = PC::trim(&pp, 32, 1, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+
+        let poly = DensePolynomial::rand(16, rng);
+        let (comm_1, rand_1) =
+            PC::commit_with_blinding(&ck, "p", &poly, 1, rng).unwrap();
+        let (comm_2, rand_2) =
+            PC::commit_with_blinding(&ck, "p", &poly, 1, rng).unwrap();
+        assert_ne!(
+            ark_ff::to_bytes!(comm_1.commitment()).unwrap(),
+            ark_ff::to_bytes!(comm_2.commitment()).unwrap()
+        );
+
+        let labeled_poly =
+            LabeledPolynomial::new("p".to_owned(), poly.clone(), None, Some(1));
+        let point = F::rand(rng);
+        let eval = poly.evaluate(&point);
+        let challenge = F::rand(rng);
+        for (comm, rand) in [(comm_1, rand_1), (comm_2, rand_2)] {
+            let proof = PC::open(
+                &ck,
+                [&labeled_poly],
+                [&comm],
+                &point,
+                challenge,
+                [&rand],
+                Some(rng),
+            )
+            .unwrap();
+            assert!(PC::check(
+                &vk,
+                [&comm],
+                &point,
+                [eval],
+                &proof,
+                challenge,
+                Some(rng),
+            )
+            .unwrap());
+        }
+    }
+
+    /// Commits with supplied blinding factors and checks that the sampled
+    /// commitment and the unblinded one are reproduced.
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_commit_with_supplied_blinding<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let rng = &mut test_rng();
+        let pp = PC::setup(32, None, rng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let (ck, _) = PC::trim(&pp, 32, 1, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+
+        let poly = DensePolynomial::rand(16, rng);
+        let (comm, rand) =
+            PC::commit_with_blinding(&ck, "p", &poly, 1, rng).unwrap();
+        let supplied =
+            PC::commit_with_supplied_blinding(&ck, "p", &poly, &rand).unwrap();
+        assert_eq!(supplied.label(), "p");
+        assert_eq!(
+            ark_ff::to_bytes!(supplied.commitment()).unwrap(),
+            ark_ff::to_bytes!(comm.commitment()).unwrap()
+        );
+
+        let (plain, _) = PC::commit(
+            &ck,
+            &[LabeledPolynomial::new(
+                "p".to_owned(),
+                poly.clone(),
+                None,
+                None,
+            )],
+            None,
+        )
+        .unwrap();
+        let unblinded = PC::commit_with_supplied_blinding(
+            &ck,
+            "p",
+            &poly,
+            &PC::Randomness::empty(),
+        )
+        .unwrap();
+        assert_eq!(
+            ark_ff::to_bytes!(unblinded.commitment()).unwrap(),
+            ark_ff::to_bytes!(plain[0].commitment()).unwrap()
+        );
+    }
+
+    /// Splits a vector longer than the commit key supports and checks that
+    /// the folded commitment opens to the evaluation of the full polynomial.
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_split_and_fold<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let rng = &mut test_rng();
+        let piece_size = 16;
+        let pp = PC::setup(piece_size, None, rng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let (ck, vk) = PC::trim(&pp, piece_size, 0, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+
+        let full = DensePolynomial::<F>::rand(3 * piece_size + 4, rng);
+        let pieces = split_coefficients(&full.coeffs, piece_size);
+        let commitments = commit_pieces::<F, PC>(&ck, "w", &pieces).unwrap();
+        assert_eq!(pieces.len(), 4);
+        assert_eq!(commitments[3].label(), "w_3");
+
+        let point = F::rand(rng);
+        let evals = pieces
+            .iter()
+            .map(|p| p.evaluate(&point))
+            .collect::<Vec<_>>();
+        let commitments = commitments
+            .iter()
+            .map(|c| c.commitment().clone())
+            .collect::<Vec<_>>();
+        let (folded_comm, folded_eval) = fold_split_commitments::<F, PC>(
+            &commitments,
+            &evals,
+            point,
+            piece_size,
+        );
+        assert_eq!(folded_eval, full.evaluate(&point));
+
+        let folded_poly =
+            aggregate_polynomials(&pieces, point.pow([piece_size as u64]));
+        let folded_poly =
+            LabeledPolynomial::new("w".to_owned(), folded_poly, None, None);
+        let folded_comm =
+            LabeledCommitment::new("w".to_owned(), folded_comm, None);
+        let challenge = F::rand(rng);
+        let proof = PC::open(
+            &ck,
+            [&folded_poly],
+            [&folded_comm],
+            &point,
+            challenge,
+            [&PC::Randomness::empty()],
+            None,
+        )
+        .unwrap();
+        assert!(PC::check(
+            &vk,
+            [&folded_comm],
+            &point,
+            [folded_eval],
+            &proof,
+            challenge,
+            None,
+        )
+        .unwrap());
+    }
+
+    /// Checks that chunked commitments match the standard ones.
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_commit_chunked_msm<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let rng = &mut test_rng();
+        let degree = 1024;
+        let pp = PC::setup(degree, None, rng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let (ck, _) = PC::trim(&pp, degree, 0, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+
+        let poly = DensePolynomial::<F>::rand(degree, rng);
+        let labeled_poly =
+            LabeledPolynomial::new("p".to_owned(), poly.clone(), None, None);
+        let (commitments, _) = PC::commit(&ck, &[labeled_poly], None).unwrap();
+        let expected = ark_ff::to_bytes!(commitments[0].commitment()).unwrap();
+        for chunk in [1, 100, 512, degree + 1, 4096] {
+            let commitment = PC::commit_chunked_msm(&ck, &poly, chunk).unwrap();
+            assert_eq!(ark_ff::to_bytes!(commitment).unwrap(), expected);
+        }
+
+        let too_large = DensePolynomial::<F>::rand(2 * degree, rng);
+        assert!(matches!(
+            PC::commit_chunked_msm(&ck, &too_large, 100),
+            Err(Error::PolynomialDegreeTooLarge)
+        ));
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [
+            test_commit_with_blinding,
+            test_commit_with_supplied_blinding,
+            test_split_and_fold,
+            test_commit_chunked_msm
+        ],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [
+            test_commit_with_blinding,
+            test_commit_with_supplied_blinding,
+            test_split_and_fold,
+            test_commit_chunked_msm
+        ],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
@@ -0,0 +1,233 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! AES Gates
+//!
+//! Bytes are handled through their bit decomposition so that the arithmetic
+//! in `GF(2^8) = GF(2)[X] / (X^8 + X^4 + X^3 + X + 1)` reduces to parities of
+//! small integer sums of bit products, which are checked with a boolean for
+//! the parity and a range check on the halved remainder.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::{BigInteger, PrimeField};
+
+/// Affine constant of the AES S-box.
+const SBOX_AFFINE_CONSTANT: u8 = 0x63;
+
+/// Multiplies by `X` in `GF(2^8)`.
+fn gf256_xtime(a: u8) -> u8 {
+    (a << 1) ^ if a & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+/// Multiplies two elements of `GF(2^8)`.
+fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a = gf256_xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+/// Inverts an element of `GF(2^8)` as `a^254`, mapping zero to zero.
+fn gf256_inv(a: u8) -> u8 {
+    (0..254).fold(1, |acc, _| gf256_mul(acc, a))
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Adds the AES S-box to the circuit description, returning
+    /// `S(a) = A · a^(-1) + 0x63`, where the inversion happens in `GF(2^8)`
+    /// (with `0^(-1) = 0`) and `A` is the affine map of the AES specification.
+    ///
+    /// The inverse is given as a witness and checked by multiplying it with
+    /// `a` in `GF(2^8)`. Both `a` and the result are constrained to be bytes.
+    pub fn aes_sbox(&mut self, a: Variable) -> Variable {
+        let zero = self.zero_var;
+        let a_bits = self.byte_to_bits(a);
+
+        let a_byte = self.value_of_var(a).into_repr().as_ref()[0] as u8;
+        let inv = self.add_input(F::from(gf256_inv(a_byte) as u64));
+        let inv_bits = self.byte_to_bits(inv);
+
+        // The inverse of zero is zero, otherwise `a · inv = 1`.
+        let is_zero = self.is_zero_with_output(a);
+        self.arithmetic_gate(|gate| {
+            gate.witness(is_zero, inv, Some(zero)).mul(F::one())
+        });
+
+        let mut products = [[zero; 8]; 8];
+        for (i, a_bit) in a_bits.iter().enumerate() {
+            for (j, inv_bit) in inv_bits.iter().enumerate() {
+                products[i][j] = self.arithmetic_gate(|gate| {
+                    gate.witness(*a_bit, *inv_bit, None).mul(F::one())
+                });
+            }
+        }
+
+        // `X^k mod (X^8 + X^4 + X^3 + X + 1)` for every degree of the
+        // unreduced product.
+        let mut reduced_powers = [1u8; 15];
+        for k in 1..15 {
+            reduced_powers[k] = gf256_xtime(reduced_powers[k - 1]);
+        }
+        for m in 0..8 {
+            let terms = (0..8)
+                .flat_map(|i| (0..8).map(move |j| (i, j)))
+                .filter(|(i, j)| (reduced_powers[i + j] >> m) & 1 == 1)
+                .map(|(i, j)| (F::one(), products[i][j]))
+                .collect::<Vec<_>>();
+            let sum = self.weighted_sum(&terms, F::zero());
+            let bit = self.parity(sum, 6);
+            if m == 0 {
+                // bit == 1 - is_zero
+                self.arithmetic_gate(|gate| {
+                    gate.witness(bit, is_zero, Some(zero))
+                        .add(F::one(), F::one())
+                        .constant(-F::one())
+                });
+            } else {
+                self.constrain_to_constant(bit, F::zero(), None);
+            }
+        }
+
+        // s_i = b_i + b_(i+4) + b_(i+5) + b_(i+6) + b_(i+7) + c_i over GF(2)
+        let mut constant = F::zero();
+        let out_terms = (0..8)
+            .map(|i| {
+                let terms = [0, 4, 5, 6, 7]
+                    .map(|offset| (F::one(), inv_bits[(i + offset) % 8]));
+                let sum = self.weighted_sum(&terms, F::zero());
+                let bit = self.parity(sum, 2);
+                let power = F::from(1u64 << i);
+                if (SBOX_AFFINE_CONSTANT >> i) & 1 == 1 {
+                    constant += power;
+                    (-power, bit)
+                } else {
+                    (power, bit)
+                }
+            })
+            .collect::<Vec<_>>();
+        self.weighted_sum(&out_terms, constant)
+    }
+
+    /// Decomposes `a` into its 8 little-endian bits, constraining it to be a
+    /// byte.
+    fn byte_to_bits(&mut self, a: Variable) -> [Variable; 8] {
+        let value = self.value_of_var(a).into_repr();
+        let mut bits = [self.zero_var; 8];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            *bit = self.add_input(F::from(value.get_bit(i) as u64));
+            self.boolean_gate(*bit);
+        }
+        let terms = bits
+            .iter()
+            .enumerate()
+            .map(|(i, bit)| (F::from(1u64 << i), *bit))
+            .collect::<Vec<_>>();
+        let recomposed = self.weighted_sum(&terms, F::zero());
+        self.assert_equal(recomposed, a);
+        bits
+    }
+
+    /// Returns the parity bit of `sum`, which must hold an integer smaller
+    /// than `2^(half_bits + 1)`, by constraining `sum = 2 · q + bit` with `q`
+    /// a `half_bits`-bit number.
+    fn parity(&mut self, sum: Variable, half_bits: usize) -> Variable {
+        let value = self.value_of_var(sum).into_repr();
+        let bit = self.add_input(F::from(value.get_bit(0) as u64));
+        let mut half = value;
+        half.div2();
+        let half = self.add_input(F::from_repr(half).unwrap());
+        self.boolean_gate(bit);
+        self.range_gate(half, half_bits);
+        self.arithmetic_gate(|gate| {
+            gate.witness(half, bit, Some(sum))
+                .add(F::from(2u64), F::one())
+        });
+        bit
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test, constraint_system::helper::*,
+        proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    fn test_aes_sbox<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for (input, output) in [
+                    (0x00u64, 0x63u64),
+                    (0x01, 0x7c),
+                    (0x10, 0xca),
+                    (0x53, 0xed),
+                    (0xc9, 0xdd),
+                    (0xff, 0x16),
+                ] {
+                    let a = composer.add_input(F::from(input));
+                    let s = composer.aes_sbox(a);
+                    composer.constrain_to_constant(s, F::from(output), None);
+                }
+            },
+            2048,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(0x53u64));
+                let s = composer.aes_sbox(a);
+                composer.constrain_to_constant(s, F::from(0x53u64), None);
+            },
+            512,
+        );
+        assert!(res.is_err());
+
+        // Inputs which aren't bytes are rejected.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(0x153u64));
+                composer.aes_sbox(a);
+            },
+            512,
+        );
+        assert!(res.is_err());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_aes_sbox],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_aes_sbox],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
@@ -6,9 +6,12 @@
 
 //! Simple Arithmetic Gates
 
-use crate::constraint_system::{StandardComposer, Variable};
+use crate::{
+    constraint_system::{StandardComposer, Variable},
+    error::Error,
+};
 use ark_ec::TEModelParameters;
-use ark_ff::PrimeField;
+use ark_ff::{FpParameters, PrimeField};
 
 #[derive(Debug, Clone, Copy)]
 pub struct ArithmeticGate<F>
@@ -139,6 +142,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
 
         if let Some(pi) = gate.pi {
             self.add_pi(self.n, &pi).unwrap_or_else(|_| {
@@ -171,6 +175,165 @@ where
 
         c
     }
+
+    /// Computes the linear combination `sum coeff_i * var_i + constant` of
+    /// the given `terms` and returns it as a new [`Variable`].
+    ///
+    /// The first gate consumes up to three terms through the left, right and
+    /// fourth wires, and every following gate takes the running sum together
+    /// with two more terms, so `k` terms use `max(1, ceil((k - 1) / 2))`
+    /// gates.
+    pub fn weighted_sum(
+        &mut self,
+        terms: &[(F, Variable)],
+        constant: F,
+    ) -> Variable {
+        let zero = self.zero_var;
+        let term = |index: usize| {
+            terms.get(index).copied().unwrap_or((F::zero(), zero))
+        };
+
+        let (q_l, a) = term(0);
+        let (q_r, b) = term(1);
+        let (q_4, d) = term(2);
+        let mut sum = self.arithmetic_gate(|gate| {
+            gate.witness(a, b, None)
+                .add(q_l, q_r)
+                .fan_in_3(q_4, d)
+                .constant(constant)
+        });
+
+        let mut index = 3;
+        while index < terms.len() {
+            let (q_r, b) = term(index);
+            let (q_4, d) = term(index + 1);
+            sum = self.arithmetic_gate(|gate| {
+                gate.witness(sum, b, None)
+                    .add(F::one(), q_r)
+                    .fan_in_3(q_4, d)
+            });
+            index += 2;
+        }
+        sum
+    }
+
+    /// Computes the inner product `sum a_i * b_i` of two vectors of
+    /// [`Variable`]s, using one gate per term by accumulating the running sum
+    /// through the fourth wire.
+    ///
+    /// Returns [`Error::DimensionMismatch`] if `a` and `b` have different
+    /// lengths.
+    pub fn inner_product(
+        &mut self,
+        a: &[Variable],
+        b: &[Variable],
+    ) -> Result<Variable, Error> {
+        if a.len() != b.len() {
+            return Err(Error::DimensionMismatch {
+                expected: a.len(),
+                found: b.len(),
+            });
+        }
+        Ok(self.inner_product_unchecked(a, b))
+    }
+
+    /// Computes the matrix-vector product `matrix * vector`, where `matrix`
+    /// is given as a list of rows, with one [`StandardComposer::inner_product`]
+    /// per row.
+    ///
+    /// Returns [`Error::DimensionMismatch`] if any row of `matrix` has a
+    /// length different from the length of `vector`, in which case no gate
+    /// is added.
+    pub fn matrix_vector_mul(
+        &mut self,
+        matrix: &[Vec<Variable>],
+        vector: &[Variable],
+    ) -> Result<Vec<Variable>, Error> {
+        if let Some(row) = matrix.iter().find(|row| row.len() != vector.len()) {
+            return Err(Error::DimensionMismatch {
+                expected: vector.len(),
+                found: row.len(),
+            });
+        }
+        Ok(matrix
+            .iter()
+            .map(|row| self.inner_product_unchecked(row, vector))
+            .collect())
+    }
+
+    /// Computes the inner product of `a` and `b`, which must have the same
+    /// length.
+    fn inner_product_unchecked(
+        &mut self,
+        a: &[Variable],
+        b: &[Variable],
+    ) -> Variable {
+        a.iter().zip(b).fold(self.zero_var, |acc, (a_i, b_i)| {
+            self.arithmetic_gate(|gate| {
+                gate.witness(*a_i, *b_i, None)
+                    .mul(F::one())
+                    .fan_in_3(F::one(), acc)
+            })
+        })
+    }
+
+    /// Computes `sum mask_i * values_i`, i.e. the sum of the `values` whose
+    /// `mask` bit is set, constraining every element of `mask` to be boolean.
+    ///
+    /// The cost is one [`StandardComposer::boolean_gate`] and one gate of
+    /// [`StandardComposer::inner_product`] per element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mask` and `values` have different lengths.
+    pub fn masked_sum(
+        &mut self,
+        mask: &[Variable],
+        values: &[Variable],
+    ) -> Variable {
+        assert_eq!(mask.len(), values.len(), "Every value needs a mask bit");
+        for bit in mask {
+            self.boolean_gate(*bit);
+        }
+        self.inner_product_unchecked(mask, values)
+    }
+
+    /// Asserts that `total` is the sum of the `entries`, e.g. that the sum of
+    /// the outputs of a transaction is the sum of its inputs.
+    ///
+    /// The entries are accumulated with a [`StandardComposer::weighted_sum`]
+    /// and the result is constrained to be equal to `total`. If `entry_bits`
+    /// is set, every entry is also range-checked to that many bits with a
+    /// single [`StandardComposer::multi_range`], so that no entry can be a
+    /// "negative" field element and the sum can't wrap around the field
+    /// modulus.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sum of the range-checked entries may exceed the field
+    /// capacity.
+    pub fn assert_running_total(
+        &mut self,
+        entries: &[Variable],
+        total: Variable,
+        entry_bits: Option<usize>,
+    ) {
+        if let Some(num_bits) = entry_bits {
+            let carry_bits =
+                (usize::BITS - entries.len().leading_zeros()) as usize;
+            assert!(
+                num_bits + carry_bits <= F::Params::CAPACITY as usize,
+                "The running total may exceed the field capacity"
+            );
+            self.multi_range(entries, &vec![num_bits; entries.len()]);
+        }
+        let terms = entries
+            .iter()
+            .map(|entry| (F::one(), *entry))
+            .collect::<Vec<_>>();
+        let sum = self.weighted_sum(&terms, F::zero());
+        self.assert_equal(sum, total);
+    }
 }
 
 #[cfg(test)]
@@ -178,7 +341,7 @@ mod test {
     use super::*;
     use crate::{
         batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*,
+        constraint_system::helper::*, proof_system::ProverCommitment,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -187,7 +350,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -226,7 +389,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -270,7 +433,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -294,7 +457,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -334,7 +497,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -367,7 +530,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -400,7 +563,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -429,6 +592,215 @@ mod test {
         assert!(res.is_err());
     }
 
+    fn test_weighted_sum<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let terms = (1..=7u64)
+                    .map(|i| {
+                        (F::from(i + 1), composer.add_input(F::from(i * 10)))
+                    })
+                    .collect::<Vec<_>>();
+
+                let n = composer.n;
+                let sum = composer.weighted_sum(&terms, F::from(5u64));
+                // 3 terms in the first gate and 2 more in each following one
+                assert_eq!(composer.n - n, 3);
+
+                // 2*10 + 3*20 + 4*30 + 5*40 + 6*50 + 7*60 + 8*70 + 5
+                composer.constrain_to_constant(sum, F::from(1685u64), None);
+
+                let empty = composer.weighted_sum(&[], F::from(5u64));
+                composer.constrain_to_constant(empty, F::from(5u64), None);
+            },
+            200,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+    }
+
+    fn test_matrix_vector_mul<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let matrix = [[1u64, 2, 3], [4, 5, 6], [7, 8, 10]];
+                let vector = [2u64, 3, 5];
+                let expected = matrix.map(|row| {
+                    row.iter().zip(vector).map(|(m, v)| m * v).sum::<u64>()
+                });
+
+                let matrix_vars = matrix
+                    .iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|m| composer.add_input(F::from(*m)))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let vector_vars = vector
+                    .iter()
+                    .map(|v| composer.add_input(F::from(*v)))
+                    .collect::<Vec<_>>();
+
+                let n = composer.n;
+                let product = composer
+                    .matrix_vector_mul(&matrix_vars, &vector_vars)
+                    .unwrap();
+                assert_eq!(composer.n - n, 9);
+
+                for (out, expected) in product.into_iter().zip(expected) {
+                    composer.constrain_to_constant(
+                        out,
+                        F::from(expected),
+                        None,
+                    );
+                }
+            },
+            64,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+    }
+
+    fn test_masked_sum<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let mask = [1u64, 0, 0, 1, 1, 0]
+                    .map(|bit| composer.add_input(F::from(bit)));
+                let values = [3u64, 100, 200, 5, 7, 300]
+                    .map(|value| composer.add_input(F::from(value)));
+                let sum = composer.masked_sum(&mask, &values);
+                composer.constrain_to_constant(sum, F::from(15u64), None);
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Mask elements which aren't bits are rejected.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let mask =
+                    [2u64, 0].map(|bit| composer.add_input(F::from(bit)));
+                let values =
+                    [3u64, 5].map(|value| composer.add_input(F::from(value)));
+                let sum = composer.masked_sum(&mask, &values);
+                composer.constrain_to_constant(sum, F::from(6u64), None);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_assert_running_total<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let entries = [120u64, 35, 0, 7, 1 << 40, 99, 5]
+                    .map(|entry| composer.add_input(F::from(entry)));
+                let total = composer.add_input(F::from((1u64 << 40) + 266));
+                composer.assert_running_total(&entries, total, Some(64));
+                composer.assert_running_total(&entries, total, None);
+
+                let zero = composer.zero_var();
+                composer.assert_running_total(&[], zero, Some(64));
+            },
+            256,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the total doesn't match the entries
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let entries = [120u64, 35, 7]
+                    .map(|entry| composer.add_input(F::from(entry)));
+                let total = composer.add_input(F::from(163u64));
+                composer.assert_running_total(&entries, total, Some(64));
+            },
+            128,
+        );
+        assert!(res.is_err());
+
+        // Should fail as an entry is "negative", which the range checks
+        // reject even though the sum matches the total in the field
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let entries = [F::from(120u64), -F::from(20u64)]
+                    .map(|entry| composer.add_input(entry));
+                let total = composer.add_input(F::from(100u64));
+                composer.assert_running_total(&entries, total, Some(64));
+            },
+            128,
+        );
+        assert!(res.is_err());
+
+        // Should fail as an entry overflows the range checks
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let entries = [1u64 << 16, 1]
+                    .map(|entry| composer.add_input(F::from(entry)));
+                let total = composer.add_input(F::from((1u64 << 16) + 1));
+                composer.assert_running_total(&entries, total, Some(16));
+            },
+            128,
+        );
+        assert!(res.is_err());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_masked_sum_length_mismatch<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let one = composer.add_input(F::one());
+        composer.masked_sum(&[one], &[one, one]);
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_dimension_mismatch<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let one = composer.add_input(F::one());
+        let n = composer.n;
+        assert!(matches!(
+            composer.inner_product(&[one], &[one, one]),
+            Err(Error::DimensionMismatch {
+                expected: 1,
+                found: 2
+            })
+        ));
+        assert!(matches!(
+            composer
+                .matrix_vector_mul(&[vec![one, one], vec![one]], &[one, one]),
+            Err(Error::DimensionMismatch {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert_eq!(composer.n, n);
+    }
+
     // Bls12-381 tests
     batch_test!(
         [
@@ -438,9 +810,14 @@ mod test {
             test_correct_big_add_mul_gate,
             test_correct_big_arith_gate,
             test_incorrect_add_mul_gate,
-            test_incorrect_big_arith_gate
+            test_incorrect_big_arith_gate,
+            test_weighted_sum,
+            test_matrix_vector_mul,
+            test_dimension_mismatch,
+            test_masked_sum,
+            test_assert_running_total
         ],
-        [] => (
+        [test_masked_sum_length_mismatch] => (
             Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
         )
     );
@@ -454,9 +831,14 @@ mod test {
             test_correct_big_add_mul_gate,
             test_correct_big_arith_gate,
             test_incorrect_add_mul_gate,
-            test_incorrect_big_arith_gate
+            test_incorrect_big_arith_gate,
+            test_weighted_sum,
+            test_matrix_vector_mul,
+            test_dimension_mismatch,
+            test_masked_sum,
+            test_assert_running_total
         ],
-        [] => (
+        [test_masked_sum_length_mismatch] => (
             Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
         )
     );
@@ -0,0 +1,160 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! BLS Signature Gates
+//!
+//! Verification of BLS signatures over a BLS12 curve in a circuit over the
+//! base field of the curve, with the [pairing gates](super::pairing). The
+//! signatures and the hashes of the messages are points of `G1`, and the
+//! public keys points of `G2`: a signature of a message hashed to `H(m)`
+//! under the secret key `sk` is `sk * H(m)`, and the public key is
+//! `sk * G2`. The messages are hashed to `G1` out of the circuit.
+//!
+//! The points are only constrained to be on the curves, so the caller has to
+//! ensure that they belong to the prime-order subgroups, as a verifier does
+//! out of the circuit.
+
+use crate::constraint_system::{
+    pairing::{Fqk, G1Var, G2Var},
+    StandardComposer,
+};
+use ark_ec::{
+    bls12::{Bls12Parameters, G2Affine},
+    AffineCurve, TEModelParameters,
+};
+use ark_ff::{One, PrimeField};
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Constrains `signature` to be the BLS signature of the message hashed
+    /// to `message` under `public_key`, checking `e(signature, G2) =
+    /// e(message, public_key)`.
+    pub fn bls_verify<B>(
+        &mut self,
+        public_key: G2Var<B>,
+        message: G1Var<B>,
+        signature: G1Var<B>,
+    ) where
+        B: Bls12Parameters<Fp = F>,
+    {
+        self.bls_verify_aggregate(&[(public_key, message)], signature);
+    }
+
+    /// Constrains `signature` to be the aggregate of the BLS signatures of
+    /// the messages hashed to the points of `signers` under their public
+    /// keys, checking `e(signature, G2) = prod e(message_i, public_key_i)`.
+    ///
+    /// Every pairing costs a Miller loop, and the final exponentiation is
+    /// shared by all of them.
+    pub fn bls_verify_aggregate<B>(
+        &mut self,
+        signers: &[(G2Var<B>, G1Var<B>)],
+        signature: G1Var<B>,
+    ) where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let pairs = signers
+            .iter()
+            .map(|(public_key, message)| (self.g1_neg(*message), *public_key))
+            .collect::<Vec<_>>();
+        self.assert_pairing_product(
+            &pairs,
+            &[(signature, G2Affine::<B>::prime_subgroup_generator())],
+            Fqk::<B>::one(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_377::{Fq, Fr, G1Affine, G1Projective, Parameters};
+    use ark_ec::ProjectiveCurve;
+    use ark_ed_on_bw6_761::EdwardsParameters;
+    use ark_ff::UniformRand;
+    use rand_core::OsRng;
+
+    type Composer = StandardComposer<Fq, EdwardsParameters>;
+
+    /// Returns a random public key, a random message hashed to `G1`, and its
+    /// signature under the secret key.
+    fn sign() -> (G2Affine<Parameters>, G1Affine, G1Affine) {
+        let secret_key = Fr::rand(&mut OsRng);
+        let message = G1Projective::rand(&mut OsRng).into_affine();
+        (
+            G2Affine::<Parameters>::prime_subgroup_generator()
+                .mul(secret_key)
+                .into_affine(),
+            message,
+            message.mul(secret_key).into_affine(),
+        )
+    }
+
+    #[test]
+    fn test_bls_verify() {
+        let (public_key, message, signature) = sign();
+        let mut composer = Composer::new();
+        let public_key = composer.add_g2_input::<Parameters>(public_key);
+        let message = composer.add_g1_input::<Parameters>(message);
+        let signature = composer.add_g1_input::<Parameters>(signature);
+        composer.bls_verify(public_key, message, signature);
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    fn test_bls_verify_aggregate() {
+        let signers = [sign(), sign()];
+        let signature = signers[0].2 + signers[1].2;
+        let mut composer = Composer::new();
+        let signers = signers
+            .iter()
+            .map(|(public_key, message, _)| {
+                (
+                    composer.add_g2_input::<Parameters>(*public_key),
+                    composer.add_g1_input::<Parameters>(*message),
+                )
+            })
+            .collect::<Vec<_>>();
+        let signature = composer.add_g1_input::<Parameters>(signature);
+        composer.bls_verify_aggregate(&signers, signature);
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn test_bls_verify_other_message() {
+        let (public_key, _, signature) = sign();
+        let (_, message, _) = sign();
+        let mut composer = Composer::new();
+        let public_key = composer.add_g2_input::<Parameters>(public_key);
+        let message = composer.add_g1_input::<Parameters>(message);
+        let signature = composer.add_g1_input::<Parameters>(signature);
+        composer.bls_verify(public_key, message, signature);
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn test_bls_verify_aggregate_other_signature() {
+        let signers = [sign(), sign()];
+        let mut composer = Composer::new();
+        let signers = signers
+            .iter()
+            .map(|(public_key, message, _)| {
+                (
+                    composer.add_g2_input::<Parameters>(*public_key),
+                    composer.add_g1_input::<Parameters>(*message),
+                )
+            })
+            .collect::<Vec<_>>();
+        let signature = composer.add_g1_input::<Parameters>(sign().2);
+        composer.bls_verify_aggregate(&signers, signature);
+        composer.check_circuit_satisfied();
+    }
+}
@@ -7,8 +7,8 @@
 //! Boolean Gates
 
 use crate::constraint_system::{StandardComposer, Variable};
-use ark_ec::ModelParameters;
-use ark_ff::PrimeField;
+use ark_ec::{ModelParameters, TEModelParameters};
+use ark_ff::{FpParameters, PrimeField};
 
 impl<F, P> StandardComposer<F, P>
 where
@@ -46,6 +46,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
 
         self.perm
             .add_variables_to_map(a, a, a, self.zero_var, self.n);
@@ -56,22 +57,57 @@ where
     }
 }
 
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Packs `bits` into a single bitmask [`Variable`] holding
+    /// `sum bits[i] * 2^i`, so that `bits[0]` is the least significant bit.
+    ///
+    /// Every bit is constrained with a [`StandardComposer::boolean_gate`],
+    /// which makes the packing injective: two different sets of flags always
+    /// give two different bitmasks. The cost is one gate per bit plus the
+    /// gates of the weighted sum, around `len / 2`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more bits than the field capacity, since the
+    /// bitmask would then wrap around the modulus.
+    pub fn pack_bits(&mut self, bits: &[Variable]) -> Variable {
+        assert!(
+            bits.len() <= F::Params::CAPACITY as usize,
+            "Cannot pack more bits than the field capacity"
+        );
+        let mut power = F::one();
+        let terms = bits
+            .iter()
+            .map(|bit| {
+                self.boolean_gate(*bit);
+                let term = (power, *bit);
+                power.double_in_place();
+                term
+            })
+            .collect::<Vec<_>>();
+        self.weighted_sum(&terms, F::zero())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::{
         batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*,
+        constraint_system::helper::*, proof_system::ProverCommitment,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
-    use ark_ec::TEModelParameters;
 
     fn test_correct_bool_gate<F, P, PC>()
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -89,7 +125,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -103,13 +139,60 @@ mod test {
         assert!(res.is_err())
     }
 
+    fn test_pack_bits<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let flags = [1u64, 0, 1, 1, 0, 0, 0, 1]
+                    .map(|bit| composer.add_input(F::from(bit)));
+                let mask = composer.pack_bits(&flags);
+                composer.constrain_to_constant(mask, F::from(0x8du64), None);
+
+                let empty = composer.pack_bits(&[]);
+                composer.constrain_to_constant(empty, F::zero(), None);
+            },
+            64,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // A non-boolean flag can't be packed, even with a matching bitmask.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let flags = [0u64, 2, 0, 0, 0, 0, 0, 0]
+                    .map(|bit| composer.add_input(F::from(bit)));
+                let mask = composer.pack_bits(&flags);
+                composer.constrain_to_constant(mask, F::from(4u64), None);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_pack_bits_over_capacity<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let zero = composer.zero_var();
+        let bits = vec![zero; F::Params::CAPACITY as usize + 1];
+        composer.pack_bits(&bits);
+    }
+
     // Test for Bls12_381
     batch_test!(
         [
             test_correct_bool_gate,
-            test_incorrect_bool_gate
+            test_incorrect_bool_gate,
+            test_pack_bits
         ],
-        [] => (
+        [test_pack_bits_over_capacity] => (
             Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
         )
     );
@@ -118,9 +201,10 @@ mod test {
     batch_test!(
         [
             test_correct_bool_gate,
-            test_incorrect_bool_gate
+            test_incorrect_bool_gate,
+            test_pack_bits
         ],
-        [] => (
+        [test_pack_bits_over_capacity] => (
             Bls12_377, ark_ed_on_bls12_377::EdwardsParameters        )
     );
 }
@@ -0,0 +1,540 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Comparison Gates
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::{BigInteger, PrimeField};
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Returns a boolean [`Variable`] which is `1` if `a < b` and `0`
+    /// otherwise.
+    ///
+    /// The comparison is done by constraining
+    /// `a - b + lt * 2^num_bits` to be a `num_bits`-bit number, which only
+    /// holds for the right value of the bit `lt`.
+    ///
+    /// # Note
+    /// Both `a` and `b` must have previously been constrained to be smaller
+    /// than `2^num_bits`, e.g. with [`StandardComposer::range_gate`].
+    pub fn less_than(
+        &mut self,
+        a: Variable,
+        b: Variable,
+        num_bits: usize,
+    ) -> Variable {
+        let a_value = self.value_of_var(a);
+        let b_value = self.value_of_var(b);
+        let is_less = a_value.into_repr() < b_value.into_repr();
+        let power = F::from(2u64).pow([num_bits as u64]);
+
+        let lt = self.add_input(F::from(is_less as u64));
+        self.boolean_gate(lt);
+        let difference = if is_less {
+            a_value - b_value + power
+        } else {
+            a_value - b_value
+        };
+        let difference = self.add_input(difference);
+        self.multi_range(&[difference], &[num_bits]);
+        self.arithmetic_gate(|gate| {
+            gate.witness(a, b, Some(difference))
+                .add(F::one(), -F::one())
+                .fan_in_3(power, lt)
+        });
+        lt
+    }
+
+    /// Returns a boolean [`Variable`] which is `1` if `a`, read as a
+    /// `num_bits`-bit two's complement integer, is negative and `0` otherwise.
+    ///
+    /// The sign is the top bit of `a`, extracted by constraining
+    /// `a = sign * 2^(num_bits - 1) + rest` with `rest` a
+    /// `(num_bits - 1)`-bit number, which also constrains `a` to be smaller
+    /// than `2^num_bits`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` is zero.
+    pub fn sign(&mut self, a: Variable, num_bits: usize) -> Variable {
+        assert!(num_bits > 0, "A signed integer needs at least one bit");
+        let a_value = self.value_of_var(a).into_repr();
+        let is_negative = a_value.get_bit(num_bits - 1);
+        let power = F::from(2u64).pow([num_bits as u64 - 1]);
+
+        let sign = self.add_input(F::from(is_negative as u64));
+        self.boolean_gate(sign);
+        let rest = if is_negative {
+            self.value_of_var(a) - power
+        } else {
+            self.value_of_var(a)
+        };
+        let rest = self.add_input(rest);
+        self.multi_range(&[rest], &[num_bits - 1]);
+        self.arithmetic_gate(|gate| {
+            gate.witness(sign, rest, Some(a)).add(power, F::one())
+        });
+        sign
+    }
+
+    /// Returns the smaller of `a` and `b`.
+    ///
+    /// # Note
+    /// Both `a` and `b` must have previously been constrained to be smaller
+    /// than `2^num_bits`, see [`StandardComposer::less_than`].
+    pub fn min(
+        &mut self,
+        a: Variable,
+        b: Variable,
+        num_bits: usize,
+    ) -> Variable {
+        let lt = self.less_than(a, b, num_bits);
+        self.conditional_select(lt, a, b)
+    }
+
+    /// Returns the larger of `a` and `b`.
+    ///
+    /// # Note
+    /// Both `a` and `b` must have previously been constrained to be smaller
+    /// than `2^num_bits`, see [`StandardComposer::less_than`].
+    pub fn max(
+        &mut self,
+        a: Variable,
+        b: Variable,
+        num_bits: usize,
+    ) -> Variable {
+        let lt = self.less_than(a, b, num_bits);
+        self.conditional_select(lt, b, a)
+    }
+
+    /// Constrains `next >= prev`, as for a counter which must never decrease
+    /// between two states of a circuit.
+    ///
+    /// Both `prev` and `next` are constrained to be smaller than
+    /// `2^num_bits`.
+    pub fn assert_monotonic(
+        &mut self,
+        prev: Variable,
+        next: Variable,
+        num_bits: usize,
+    ) {
+        self.multi_range(&[prev, next], &[num_bits, num_bits]);
+        let decreasing = self.less_than(next, prev, num_bits);
+        self.constrain_to_constant(decreasing, F::zero(), None);
+    }
+
+    /// Constrains `next > prev`, as for a counter which must increase between
+    /// two states of a circuit.
+    ///
+    /// Both `prev` and `next` are constrained to be smaller than
+    /// `2^num_bits`.
+    pub fn assert_strictly_monotonic(
+        &mut self,
+        prev: Variable,
+        next: Variable,
+        num_bits: usize,
+    ) {
+        self.multi_range(&[prev, next], &[num_bits, num_bits]);
+        let increasing = self.less_than(prev, next, num_bits);
+        self.constrain_to_constant(increasing, F::one(), None);
+    }
+
+    /// Returns `min(max(a, lo), hi)`, which always lies between `lo` and
+    /// `hi`.
+    ///
+    /// # Note
+    /// `a` must have previously been constrained to be smaller than
+    /// `2^num_bits`, see [`StandardComposer::less_than`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo > hi` or if `hi` doesn't fit in `num_bits` bits.
+    pub fn clamp(
+        &mut self,
+        a: Variable,
+        lo: F,
+        hi: F,
+        num_bits: usize,
+    ) -> Variable {
+        assert!(
+            lo.into_repr() <= hi.into_repr(),
+            "The lower bound of a clamp must not exceed its upper bound"
+        );
+        assert!(
+            hi.into_repr().num_bits() as usize <= num_bits,
+            "The upper bound of a clamp must fit in num_bits bits"
+        );
+        let lo = self.add_witness_to_circuit_description(lo);
+        let hi = self.add_witness_to_circuit_description(hi);
+        let above_lo = self.max(a, lo, num_bits);
+        self.min(above_lo, hi, num_bits)
+    }
+
+    /// Returns a boolean [`Variable`] which is `1` if the byte string `a` is
+    /// lexicographically smaller than `b` and `0` otherwise, as needed to
+    /// order the keys of sorted Merkle trees.
+    ///
+    /// The result is folded from the last byte to the first as
+    /// `lt_i + eq_i * acc`, where `lt_i` and `eq_i` compare the `i`-th bytes
+    /// with [`StandardComposer::less_than`] and
+    /// [`StandardComposer::is_eq_with_output`], so that the first differing
+    /// byte decides. Equal strings aren't smaller than each other.
+    ///
+    /// # Note
+    /// Every byte of `a` and `b` must have previously been constrained to be
+    /// a byte, e.g. with [`StandardComposer::assert_byte`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths.
+    pub fn bytes_less_than(
+        &mut self,
+        a: &[Variable],
+        b: &[Variable],
+    ) -> Variable {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "Only byte strings of the same length can be compared"
+        );
+        a.iter()
+            .zip(b)
+            .rev()
+            .fold(self.zero_var, |acc, (a_byte, b_byte)| {
+                let lt = self.less_than(*a_byte, *b_byte, 8);
+                let eq = self.is_eq_with_output(*a_byte, *b_byte);
+                self.arithmetic_gate(|gate| {
+                    gate.witness(eq, acc, None)
+                        .mul(F::one())
+                        .fan_in_3(F::one(), lt)
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test, commitment::HomomorphicCommitment,
+        constraint_system::helper::*, proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    fn test_less_than<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for (a, b, expected) in
+                    [(3u64, 5u64, 1u64), (5, 3, 0), (4, 4, 0), (0, 255, 1)]
+                {
+                    let a = composer.add_input(F::from(a));
+                    let b = composer.add_input(F::from(b));
+                    composer.assert_byte(a);
+                    composer.assert_byte(b);
+                    let lt = composer.less_than(a, b, 8);
+                    composer.constrain_to_constant(lt, F::from(expected), None);
+                }
+            },
+            128,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(5u64));
+                let b = composer.add_input(F::from(3u64));
+                let lt = composer.less_than(a, b, 8);
+                composer.constrain_to_constant(lt, F::one(), None);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_min_max<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for (a, b, min, max) in
+                    [(3u64, 5u64, 3u64, 5u64), (5, 3, 3, 5), (7, 7, 7, 7)]
+                {
+                    let a = composer.add_input(F::from(a));
+                    let b = composer.add_input(F::from(b));
+                    composer.range_gate(a, 16);
+                    composer.range_gate(b, 16);
+                    let min_var = composer.min(a, b, 16);
+                    let max_var = composer.max(a, b, 16);
+                    composer.constrain_to_constant(min_var, F::from(min), None);
+                    composer.constrain_to_constant(max_var, F::from(max), None);
+                }
+            },
+            256,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(3u64));
+                let b = composer.add_input(F::from(5u64));
+                let min = composer.min(a, b, 16);
+                composer.constrain_to_constant(min, F::from(5u64), None);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_clamp<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (lo, hi) = (F::from(10u64), F::from(20u64));
+                // Below, inside, at the bounds of and above the range.
+                for (a, expected) in
+                    [(3u64, 10u64), (15, 15), (10, 10), (20, 20), (255, 20)]
+                {
+                    let a = composer.add_input(F::from(a));
+                    composer.range_gate(a, 8);
+                    let clamped = composer.clamp(a, lo, hi, 8);
+                    composer.constrain_to_constant(
+                        clamped,
+                        F::from(expected),
+                        None,
+                    );
+                    // The output is always within the bounds.
+                    let lo_var = composer.add_input(lo);
+                    let hi_var = composer.add_input(hi);
+                    let below = composer.less_than(clamped, lo_var, 8);
+                    let above = composer.less_than(hi_var, clamped, 8);
+                    composer.constrain_to_constant(below, F::zero(), None);
+                    composer.constrain_to_constant(above, F::zero(), None);
+                }
+            },
+            512,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(30u64));
+                composer.range_gate(a, 8);
+                let clamped =
+                    composer.clamp(a, F::from(10u64), F::from(20u64), 8);
+                composer.constrain_to_constant(clamped, F::from(30u64), None);
+            },
+            128,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_assert_monotonic<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for (prev, next) in [(3u64, 5u64), (7, 7), (0, 255)] {
+                    let prev = composer.add_input(F::from(prev));
+                    let next = composer.add_input(F::from(next));
+                    composer.assert_monotonic(prev, next, 8);
+                }
+                let prev = composer.add_input(F::from(3u64));
+                let next = composer.add_input(F::from(4u64));
+                composer.assert_strictly_monotonic(prev, next, 8);
+            },
+            256,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the counter decreases
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let prev = composer.add_input(F::from(5u64));
+                let next = composer.add_input(F::from(4u64));
+                composer.assert_monotonic(prev, next, 8);
+            },
+            64,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the counter doesn't increase
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let prev = composer.add_input(F::from(7u64));
+                let next = composer.add_input(F::from(7u64));
+                composer.assert_strictly_monotonic(prev, next, 8);
+            },
+            64,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the next value is out of range
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let prev = composer.add_input(F::from(7u64));
+                let next = composer.add_input(F::from(256u64));
+                composer.assert_monotonic(prev, next, 8);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_sign<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                // -3, 5, 0, 127 and -128 as 8-bit integers.
+                for (value, sign) in
+                    [(253u64, 1u64), (5, 0), (0, 0), (127, 0), (128, 1)]
+                {
+                    let a = composer.add_input(F::from(value));
+                    let a_sign = composer.sign(a, 8);
+                    composer.constrain_to_constant(a_sign, F::from(sign), None);
+                }
+                let a = composer.add_input(F::one());
+                let a_sign = composer.sign(a, 1);
+                composer.constrain_to_constant(a_sign, F::one(), None);
+            },
+            128,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the value doesn't fit in 8 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(256u64));
+                composer.sign(a, 8);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_clamp_empty_range<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::from(5u64));
+        composer.clamp(a, F::from(20u64), F::from(10u64), 8);
+    }
+
+    fn test_bytes_less_than<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for (a, b, expected) in [
+                    (&b"abc"[..], &b"abd"[..], 1u64),
+                    (b"abd", b"abc", 0),
+                    (b"abc", b"abc", 0),
+                    (b"abz", b"aca", 1),
+                    (b"", b"", 0),
+                ] {
+                    let mut bytes = |s: &[u8]| {
+                        s.iter()
+                            .map(|byte| {
+                                let byte = composer.add_input(F::from(*byte));
+                                composer.assert_byte(byte);
+                                byte
+                            })
+                            .collect::<Vec<_>>()
+                    };
+                    let (a, b) = (bytes(a), bytes(b));
+                    let lt = composer.bytes_less_than(&a, &b);
+                    composer.constrain_to_constant(lt, F::from(expected), None);
+                }
+            },
+            512,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as "abc" isn't smaller than itself
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = b"abc".map(|byte| composer.add_input(F::from(byte)));
+                let lt = composer.bytes_less_than(&a, &a);
+                composer.constrain_to_constant(lt, F::one(), None);
+            },
+            512,
+        );
+        assert!(res.is_err());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_bytes_less_than_different_lengths<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::one());
+        composer.bytes_less_than(&[a, a], &[a]);
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [
+            test_less_than,
+            test_min_max,
+            test_clamp,
+            test_assert_monotonic,
+            test_sign,
+            test_bytes_less_than
+        ],
+        [test_clamp_empty_range, test_bytes_less_than_different_lengths] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [
+            test_less_than,
+            test_min_max,
+            test_clamp,
+            test_assert_monotonic,
+            test_sign,
+            test_bytes_less_than
+        ],
+        [test_clamp_empty_range, test_bytes_less_than_different_lengths] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
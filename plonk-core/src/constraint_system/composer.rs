@@ -15,7 +15,9 @@
 //! ECC op. gates, Range checks, Logical gates (Bitwise ops) etc.
 
 use crate::{
-    constraint_system::Variable, error::Error, permutation::Permutation,
+    constraint_system::{Variable, WireData},
+    error::Error,
+    permutation::Permutation,
 };
 
 use crate::lookup::LookupTable;
@@ -95,6 +97,8 @@ where
     pub(crate) q_variable_group_add: Vec<F>,
     /// Lookup gate selector
     pub(crate) q_lookup: Vec<F>,
+    /// Multiset equality selector
+    pub(crate) q_multiset: Vec<F>,
 
     /// Sparse representation of the Public Inputs linking the positions of the
     /// non-zero ones to it's actual values.
@@ -123,6 +127,23 @@ where
     /// These are the actual variable values.
     pub(crate) variables: HashMap<Variable, F>,
 
+    /// Number of variables allocated by the composer itself, namely the zero
+    /// variable and the blinding factors, which come before every user
+    /// variable.
+    pub(crate) num_reserved_vars: usize,
+
+    /// Number of gates of the circuit once it has been
+    /// [finalized](StandardComposer::finalize).
+    pub(crate) finalized_size: Option<usize>,
+
+    /// Number of rows of the lookup table once the circuit has been
+    /// [finalized](StandardComposer::finalize).
+    pub(crate) finalized_table_size: usize,
+
+    /// Number of gates added by [`StandardComposer::finalize`] to pad the
+    /// circuit to a power of two.
+    pub(crate) num_padding_gates: usize,
+
     /// Permutation argument.
     pub(crate) perm: Permutation,
 
@@ -145,6 +166,16 @@ where
         self.total_size().next_power_of_two()
     }
 
+    /// Returns the fraction of the padded domain used by the gates of the
+    /// circuit, excluding the padding added when finalizing it.
+    ///
+    /// A low utilization means that the circuit barely crosses a power of
+    /// two and that trimming a few gates would halve the size of the SRS
+    /// needed to prove it.
+    pub fn utilization(&self) -> f64 {
+        (self.n - self.num_padding_gates) as f64 / self.circuit_bound() as f64
+    }
+
     /// Returns a reference to the [`PublicInputs`] stored in the
     /// [`StandardComposer`].
     pub fn get_pi(&self) -> &PublicInputs<F> {
@@ -225,6 +256,7 @@ where
             q_fixed_group_add: Vec::with_capacity(expected_size),
             q_variable_group_add: Vec::with_capacity(expected_size),
             q_lookup: Vec::with_capacity(expected_size),
+            q_multiset: Vec::with_capacity(expected_size),
             public_inputs: PublicInputs::new(),
             intended_pi_pos: Vec::new(),
             w_l: Vec::with_capacity(expected_size),
@@ -234,6 +266,10 @@ where
             lookup_table: LookupTable::new(),
             zero_var: Variable(0),
             variables: HashMap::with_capacity(expected_size),
+            num_reserved_vars: 0,
+            finalized_size: None,
+            finalized_table_size: 0,
+            num_padding_gates: 0,
             perm: Permutation::new(),
             __: PhantomData::<P>,
         };
@@ -244,10 +280,49 @@ where
 
         // Add dummy constraints
         composer.add_blinding_factors(&mut rand_core::OsRng);
+        composer.num_reserved_vars = composer.variables.len();
 
         composer
     }
 
+    /// Releases the capacity of the selector, wire and variable storage of
+    /// the composer that is left over from growing it, which is worth doing
+    /// on large circuits before keeping them alive to derive the keys.
+    pub fn shrink_to_fit(&mut self) {
+        for selector in [
+            &mut self.q_m,
+            &mut self.q_l,
+            &mut self.q_r,
+            &mut self.q_o,
+            &mut self.q_4,
+            &mut self.q_c,
+            &mut self.q_hl,
+            &mut self.q_hr,
+            &mut self.q_h4,
+            &mut self.q_arith,
+            &mut self.q_range,
+            &mut self.q_logic,
+            &mut self.q_fixed_group_add,
+            &mut self.q_variable_group_add,
+            &mut self.q_lookup,
+            &mut self.q_multiset,
+        ] {
+            selector.shrink_to_fit();
+        }
+        for wire in [&mut self.w_l, &mut self.w_r, &mut self.w_o, &mut self.w_4]
+        {
+            wire.shrink_to_fit();
+        }
+        self.intended_pi_pos.shrink_to_fit();
+        self.lookup_table.0.shrink_to_fit();
+        self.variables.shrink_to_fit();
+        self.perm.variable_map.shrink_to_fit();
+        self.perm
+            .variable_map
+            .values_mut()
+            .for_each(Vec::shrink_to_fit);
+    }
+
     /// Witness representation of zero of the first variable of any circuit
     pub fn zero_var(&self) -> Variable {
         self.zero_var
@@ -308,6 +383,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
 
         // add high degree selectors
         self.q_hl.push(F::zero());
@@ -503,6 +579,32 @@ where
         f_x
     }
 
+    /// Returns `vars` reordered by bit-reversed index, so that the `i`-th
+    /// output is `vars[rev(i)]` where `rev` reverses the `log2(vars.len())`
+    /// low bits of `i`.
+    ///
+    /// This is pure rewiring: the outputs are the input [`Variable`]s
+    /// themselves, so every later use is tied to the inputs by the copy
+    /// constraints and no gate is added.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `vars` isn't a power of two.
+    pub fn bit_reverse(&mut self, vars: &[Variable]) -> Vec<Variable> {
+        assert!(
+            vars.len().is_power_of_two(),
+            "Cannot bit-reverse {} variables, the length must be a power of two",
+            vars.len()
+        );
+        let log_len = vars.len().trailing_zeros();
+        (0..vars.len())
+            .map(|i| match log_len {
+                0 => vars[0],
+                _ => vars[i.reverse_bits() >> (usize::BITS - log_len)],
+            })
+            .collect()
+    }
+
     /// This function adds two dummy gates to the circuit
     /// description which are guaranteed to always satisfy the gate equation.
     /// This function is only used in benchmarking
@@ -524,6 +626,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::one());
+        self.q_multiset.push(F::zero());
         // add high degree selectors
         self.q_hl.push(F::zero());
         self.q_hr.push(F::zero());
@@ -553,6 +656,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::one());
+        self.q_multiset.push(F::zero());
         // add high degree selectors
         self.q_hl.push(F::zero());
         self.q_hr.push(F::zero());
@@ -632,6 +736,7 @@ where
             self.q_fixed_group_add.push(F::zero());
             self.q_variable_group_add.push(F::zero());
             self.q_lookup.push(F::zero());
+            self.q_multiset.push(F::zero());
             // add high degree selectors
             self.q_hl.push(F::zero());
             self.q_hr.push(F::zero());
@@ -664,6 +769,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
         // add high degree selectors
         self.q_hl.push(F::zero());
         self.q_hr.push(F::zero());
@@ -872,6 +978,92 @@ where
             .copied()
             .expect("the variable does not exist")
     }
+
+    /// Returns the indices of the gates which have `var` on any of their
+    /// wires, in increasing order and without repetitions. The result is
+    /// empty if `var` isn't on any wire, including when it wasn't created by
+    /// this composer.
+    ///
+    /// This is meant to help understanding how a [`Variable`] flows through
+    /// a circuit, e.g. when debugging a gadget.
+    pub fn gates_using(&self, var: Variable) -> Vec<usize> {
+        let mut gates = self
+            .perm
+            .variable_map
+            .get(&var)
+            .map(|wires| {
+                wires
+                    .iter()
+                    .map(|wire| match wire {
+                        WireData::Left(gate)
+                        | WireData::Right(gate)
+                        | WireData::Output(gate)
+                        | WireData::Fourth(gate) => *gate,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        gates.sort_unstable();
+        gates.dedup();
+        gates
+    }
+
+    /// Returns the values of every [`Variable`] allocated after the ones
+    /// reserved by the composer, in allocation order.
+    ///
+    /// This is the layout expected by
+    /// [`assign_witness`](StandardComposer::assign_witness).
+    pub fn witness(&self) -> Vec<F> {
+        (self.num_reserved_vars..self.variables.len())
+            .map(|i| self.variables[&Variable(i)])
+            .collect()
+    }
+
+    /// Returns the number of [`Variable`]s of the circuit, including the ones
+    /// reserved by the composer.
+    pub fn num_variables(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// Returns the values of every [`Variable`] of the circuit, indexed by
+    /// [`Variable::index`].
+    pub(crate) fn assignment(&self) -> Vec<F> {
+        (0..self.variables.len())
+            .map(|i| self.variables[&Variable(i)])
+            .collect()
+    }
+
+    /// Replaces the values of every [`Variable`] allocated after the ones
+    /// reserved by the composer with `values`, in allocation order, and
+    /// samples fresh blinding factors.
+    ///
+    /// This allows emitting the gates of a circuit once and proving it for
+    /// many witnesses, with a frontend computing the witness on its own. The
+    /// public inputs are part of the circuit description and are left
+    /// untouched.
+    ///
+    /// Returns [`Error::InvalidWitnessLength`] if `values` doesn't hold
+    /// exactly one value per user variable.
+    pub fn assign_witness(&mut self, values: &[F]) -> Result<(), Error> {
+        let expected = self.variables.len() - self.num_reserved_vars;
+        if values.len() != expected {
+            return Err(Error::InvalidWitnessLength {
+                expected,
+                found: values.len(),
+            });
+        }
+        // The zero variable keeps its value, the blinding factors are
+        // resampled so that no two proofs share them.
+        for i in 1..self.num_reserved_vars {
+            self.variables
+                .insert(Variable(i), F::rand(&mut rand_core::OsRng));
+        }
+        for (i, value) in values.iter().enumerate() {
+            self.variables
+                .insert(Variable(self.num_reserved_vars + i), *value);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -879,9 +1071,8 @@ mod test {
     use super::*;
     use crate::{
         batch_test, batch_test_field_params,
-        commitment::HomomorphicCommitment,
         constraint_system::helper::*,
-        proof_system::{Prover, Verifier},
+        proof_system::{Prover, ProverCommitment, Verifier},
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -907,7 +1098,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // NOTE: Does nothing except add the dummy constraints.
         let res =
@@ -919,7 +1110,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // Check that it gives true on zero input:
         let res = gadget_tester::<F, P, PC>(
@@ -948,7 +1139,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // Check that it gives true on equal inputs:
         let res = gadget_tester::<F, P, PC>(
@@ -983,7 +1174,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -1006,12 +1197,55 @@ mod test {
         assert!(res.is_ok(), "{:?}", res.err().unwrap());
     }
 
+    fn test_bit_reverse<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let vars = (0..8u64)
+                    .map(|i| composer.add_input(F::from(i)))
+                    .collect::<Vec<_>>();
+                let reversed = composer.bit_reverse(&vars);
+                for (out, expected) in
+                    reversed.into_iter().zip([0u64, 4, 2, 6, 1, 5, 3, 7])
+                {
+                    assert_eq!(composer.value_of_var(out), F::from(expected));
+                    composer.constrain_to_constant(
+                        out,
+                        F::from(expected),
+                        None,
+                    );
+                }
+
+                let one = composer.add_input(F::one());
+                assert_eq!(composer.bit_reverse(&[one]), vec![one]);
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_bit_reverse_non_power_of_two<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let mut composer: StandardComposer<F, P> = StandardComposer::new();
+        let zero = composer.zero_var();
+        composer.bit_reverse(&[zero; 6]);
+    }
+
     // FIXME: Move this to integration tests
     fn test_multiple_proofs<F, P, PC>()
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let u_params = PC::setup(2 * 30, None, &mut OsRng).unwrap();
 
@@ -1054,10 +1288,254 @@ mod test {
         }
     }
 
+    fn test_assign_witness<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        fn product_circuit<F, P>(
+            composer: &mut StandardComposer<F, P>,
+            values: [u64; 3],
+        ) where
+            F: PrimeField,
+            P: TEModelParameters<BaseField = F>,
+        {
+            let [a, b, c] = values.map(|v| composer.add_input(F::from(v)));
+            composer.arithmetic_gate(|gate| {
+                gate.witness(a, b, Some(c)).mul(F::one())
+            });
+        }
+
+        let u_params = PC::setup(2 * 30, None, &mut OsRng).unwrap();
+        let (ck, vk) = PC::trim(&u_params, 2 * 20, 0, None).unwrap();
+
+        // The gates are emitted once, the witnesses are assigned afterwards.
+        let mut prover: Prover<F, P, PC> = Prover::new(b"assign_witness");
+        product_circuit(prover.mut_cs(), [0, 0, 0]);
+        prover.preprocess(&ck).unwrap();
+        let pk = prover.prover_key.clone().unwrap();
+        let public_inputs = prover.cs.get_pi().clone();
+
+        let mut proofs = Vec::new();
+        for witness in [[2u64, 3, 6], [5, 7, 35]] {
+            let witness = witness.map(F::from);
+            prover.mut_cs().assign_witness(&witness).unwrap();
+            assert_eq!(prover.cs.witness(), witness);
+            proofs.push(
+                prover
+                    .prove_with_preprocessed(&ck, &pk, PhantomData::<PC>)
+                    .unwrap(),
+            );
+        }
+
+        assert!(matches!(
+            prover.mut_cs().assign_witness(&[F::one()]),
+            Err(Error::InvalidWitnessLength {
+                expected: 3,
+                found: 1
+            })
+        ));
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"assign_witness");
+        product_circuit(verifier.mut_cs(), [0, 0, 0]);
+        verifier.preprocess(&ck).unwrap();
+        for proof in proofs {
+            assert!(verifier.verify(&proof, &vk, &public_inputs).is_ok());
+        }
+
+        // A witness which doesn't satisfy the circuit still fails.
+        prover
+            .mut_cs()
+            .assign_witness(&[2u64, 3, 7].map(F::from))
+            .unwrap();
+        let proof = prover
+            .prove_with_preprocessed(&ck, &pk, PhantomData::<PC>)
+            .unwrap();
+        assert!(verifier.verify(&proof, &vk, &public_inputs).is_err());
+    }
+
+    fn test_prove_with_assignment<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        /// Constrains `a * b` to the public input, returning `a` and `b`.
+        fn product_circuit<F, P>(
+            composer: &mut StandardComposer<F, P>,
+        ) -> (Variable, Variable)
+        where
+            F: PrimeField,
+            P: TEModelParameters<BaseField = F>,
+        {
+            let zero = composer.zero_var();
+            let a = composer.add_input(F::zero());
+            let b = composer.add_input(F::zero());
+            composer.arithmetic_gate(|gate| {
+                gate.witness(a, b, Some(zero)).mul(F::one()).pi(F::zero())
+            });
+            (a, b)
+        }
+
+        let u_params = PC::setup(2 * 30, None, &mut OsRng).unwrap();
+        let (ck, vk) = PC::trim(&u_params, 2 * 20, 0, None).unwrap();
+
+        let mut prover: Prover<F, P, PC> = Prover::new(b"assignment");
+        let (a, b) = product_circuit(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let pk = prover.prover_key.clone().unwrap();
+
+        // The assignment is computed without the composer, only knowing the
+        // indices of the variables.
+        let mut assignment = vec![F::zero(); prover.cs.num_variables()];
+        assignment[a.index()] = F::from(3u64);
+        assignment[b.index()] = F::from(5u64);
+        let pi = [-F::from(15u64)];
+        let proof = prover
+            .prove_with_assignment(&ck, &pk, &assignment, &pi)
+            .unwrap();
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"assignment");
+        product_circuit(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        let pi_pos = prover.cs.intended_pi_pos.clone();
+        let public_inputs = PublicInputs::from_val_pos(&pi_pos, &pi).unwrap();
+        assert!(verifier.verify(&proof, &vk, &public_inputs).is_ok());
+
+        // The same proof isn't valid for other public inputs.
+        let other_public_inputs =
+            PublicInputs::from_val_pos(&pi_pos, &[-F::from(16u64)]).unwrap();
+        assert!(verifier.verify(&proof, &vk, &other_public_inputs).is_err());
+
+        // An assignment which doesn't satisfy the circuit fails.
+        let proof = prover
+            .prove_with_assignment(&ck, &pk, &assignment, &[-F::from(16u64)])
+            .unwrap();
+        let public_inputs =
+            PublicInputs::from_val_pos(&pi_pos, &[-F::from(16u64)]).unwrap();
+        assert!(verifier.verify(&proof, &vk, &public_inputs).is_err());
+
+        assert!(matches!(
+            prover.prove_with_assignment(&ck, &pk, &assignment[1..], &pi),
+            Err(Error::InvalidWitnessLength { .. })
+        ));
+        assert!(matches!(
+            prover.prove_with_assignment(&ck, &pk, &assignment, &[]),
+            Err(Error::InvalidPublicInputLength {
+                expected: 1,
+                found: 0
+            })
+        ));
+    }
+
+    fn test_gates_using<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let zero = composer.zero_var();
+        let a = composer.add_input(F::from(3u64));
+        let b = composer.add_input(F::from(5u64));
+        assert!(composer.gates_using(a).is_empty());
+        let unknown = Variable(composer.num_variables());
+        assert!(composer.gates_using(unknown).is_empty());
+
+        let first = composer.n;
+        let sum = composer.arithmetic_gate(|gate| {
+            gate.witness(a, b, None).add(F::one(), F::one())
+        });
+        composer.arithmetic_gate(|gate| {
+            gate.witness(b, sum, None).add(F::one(), F::one())
+        });
+        // `a` on two wires of the same gate counts once.
+        let third = composer.n;
+        composer.arithmetic_gate(|gate| {
+            gate.witness(a, a, Some(zero)).add(F::one(), -F::one())
+        });
+        let fourth = composer.n;
+        composer.arithmetic_gate(|gate| {
+            gate.witness(sum, b, None)
+                .mul(F::one())
+                .fan_in_3(F::one(), a)
+        });
+
+        assert_eq!(composer.gates_using(a), [first, third, fourth]);
+        assert_eq!(composer.gates_using(sum), [first, first + 1, fourth]);
+        assert!(composer.gates_using(zero).contains(&third));
+    }
+
+    fn test_shrink_to_fit<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let mut composer = StandardComposer::<F, P>::with_expected_size(1024);
+        let a = composer.add_input(F::from(3u64));
+        for _ in 0..10 {
+            composer.arithmetic_gate(|gate| {
+                gate.witness(a, a, None).add(F::one(), F::one())
+            });
+        }
+        let n = composer.n;
+        let num_variables = composer.num_variables();
+        assert!(composer.q_m.capacity() > composer.q_m.len());
+
+        composer.shrink_to_fit();
+        for selector in [
+            &composer.q_m,
+            &composer.q_l,
+            &composer.q_r,
+            &composer.q_o,
+            &composer.q_4,
+            &composer.q_c,
+            &composer.q_hl,
+            &composer.q_hr,
+            &composer.q_h4,
+            &composer.q_arith,
+            &composer.q_range,
+            &composer.q_logic,
+            &composer.q_fixed_group_add,
+            &composer.q_variable_group_add,
+            &composer.q_lookup,
+            &composer.q_multiset,
+        ] {
+            assert_eq!(selector.capacity(), selector.len());
+        }
+        assert_eq!(composer.w_l.capacity(), composer.w_l.len());
+
+        // The circuit itself is left untouched.
+        assert_eq!(composer.n, n);
+        assert_eq!(composer.num_variables(), num_variables);
+        composer.check_circuit_satisfied();
+    }
+
+    fn test_utilization<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        for _ in 0..48 {
+            composer.add_dummy_constraints();
+        }
+        assert_eq!(composer.n, 100);
+        assert!((composer.utilization() - 100.0 / 128.0).abs() < 1e-9);
+
+        // Padding gates don't count as used.
+        composer.finalize().unwrap();
+        assert_eq!(composer.n, 128);
+        assert!((composer.utilization() - 100.0 / 128.0).abs() < 1e-9);
+    }
+
     // Tests for Bls12_381
     batch_test_field_params!(
         [
-            test_initial_circuit_size
+            test_initial_circuit_size,
+            test_utilization,
+            test_gates_using,
+            test_shrink_to_fit
         ],
         [] => (
             Bls12_381,
@@ -1069,7 +1547,10 @@ mod test {
     // Tests for Bls12_377
     batch_test_field_params!(
         [
-            test_initial_circuit_size
+            test_initial_circuit_size,
+            test_utilization,
+            test_gates_using,
+            test_shrink_to_fit
         ],
         [] => (
             Bls12_377,
@@ -1084,9 +1565,12 @@ mod test {
             test_correct_is_zero_with_output,
             test_correct_is_eq_with_output,
             test_conditional_select,
-            test_multiple_proofs
+            test_multiple_proofs,
+            test_assign_witness,
+            test_prove_with_assignment,
+            test_bit_reverse
         ],
-        [] => (
+        [test_bit_reverse_non_power_of_two] => (
             Bls12_381,
             ark_ed_on_bls12_381::EdwardsParameters
         )
@@ -1099,9 +1583,12 @@ mod test {
             test_correct_is_zero_with_output,
             test_correct_is_eq_with_output,
             test_conditional_select,
-            test_multiple_proofs
+            test_multiple_proofs,
+            test_assign_witness,
+            test_prove_with_assignment,
+            test_bit_reverse
         ],
-        [] => (
+        [test_bit_reverse_non_power_of_two] => (
             Bls12_377,
             ark_ed_on_bls12_377::EdwardsParameters
         )
@@ -0,0 +1,256 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Gate Cost Reporting
+//!
+//! The [`ConstraintCounter`] wraps a [`StandardComposer`] and records how many
+//! gates every named gadget call adds to it, so that gadget authors can see
+//! where the gates of a circuit go.
+
+use crate::constraint_system::StandardComposer;
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// Wrapper around a [`StandardComposer`] recording the number of gates added
+/// inside every [`GadgetScope`].
+///
+/// The counter only looks at the gate count of the composer, so the circuit
+/// built through it is exactly the one that would have been built without it
+/// and can be recovered with [`ConstraintCounter::into_inner`] to be proven.
+#[derive(derivative::Derivative)]
+#[derivative(Debug, Default(bound = ""))]
+pub struct ConstraintCounter<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Wrapped composer.
+    composer: StandardComposer<F, P>,
+
+    /// Name and gate count of every scope, in the order they were closed.
+    costs: Vec<(&'static str, usize)>,
+}
+
+impl<F, P> ConstraintCounter<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Wraps `composer` into a new counter.
+    pub fn new(composer: StandardComposer<F, P>) -> Self {
+        Self {
+            composer,
+            costs: Vec::new(),
+        }
+    }
+
+    /// Opens a scope named `name`, which records the gates added through it
+    /// when dropped.
+    pub fn scope(&mut self, name: &'static str) -> GadgetScope<'_, F, P> {
+        let start = self.composer.n;
+        GadgetScope {
+            counter: self,
+            name,
+            start,
+        }
+    }
+
+    /// Returns the name and gate count of every closed scope, in the order
+    /// they were closed.
+    pub fn report(&self) -> &[(&'static str, usize)] {
+        &self.costs
+    }
+
+    /// Returns the total number of gates added by the scopes named `name`.
+    pub fn gates(&self, name: &str) -> usize {
+        self.costs
+            .iter()
+            .filter(|(scope, _)| *scope == name)
+            .map(|(_, gates)| gates)
+            .sum()
+    }
+
+    /// Returns the wrapped composer.
+    pub fn into_inner(self) -> StandardComposer<F, P> {
+        self.composer
+    }
+}
+
+impl<F, P> Deref for ConstraintCounter<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    type Target = StandardComposer<F, P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.composer
+    }
+}
+
+impl<F, P> DerefMut for ConstraintCounter<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.composer
+    }
+}
+
+impl<F, P> fmt::Display for ConstraintCounter<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, gates) in &self.costs {
+            writeln!(f, "{}: {} gates", name, gates)?;
+        }
+        write!(f, "total: {} gates", self.composer.n)
+    }
+}
+
+/// Scope guard of a [`ConstraintCounter`] giving access to the wrapped
+/// [`StandardComposer`] and recording the gates added through it when
+/// dropped.
+pub struct GadgetScope<'a, F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Counter the scope was opened on.
+    counter: &'a mut ConstraintCounter<F, P>,
+
+    /// Name of the scope.
+    name: &'static str,
+
+    /// Gate count of the composer when the scope was opened.
+    start: usize,
+}
+
+impl<'a, F, P> Deref for GadgetScope<'a, F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    type Target = StandardComposer<F, P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.counter.composer
+    }
+}
+
+impl<'a, F, P> DerefMut for GadgetScope<'a, F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.counter.composer
+    }
+}
+
+impl<'a, F, P> Drop for GadgetScope<'a, F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    fn drop(&mut self) {
+        let gates = self.counter.composer.n - self.start;
+        self.counter.costs.push((self.name, gates));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test, constraint_system::helper::*,
+        proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    fn test_constraint_counter<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // Reference gate counts measured on a bare composer.
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::from(5u64));
+        let b = composer.add_input(F::from(9u64));
+        let start = composer.n;
+        composer.range_gate(a, 8);
+        let range_gates = composer.n - start;
+        let start = composer.n;
+        composer.xor_gate(a, b, 8);
+        let xor_gates = composer.n - start;
+
+        let mut counter =
+            ConstraintCounter::new(StandardComposer::<F, P>::new());
+        let a = counter.add_input(F::from(5u64));
+        let b = counter.add_input(F::from(9u64));
+        counter.scope("range_gate").range_gate(a, 8);
+        counter.scope("xor_gate").xor_gate(a, b, 8);
+        {
+            let mut scope = counter.scope("range_gate");
+            scope.range_gate(b, 8);
+        }
+        assert_eq!(
+            counter.report(),
+            [
+                ("range_gate", range_gates),
+                ("xor_gate", xor_gates),
+                ("range_gate", range_gates)
+            ]
+        );
+        assert_eq!(counter.gates("range_gate"), 2 * range_gates);
+        assert_eq!(counter.gates("xor_gate"), xor_gates);
+        assert_eq!(counter.gates("and_gate"), 0);
+        assert!(counter
+            .to_string()
+            .contains(&format!("xor_gate: {} gates", xor_gates)));
+
+        // Counting doesn't change the circuit.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let mut counter =
+                    ConstraintCounter::new(core::mem::take(composer));
+                let a = counter.add_input(F::from(5u64));
+                let b = counter.add_input(F::from(9u64));
+                counter.scope("range_gate").range_gate(a, 8);
+                let c = counter.scope("xor_gate").xor_gate(a, b, 8);
+                counter.constrain_to_constant(c, F::from(12u64), None);
+                *composer = counter.into_inner();
+            },
+            200,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_constraint_counter],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_constraint_counter],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
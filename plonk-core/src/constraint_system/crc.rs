@@ -0,0 +1,178 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! CRC Gates
+
+use crate::{
+    constraint_system::{StandardComposer, Variable},
+    lookup::lookup_table::{crc32_table_entry, CRC32_TABLE_TAG},
+};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Returns the CRC-32 of `bytes`, as used by Ethernet, zlib and PNG, for
+    /// circuits checking the integrity of some data.
+    ///
+    /// Every byte is processed with the byte-wise algorithm
+    /// `crc' = T[(crc ^ byte) & 0xff] ^ (crc >> 8)`: the low byte of the
+    /// running CRC is split off, XORed with the input byte and the remainder
+    /// of its division by the CRC polynomial is looked up in a table holding
+    /// `T`. The table is added to the lookup table of the composer if it
+    /// isn't already there, which pads the circuit to at least 256 rows.
+    ///
+    /// The inputs of the XOR gates are constrained to be equal to the values
+    /// accumulated by the gates, so every input byte is constrained to be
+    /// inside of the range \[0, 255\] and doesn't need to be range-checked
+    /// beforehand.
+    pub fn crc32(&mut self, bytes: &[Variable]) -> Variable {
+        let tag = F::from(CRC32_TABLE_TAG);
+        if !self.lookup_table.0.iter().any(|row| row[3] == tag) {
+            self.lookup_table.insert_crc32_rows();
+        }
+        let tag = self.add_witness_to_circuit_description(tag);
+        let zero = self.zero_var;
+        let all_ones = F::from(u64::from(u32::MAX));
+
+        let mut crc = self.add_witness_to_circuit_description(all_ones);
+        for byte in bytes {
+            let value = self.value_of_var(crc).into_repr().as_ref()[0];
+            let low = self.add_input(F::from(value & 0xff));
+            let high = self.add_input(F::from(value >> 8));
+            // crc = low + 256 * high, where `low` is a byte and `high` has 32
+            // bits, which only holds for `high < 2^24` as `crc` has 32 bits.
+            self.arithmetic_gate(|gate| {
+                gate.witness(low, high, Some(crc))
+                    .add(F::one(), F::from(256u64))
+            });
+
+            let index = self.checked_xor(low, *byte, 8);
+            let index_value = self.value_of_var(index).into_repr().as_ref()[0];
+            let entry = self.add_input(F::from(crc32_table_entry(index_value)));
+            self.lookup_gate(index, zero, entry, Some(tag), None);
+            crc = self.checked_xor(entry, high, 32);
+        }
+
+        // The final XOR with 0xffffffff is the complement of the 32 bits.
+        self.arithmetic_gate(|gate| {
+            gate.witness(crc, zero, None)
+                .add(-F::one(), F::zero())
+                .constant(all_ones)
+        })
+    }
+
+    /// Returns [`StandardComposer::xor_gate`] of `a` and `b`, additionally
+    /// constraining them to be equal to the values accumulated from the
+    /// quads of the gate, so that both have at most `num_bits` bits.
+    fn checked_xor(
+        &mut self,
+        a: Variable,
+        b: Variable,
+        num_bits: usize,
+    ) -> Variable {
+        let out = self.xor_gate(a, b, num_bits);
+        let last = self.n - 1;
+        let (left, right) = (self.w_l[last], self.w_r[last]);
+        self.assert_equal(left, a);
+        self.assert_equal(right, b);
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test, constraint_system::helper::*, lookup::LookupTable,
+        proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    /// Bit-wise CRC-32 of `bytes`, as a reference.
+    fn reference_crc32(bytes: &[u8]) -> u32 {
+        !bytes.iter().fold(u32::MAX, |crc, byte| {
+            (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+                (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+            })
+        })
+    }
+
+    fn test_crc32<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        assert_eq!(reference_crc32(b"123456789"), 0xcbf4_3926);
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for data in [&b"123456789"[..], b"plonk", b""] {
+                    let bytes = data
+                        .iter()
+                        .map(|byte| composer.add_input(F::from(*byte)))
+                        .collect::<Vec<_>>();
+                    let crc = composer.crc32(&bytes);
+                    composer.constrain_to_constant(
+                        crc,
+                        F::from(reference_crc32(data)),
+                        None,
+                    );
+                }
+                assert_eq!(composer.lookup_table, LookupTable::crc32_table());
+            },
+            1024,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the CRC of other data is expected
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let bytes = b"123456788"
+                    .map(|byte| composer.add_input(F::from(u64::from(byte))));
+                let crc = composer.crc32(&bytes);
+                composer.constrain_to_constant(
+                    crc,
+                    F::from(reference_crc32(b"123456789")),
+                    None,
+                );
+            },
+            1024,
+        );
+        assert!(res.is_err());
+
+        // Should fail as an input isn't a byte
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let byte = composer.add_input(F::from(256u64));
+                composer.crc32(&[byte]);
+            },
+            1024,
+        );
+        assert!(res.is_err());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_crc32],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_crc32],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
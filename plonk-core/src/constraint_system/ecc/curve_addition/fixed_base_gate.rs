@@ -94,6 +94,7 @@ where
         self.q_range.push(F::zero());
         self.q_logic.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
 
         // add high degree selectors
         self.q_hl.push(F::zero());
@@ -73,6 +73,7 @@ where
         self.q_logic.extend(&zeros);
         self.q_fixed_group_add.extend(&zeros);
         self.q_lookup.extend(&zeros);
+        self.q_multiset.extend(&zeros);
 
         // add high degree selectors
         self.q_hl.extend(zeros.iter());
@@ -105,7 +106,7 @@ mod test {
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
 
-    use crate::commitment::HomomorphicCommitment;
+    use crate::proof_system::ProverCommitment;
 
     /// Adds two curve points together using the classical point addition
     /// algorithm. This method is slower than WNAF and is just meant to be the
@@ -217,7 +218,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
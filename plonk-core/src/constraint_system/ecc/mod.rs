@@ -7,6 +7,7 @@
 //! Elliptic Curve Gates
 
 pub mod curve_addition;
+pub mod pedersen;
 pub mod scalar_mul;
 
 use crate::constraint_system::{variable::Variable, StandardComposer};
@@ -129,6 +130,24 @@ where
         self.assert_equal(lhs.y, rhs.y);
     }
 
+    /// Asserts that two points in the circuit have the same x-coordinate.
+    ///
+    /// # Note
+    ///
+    /// On a twisted Edwards curve the points sharing the x-coordinate of
+    /// `(x, y)` are itself and `(x, -y)`, which is its negation plus the
+    /// point of order two `(0, -1)`. A point and its negation `(-x, y)` share
+    /// the y-coordinate instead, see [`StandardComposer::assert_same_y`].
+    pub fn assert_same_x(&mut self, a: Point<P>, b: Point<P>) {
+        self.assert_equal(a.x, b.x);
+    }
+
+    /// Asserts that two points in the circuit have the same y-coordinate,
+    /// i.e. that they are equal up to their sign.
+    pub fn assert_same_y(&mut self, a: Point<P>, b: Point<P>) {
+        self.assert_equal(a.y, b.y);
+    }
+
     /// Adds to the circuit description the conditional selection of the
     /// a point between two of them:
     ///
@@ -210,8 +229,8 @@ where
 mod test {
     use super::*;
     use crate::{
-        batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*,
+        batch_test, constraint_system::helper::*,
+        proof_system::ProverCommitment,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -220,7 +239,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -251,7 +270,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -278,11 +297,59 @@ mod test {
         .expect("test failed");
     }
 
+    fn test_assert_same_coordinate<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (x, y) = P::AFFINE_GENERATOR_COEFFS;
+                let point = TEGroupAffine::<P>::new(x, y);
+                let torsion = TEGroupAffine::<P>::new(F::zero(), -F::one());
+                let point_var = composer.add_affine(point);
+                let neg_var = composer.add_affine(-point);
+                let flipped_var = composer.add_affine(-point + torsion);
+
+                composer.assert_same_x(point_var, flipped_var);
+                composer.assert_same_y(point_var, neg_var);
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (x, y) = P::AFFINE_GENERATOR_COEFFS;
+                let point = TEGroupAffine::<P>::new(x, y);
+                let point_var = composer.add_affine(point);
+                let double_var = composer.add_affine(point + point);
+                composer.assert_same_x(point_var, double_var);
+            },
+            32,
+        );
+        assert!(res.is_err());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (x, y) = P::AFFINE_GENERATOR_COEFFS;
+                let point = TEGroupAffine::<P>::new(x, y);
+                let point_var = composer.add_affine(point);
+                let double_var = composer.add_affine(point + point);
+                composer.assert_same_y(point_var, double_var);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
     // Bls12-381 tests
     batch_test!(
         [
             test_conditional_select_point,
-            test_conditional_point_neg
+            test_conditional_point_neg,
+            test_assert_same_coordinate
         ],
         [] => (
             Bls12_381,
@@ -294,7 +361,8 @@ mod test {
     batch_test!(
         [
             test_conditional_select_point,
-            test_conditional_point_neg
+            test_conditional_point_neg,
+            test_assert_same_coordinate
         ],
         [] => (
             Bls12_377,
@@ -0,0 +1,223 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Pedersen Vector Commitments
+
+use crate::{
+    constraint_system::{ecc::Point, StandardComposer, Variable},
+    util,
+};
+use ark_ec::{
+    twisted_edwards_extended::{
+        GroupAffine as TEGroupAffine, GroupProjective as TEGroupProjective,
+    },
+    AffineCurve, ProjectiveCurve, TEModelParameters,
+};
+use ark_ff::{PrimeField, UniformRand};
+use rand_core::{CryptoRng, RngCore};
+
+/// Generators of the Pedersen vector commitment
+/// `sum values_i * G_i + blinding * H`.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct PedersenParams<P>
+where
+    P: TEModelParameters,
+{
+    /// Generators `G_i` the values are committed with.
+    generators: Vec<TEGroupAffine<P>>,
+
+    /// Generator `H` the blinding factor is committed with.
+    blinding_generator: TEGroupAffine<P>,
+}
+
+impl<F, P> PedersenParams<P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Builds the parameters from the generators of the values and of the
+    /// blinding factor.
+    ///
+    /// The commitment is only binding if nobody knows the discrete logarithm
+    /// of any of the generators with respect to the others.
+    pub fn new(
+        generators: Vec<TEGroupAffine<P>>,
+        blinding_generator: TEGroupAffine<P>,
+    ) -> Self {
+        Self {
+            generators,
+            blinding_generator,
+        }
+    }
+
+    /// Samples the parameters for vectors of up to `len` values with fresh
+    /// random generators.
+    pub fn setup<R>(len: usize, rng: &mut R) -> Self
+    where
+        R: CryptoRng + RngCore,
+    {
+        let mut points = (0..=len)
+            .map(|_| TEGroupProjective::<P>::rand(rng))
+            .collect::<Vec<_>>();
+        let blinding_generator = points.pop().unwrap().into_affine();
+        Self::new(
+            ProjectiveCurve::batch_normalization_into_affine(&points),
+            blinding_generator,
+        )
+    }
+
+    /// Returns the generators the values are committed with.
+    pub fn generators(&self) -> &[TEGroupAffine<P>] {
+        &self.generators
+    }
+
+    /// Returns the generator the blinding factor is committed with.
+    pub fn blinding_generator(&self) -> TEGroupAffine<P> {
+        self.blinding_generator
+    }
+
+    /// Computes the commitment to `values` with the given `blinding` factor
+    /// outside of a circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more values than generators, or if a value or the
+    /// blinding factor doesn't fit in the scalar field of the curve.
+    pub fn commit(&self, values: &[F], blinding: F) -> TEGroupAffine<P> {
+        assert!(
+            values.len() <= self.generators.len(),
+            "There are more values than Pedersen generators"
+        );
+        values
+            .iter()
+            .zip(&self.generators)
+            .fold(
+                self.blinding_generator
+                    .mul(util::to_embedded_curve_scalar::<F, P>(blinding)),
+                |acc, (value, generator)| {
+                    acc + generator
+                        .mul(util::to_embedded_curve_scalar::<F, P>(*value))
+                },
+            )
+            .into_affine()
+    }
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Asserts that `commitment` is the Pedersen commitment to `values` with
+    /// the given `blinding` factor, as computed by
+    /// [`PedersenParams::commit`], proving knowledge of the contents of a
+    /// committed vector.
+    ///
+    /// Every value and the blinding factor cost a
+    /// [`StandardComposer::fixed_base_scalar_mul`] each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more values than generators in `params`.
+    pub fn assert_pedersen_opening(
+        &mut self,
+        commitment: Point<P>,
+        values: &[Variable],
+        blinding: Variable,
+        params: &PedersenParams<P>,
+    ) {
+        assert!(
+            values.len() <= params.generators.len(),
+            "There are more values than Pedersen generators"
+        );
+        let blinding =
+            self.fixed_base_scalar_mul(blinding, params.blinding_generator);
+        let opening = values.iter().zip(&params.generators).fold(
+            blinding,
+            |acc, (value, generator)| {
+                let term = self.fixed_base_scalar_mul(*value, *generator);
+                self.point_addition_gate(acc, term)
+            },
+        );
+        self.assert_equal_point(opening, commitment);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test, constraint_system::helper::*,
+        proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use ark_std::test_rng;
+
+    fn test_assert_pedersen_opening<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                // The circuit is built twice, so the generators must be the
+                // same every time.
+                let params = PedersenParams::<P>::setup(3, &mut test_rng());
+                let values = [F::from(7u64), F::from(11u64)];
+                let blinding = F::from(123456789u64);
+                let commitment = composer
+                    .add_public_affine(params.commit(&values, blinding));
+
+                let values = values.map(|value| composer.add_input(value));
+                let blinding = composer.add_input(blinding);
+                composer.assert_pedersen_opening(
+                    commitment, &values, blinding, &params,
+                );
+            },
+            2048,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the values don't match the commitment
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let params = PedersenParams::<P>::setup(2, &mut test_rng());
+                let blinding = F::from(123456789u64);
+                let commitment = composer.add_public_affine(
+                    params.commit(&[F::from(7u64), F::from(11u64)], blinding),
+                );
+
+                let values = [F::from(11u64), F::from(7u64)]
+                    .map(|value| composer.add_input(value));
+                let blinding = composer.add_input(blinding);
+                composer.assert_pedersen_opening(
+                    commitment, &values, blinding, &params,
+                );
+            },
+            2048,
+        );
+        assert!(res.is_err());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_assert_pedersen_opening],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_assert_pedersen_opening],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
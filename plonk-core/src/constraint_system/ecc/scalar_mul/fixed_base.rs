@@ -158,14 +158,31 @@ where
 
         Point::new(acc_x, acc_y)
     }
+
+    /// Asserts that `point` is `scalar * base`, proving knowledge of the
+    /// discrete logarithm of `point` in base `base`, as in the statement
+    /// "I know `x` such that `P = x * G`".
+    ///
+    /// The `point` is usually a public input, added with
+    /// [`StandardComposer::add_public_affine`]. The same restrictions as for
+    /// [`StandardComposer::fixed_base_scalar_mul`] apply to `base`.
+    pub fn assert_dlog(
+        &mut self,
+        point: Point<P>,
+        scalar: Variable,
+        base: TEGroupAffine<P>,
+    ) {
+        let scalar_mul = self.fixed_base_scalar_mul(scalar, base);
+        self.assert_equal_point(scalar_mul, point);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*, util,
+        batch_test, constraint_system::helper::*,
+        proof_system::ProverCommitment, util,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -175,7 +192,7 @@ mod tests {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -211,7 +228,7 @@ mod tests {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -241,7 +258,7 @@ mod tests {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -271,11 +288,52 @@ mod tests {
         assert!(res.is_err());
     }
 
+    fn test_assert_dlog<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (x, y) = P::AFFINE_GENERATOR_COEFFS;
+                let generator = TEGroupAffine::<P>::new(x, y);
+                let point: TEGroupAffine<P> = AffineCurve::mul(
+                    &generator,
+                    util::to_embedded_curve_scalar::<F, P>(F::from(4242u64)),
+                )
+                .into();
+                let point = composer.add_public_affine(point);
+                let scalar = composer.add_input(F::from(4242u64));
+                composer.assert_dlog(point, scalar, generator);
+            },
+            600,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (x, y) = P::AFFINE_GENERATOR_COEFFS;
+                let generator = TEGroupAffine::<P>::new(x, y);
+                let point: TEGroupAffine<P> = AffineCurve::mul(
+                    &generator,
+                    util::to_embedded_curve_scalar::<F, P>(F::from(4242u64)),
+                )
+                .into();
+                let point = composer.add_public_affine(point);
+                let scalar = composer.add_input(F::from(4243u64));
+                composer.assert_dlog(point, scalar, generator);
+            },
+            600,
+        );
+        assert!(res.is_err());
+    }
+
     fn test_point_addition<F, P, PC>()
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -313,7 +371,7 @@ mod tests {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -388,7 +446,7 @@ mod tests {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -454,6 +512,7 @@ mod tests {
             test_ecc_constraint,
             test_ecc_constraint_zero,
             test_ecc_constraint_should_fail,
+            test_assert_dlog,
             test_point_addition,
             test_pedersen_hash,
             test_pedersen_balance
@@ -470,6 +529,7 @@ mod tests {
             test_ecc_constraint,
             test_ecc_constraint_zero,
             test_ecc_constraint_should_fail,
+            test_assert_dlog,
             test_point_addition,
             test_pedersen_hash,
             test_pedersen_balance
@@ -53,6 +53,109 @@ where
         result
     }
 
+    /// Adds a variable-base multi-scalar multiplication to the circuit
+    /// description, returning `sum scalars[i] * points[i]`.
+    ///
+    /// All the scalars are decomposed up front so that the products share a
+    /// single chain of doublings, which makes this cheaper than summing the
+    /// outputs of [`StandardComposer::variable_base_scalar_mul`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scalars` and `points` have different lengths.
+    pub fn variable_base_msm(
+        &mut self,
+        scalars: &[Variable],
+        points: &[Point<P>],
+    ) -> Point<P> {
+        assert_eq!(
+            scalars.len(),
+            points.len(),
+            "The number of scalars and points of an MSM must match"
+        );
+
+        let scalar_bits_vars: Vec<Vec<Variable>> = scalars
+            .iter()
+            .map(|scalar| {
+                let raw_scalar = *self
+                    .variables
+                    .get(scalar)
+                    .expect("Variable in existance without referenced scalar");
+                self.scalar_decomposition(*scalar, raw_scalar)
+            })
+            .collect();
+
+        let mut result = Point::identity(self);
+        for i in (0..<F as PrimeField>::Params::MODULUS_BITS as usize).rev() {
+            result = self.point_addition_gate(result, result);
+            for (bits, point) in scalar_bits_vars.iter().zip(points) {
+                let point_to_add =
+                    self.conditional_select_identity(bits[i], *point);
+                result = self.point_addition_gate(result, point_to_add);
+            }
+        }
+
+        result
+    }
+
+    /// Adds to the circuit description the group side of a KZG opening check
+    /// for a claimed evaluation `value` of the committed polynomial at
+    /// `point`, returning
+    ///
+    /// ```text
+    /// commitment - value * generator + point * proof
+    /// ```
+    ///
+    /// The opening is valid iff this point `Q` satisfies
+    /// `e(Q, H) = e(proof, tau * H)`, so the caller only has to feed the
+    /// result into the pairing check.
+    ///
+    /// # Note
+    ///
+    /// Only the MSM is computed in-circuit. The points live on the embedded
+    /// curve `P`, so this is meant for recursion over a curve cycle where the
+    /// commitments of the inner proof are points of `P`.
+    pub fn kzg_opening_msm(
+        &mut self,
+        commitment: Point<P>,
+        proof: Point<P>,
+        generator: Point<P>,
+        value: Variable,
+        point: Variable,
+    ) -> Point<P> {
+        let zero = self.zero_var;
+        let neg_generator_x = self.arithmetic_gate(|gate| {
+            gate.witness(generator.x, zero, None)
+                .add(-F::one(), F::zero())
+        });
+        let neg_generator = Point::new(neg_generator_x, generator.y);
+        let msm =
+            self.variable_base_msm(&[value, point], &[neg_generator, proof]);
+        self.point_addition_gate(commitment, msm)
+    }
+
+    /// Constrains `s` to be a canonical scalar of the embedded curve, i.e.
+    /// to be smaller than the order `r` of its subgroup, before it's used in
+    /// [`StandardComposer::variable_base_scalar_mul`].
+    ///
+    /// Both `s` and `r - 1 - s` are range-checked to the bit length of `r`,
+    /// which fails whenever `s >= r` since the difference then wraps around
+    /// the (larger) modulus of the circuit field.
+    pub fn assert_valid_scalar(&mut self, s: Variable) {
+        let num_bits =
+            <P::ScalarField as PrimeField>::Params::MODULUS_BITS as usize;
+        let order = F::from_le_bytes_mod_order(
+            &<P::ScalarField as PrimeField>::Params::MODULUS.to_bytes_le(),
+        );
+        let zero = self.zero_var;
+        let complement = self.arithmetic_gate(|gate| {
+            gate.witness(s, zero, None)
+                .add(-F::one(), F::zero())
+                .constant(order - F::one())
+        });
+        self.multi_range(&[s, complement], &[num_bits, num_bits]);
+    }
+
     fn scalar_decomposition(
         &mut self,
         witness_var: Variable,
@@ -99,8 +202,8 @@ where
 mod test {
     use super::*;
     use crate::{
-        batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*, util,
+        batch_test, constraint_system::helper::*,
+        proof_system::ProverCommitment, util,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -108,12 +211,13 @@ mod test {
         twisted_edwards_extended::GroupAffine as TEGroupAffine, AffineCurve,
         TEModelParameters,
     };
+    use ark_ff::{One, Zero};
 
     fn test_var_base_scalar_mul<F, P, PC>()
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -148,9 +252,184 @@ mod test {
         assert!(res.is_ok());
     }
 
+    fn test_variable_base_msm<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (x, y) = P::AFFINE_GENERATOR_COEFFS;
+                let generator = TEGroupAffine::<P>::new(x, y);
+                let other: TEGroupAffine<P> =
+                    AffineCurve::mul(&generator, P::ScalarField::from(7u64))
+                        .into();
+
+                let s_1 = P::ScalarField::from(1234567u64);
+                let s_2 = P::ScalarField::from(89u64);
+                let expected = AffineCurve::mul(&generator, s_1)
+                    + AffineCurve::mul(&other, s_2);
+
+                let scalars = [
+                    composer.add_input(
+                        util::from_embedded_curve_scalar::<F, P>(s_1),
+                    ),
+                    composer.add_input(
+                        util::from_embedded_curve_scalar::<F, P>(s_2),
+                    ),
+                ];
+                let points = [
+                    composer.add_affine(generator),
+                    composer.add_affine(other),
+                ];
+                let msm = composer.variable_base_msm(&scalars, &points);
+
+                composer.assert_equal_public_point(msm, expected.into());
+            },
+            4096,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+    }
+
+    fn test_kzg_opening_msm<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // Simulates a KZG opening of `p(X) = 3 + 2X + 5X^2 + X^3` at `z`
+        // over the embedded curve with a known trapdoor `tau`, so that the
+        // pairing check `e(Q, H) = e(proof, tau * H)` reduces to
+        // `Q = tau * proof`.
+        fn build_opening<F, P>(
+            composer: &mut StandardComposer<F, P>,
+            tamper: bool,
+        ) where
+            F: PrimeField,
+            P: TEModelParameters<BaseField = F>,
+        {
+            let coeffs = [3u64, 2, 5, 1].map(P::ScalarField::from);
+            let eval = |x: P::ScalarField| {
+                coeffs
+                    .iter()
+                    .rev()
+                    .fold(P::ScalarField::zero(), |acc, c| acc * x + c)
+            };
+            let tau = P::ScalarField::from(987654321u64);
+            let z = P::ScalarField::from(17u64);
+            let value = eval(z);
+            let quotient = (eval(tau) - value) / (tau - z);
+
+            let (x, y) = P::AFFINE_GENERATOR_COEFFS;
+            let generator = TEGroupAffine::<P>::new(x, y);
+            let commitment: TEGroupAffine<P> =
+                AffineCurve::mul(&generator, eval(tau)).into();
+            let proof: TEGroupAffine<P> =
+                AffineCurve::mul(&generator, quotient).into();
+            let expected: TEGroupAffine<P> =
+                AffineCurve::mul(&proof, tau).into();
+
+            let claimed = if tamper {
+                value + P::ScalarField::one()
+            } else {
+                value
+            };
+            let value_var = composer
+                .add_input(util::from_embedded_curve_scalar::<F, P>(claimed));
+            let z_var =
+                composer.add_input(util::from_embedded_curve_scalar::<F, P>(z));
+            let commitment_var = composer.add_affine(commitment);
+            let proof_var = composer.add_affine(proof);
+            let generator_var = composer.add_affine(generator);
+
+            let q = composer.kzg_opening_msm(
+                commitment_var,
+                proof_var,
+                generator_var,
+                value_var,
+                z_var,
+            );
+            composer.assert_equal_public_point(q, expected);
+        }
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                build_opening(composer, false)
+            },
+            4096,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                build_opening(composer, true)
+            },
+            4096,
+        );
+        assert!(res.is_err());
+    }
+
+    /// Returns the order of the embedded curve subgroup as a circuit field
+    /// element.
+    fn embedded_order<F, P>() -> F
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        F::from_le_bytes_mod_order(
+            &<P::ScalarField as PrimeField>::Params::MODULUS.to_bytes_le(),
+        )
+    }
+
+    fn test_assert_valid_scalar<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let max = embedded_order::<F, P>() - F::one();
+                for scalar in [F::zero(), F::from(5u64), max] {
+                    let s = composer.add_input(scalar);
+                    composer.assert_valid_scalar(s);
+                }
+            },
+            1024,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the scalar is the order of the subgroup
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let s = composer.add_input(embedded_order::<F, P>());
+                composer.assert_valid_scalar(s);
+            },
+            512,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the scalar is above the order of the subgroup
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let s = composer
+                    .add_input(embedded_order::<F, P>() + F::from(5u64));
+                composer.assert_valid_scalar(s);
+            },
+            512,
+        );
+        assert!(res.is_err());
+    }
+
     // Tests for Bls12_381
     batch_test!(
-        [test_var_base_scalar_mul],
+        [
+            test_var_base_scalar_mul,
+            test_variable_base_msm,
+            test_kzg_opening_msm,
+            test_assert_valid_scalar
+        ],
         [] => (
             Bls12_381,
             ark_ed_on_bls12_381::EdwardsParameters
@@ -159,7 +438,12 @@ mod test {
 
     // Tests for Bls12_377
     batch_test!(
-        [test_var_base_scalar_mul],
+        [
+            test_var_base_scalar_mul,
+            test_variable_base_msm,
+            test_kzg_opening_msm,
+            test_assert_valid_scalar
+        ],
         [] => (
             Bls12_377,
             ark_ed_on_bls12_377::EdwardsParameters
@@ -0,0 +1,610 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Non-native field arithmetic
+//!
+//! Every relation between [`NonNativeElement`]s is checked as an integer
+//! identity `combination - quotient * modulus = 0`. Both sides are split
+//! into columns of limb products, which are merged in pairs of 128 bits so
+//! that each pair, along with the carry from the previous one, is small
+//! enough to be checked in the native field without wrapping around.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::{FpParameters, PrimeField};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, Zero};
+
+/// Number of limbs of a [`NonNativeElement`].
+pub(crate) const NUM_LIMBS: usize = 4;
+
+/// Number of bits of the limbs of a [`NonNativeElement`].
+pub(crate) const LIMB_BITS: usize = 64;
+
+/// Represents an integer of at most 256 bits in the circuit, standing for an
+/// element of a field other than the one of the circuit, such as the base or
+/// scalar field of secp256k1.
+///
+/// The integer is split into little-endian 64-bit limbs, each constrained to
+/// be inside of the range \[0, 2^64\), and isn't necessarily reduced modulo
+/// the modulus of the field.
+#[derive(Clone, Copy, Debug)]
+pub struct NonNativeElement {
+    /// Little-endian limbs
+    limbs: [Variable; NUM_LIMBS],
+}
+
+impl NonNativeElement {
+    /// Builds a new [`NonNativeElement`] from little-endian `limbs`, which
+    /// must already be range-checked to 64 bits.
+    pub(crate) fn new(limbs: [Variable; NUM_LIMBS]) -> Self {
+        Self { limbs }
+    }
+
+    /// Returns the little-endian 64-bit limbs of `self`.
+    pub fn limbs(&self) -> &[Variable; NUM_LIMBS] {
+        &self.limbs
+    }
+}
+
+/// Integer combination `sum c * a * b + sum d * e + constant` of
+/// [`NonNativeElement`]s with small signed coefficients, see
+/// [`StandardComposer::assert_nonnative_zero`].
+#[derive(Debug, Default)]
+pub(crate) struct Combination {
+    /// Products `c * a * b`
+    products: Vec<(i64, NonNativeElement, NonNativeElement)>,
+
+    /// Terms `d * e`
+    terms: Vec<(i64, NonNativeElement)>,
+
+    /// Constant term
+    constant: BigInt,
+}
+
+impl Combination {
+    /// Adds `coeff * a * b` to the combination.
+    pub(crate) fn product(
+        mut self,
+        coeff: i64,
+        a: NonNativeElement,
+        b: NonNativeElement,
+    ) -> Self {
+        self.products.push((coeff, a, b));
+        self
+    }
+
+    /// Adds `coeff * a` to the combination.
+    pub(crate) fn term(mut self, coeff: i64, a: NonNativeElement) -> Self {
+        self.terms.push((coeff, a));
+        self
+    }
+
+    /// Adds `constant` to the combination.
+    pub(crate) fn constant(mut self, constant: BigInt) -> Self {
+        self.constant += constant;
+        self
+    }
+}
+
+/// Column of the limb-wise expansion of an integer identity, holding the
+/// coefficients of `2^(64 * i)` for some `i`.
+#[derive(Default)]
+struct Column<F>
+where
+    F: PrimeField,
+{
+    /// Products of limbs
+    products: Vec<(F, Variable, Variable)>,
+
+    /// Multiples of limbs
+    terms: Vec<(F, Variable)>,
+
+    /// Constant term
+    constant: BigInt,
+
+    /// Value of the column for the witness
+    value: BigInt,
+
+    /// Upper bound of the absolute value of the column
+    bound: BigUint,
+}
+
+impl<F> Column<F>
+where
+    F: PrimeField,
+{
+    /// Adds `other * 2^(64 * shift)` to `self`.
+    fn merge(&mut self, other: Self, shift: usize) {
+        let scale = F::from(2u64).pow([(LIMB_BITS * shift) as u64]);
+        let bits = LIMB_BITS * shift;
+        self.products.extend(
+            other
+                .products
+                .into_iter()
+                .map(|(c, a, b)| (c * scale, a, b)),
+        );
+        self.terms
+            .extend(other.terms.into_iter().map(|(c, a)| (c * scale, a)));
+        self.constant += other.constant << bits;
+        self.value += other.value << bits;
+        self.bound += other.bound << bits;
+    }
+}
+
+/// Returns the integer with the little-endian 64-bit `limbs`.
+pub(crate) fn from_limbs(limbs: &[u64; NUM_LIMBS]) -> BigUint {
+    limbs
+        .iter()
+        .rev()
+        .fold(BigUint::zero(), |acc, limb| (acc << LIMB_BITS) + *limb)
+}
+
+/// Returns the inverse of `value` modulo the prime `modulus`, or zero if
+/// `value` is a multiple of `modulus`.
+pub(crate) fn inverse(value: &BigUint, modulus: &BigUint) -> BigUint {
+    value.modpow(&(modulus - 2u32), modulus)
+}
+
+/// Returns the little-endian 64-bit limbs of `value`, the last one holding
+/// all the bits above the others.
+fn split(value: &BigUint) -> [BigUint; NUM_LIMBS] {
+    let mask = (BigUint::one() << LIMB_BITS) - 1u32;
+    let mut limbs = [(); NUM_LIMBS].map(|_| BigUint::zero());
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        *limb = value >> (LIMB_BITS * i);
+        if i < NUM_LIMBS - 1 {
+            *limb &= &mask;
+        }
+    }
+    limbs
+}
+
+/// Maps the signed integer `value` into the field `F`.
+fn to_field<F>(value: &BigInt) -> F
+where
+    F: PrimeField,
+{
+    let magnitude = F::from(value.magnitude().clone());
+    match value.sign() {
+        Sign::Minus => -magnitude,
+        _ => magnitude,
+    }
+}
+
+/// Rounds `num_bits` up to an even number, as range-checked by
+/// [`StandardComposer::multi_range`] without extra gates.
+fn even(num_bits: usize) -> usize {
+    num_bits + num_bits % 2
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Adds the integer with the little-endian 64-bit `limbs` to the circuit
+    /// as a witness [`NonNativeElement`], range-checking every limb.
+    pub fn add_nonnative_input(
+        &mut self,
+        limbs: [u64; NUM_LIMBS],
+    ) -> NonNativeElement {
+        let element = self.nonnative_witness(&from_limbs(&limbs));
+        self.multi_range(&element.limbs, &[LIMB_BITS; NUM_LIMBS]);
+        element
+    }
+
+    /// Adds the integer `value`, of at most 256 bits, to the circuit
+    /// description as a constant [`NonNativeElement`].
+    pub(crate) fn nonnative_constant(
+        &mut self,
+        value: &BigUint,
+    ) -> NonNativeElement {
+        NonNativeElement::new(
+            split(value).map(|limb| {
+                self.add_witness_to_circuit_description(F::from(limb))
+            }),
+        )
+    }
+
+    /// Adds the integer `value`, of at most 256 bits, to the circuit as a
+    /// witness [`NonNativeElement`] whose limbs are range-checked by the
+    /// [`StandardComposer::assert_nonnative_zero`] constraining it.
+    pub(crate) fn nonnative_witness(
+        &mut self,
+        value: &BigUint,
+    ) -> NonNativeElement {
+        NonNativeElement::new(
+            split(value).map(|limb| self.add_input(F::from(limb))),
+        )
+    }
+
+    /// Returns the integer `element` holds.
+    pub(crate) fn nonnative_value(&self, element: NonNativeElement) -> BigUint {
+        element
+            .limbs
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, limb| {
+                let limb: BigUint = self.value_of_var(*limb).into();
+                (acc << LIMB_BITS) + limb
+            })
+    }
+
+    /// Returns `a * b` reduced modulo `modulus`.
+    pub(crate) fn nonnative_mul(
+        &mut self,
+        a: NonNativeElement,
+        b: NonNativeElement,
+        modulus: &BigUint,
+    ) -> NonNativeElement {
+        let value = self.nonnative_value(a) * self.nonnative_value(b) % modulus;
+        let product = self.nonnative_witness(&value);
+        self.assert_nonnative_zero(
+            &Combination::default().product(1, a, b).term(-1, product),
+            Some(modulus),
+            &[product],
+        );
+        product
+    }
+
+    /// Constrains `a` to be smaller than `bound`, e.g. to be reduced modulo
+    /// `bound`.
+    pub(crate) fn assert_nonnative_less_than(
+        &mut self,
+        a: NonNativeElement,
+        bound: &BigUint,
+    ) {
+        // bound - 1 - a has at most 256 bits iff a < bound.
+        let max = bound - 1u32;
+        let value = self.nonnative_value(a);
+        let difference = self.nonnative_witness(&if value <= max {
+            &max - value
+        } else {
+            BigUint::zero()
+        });
+        self.assert_nonnative_zero(
+            &Combination::default()
+                .term(1, a)
+                .term(1, difference)
+                .constant(-BigInt::from(max)),
+            None,
+            &[difference],
+        );
+    }
+
+    /// Constrains the integer `combination` to be a multiple of `modulus`, or
+    /// to be zero if there is no modulus, range-checking the limbs of the
+    /// `fresh` elements along the way.
+    ///
+    /// The quotient is shifted to be non-negative and range-checked to the
+    /// bits the combination can reach, given that every limb has 64 bits.
+    /// The columns of the identity are then checked pair by pair, each one
+    /// carrying its multiple of `2^128` over to the next one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the native field is too small for the columns to be checked
+    /// without wrapping around its modulus.
+    pub(crate) fn assert_nonnative_zero(
+        &mut self,
+        combination: &Combination,
+        modulus: Option<&BigUint>,
+        fresh: &[NonNativeElement],
+    ) {
+        let max_limb = (BigUint::one() << LIMB_BITS) - 1u32;
+        let max_element = (BigUint::one() << (LIMB_BITS * NUM_LIMBS)) - 1u32;
+        let mut range = fresh
+            .iter()
+            .flat_map(|element| element.limbs.map(|limb| (limb, LIMB_BITS)))
+            .collect::<Vec<_>>();
+
+        // Value of the combination and bounds of its positive and negative
+        // parts.
+        let mut value = combination.constant.clone();
+        let mut bounds = [BigUint::zero(), BigUint::zero()];
+        let mut add_bound = |coeff: &BigInt, magnitude: &BigUint| {
+            let side = usize::from(coeff.sign() == Sign::Minus);
+            bounds[side] += coeff.magnitude() * magnitude;
+        };
+        add_bound(&combination.constant, &BigUint::one());
+        for (coeff, a, b) in &combination.products {
+            let coeff = BigInt::from(*coeff);
+            value += &coeff
+                * BigInt::from(
+                    self.nonnative_value(*a) * self.nonnative_value(*b),
+                );
+            add_bound(&coeff, &(&max_element * &max_element));
+        }
+        for (coeff, a) in &combination.terms {
+            let coeff = BigInt::from(*coeff);
+            value += &coeff * BigInt::from(self.nonnative_value(*a));
+            add_bound(&coeff, &max_element);
+        }
+        let [positive, negative] = bounds;
+
+        let mut columns = Vec::<Column<F>>::new();
+        columns.resize_with(2 * NUM_LIMBS - 1, Default::default);
+
+        // Subtract `quotient * modulus`, where the quotient is shifted by
+        // `shift` so that `combination + shift * modulus` is non-negative.
+        let mut constant = combination.constant.clone();
+        if let Some(modulus) = modulus {
+            let shift = (&negative + modulus - 1u32) / modulus;
+            let max_quotient = (&positive + &shift * modulus) / modulus;
+            let quotient = (value.clone() + BigInt::from(&shift * modulus))
+                .to_biguint()
+                .unwrap_or_default()
+                / modulus;
+            constant += BigInt::from(&shift * modulus);
+
+            let num_bits = max_quotient.bits() as usize;
+            for (i, limb) in split(&quotient).iter().enumerate() {
+                let limb_bits = if i < NUM_LIMBS - 1 {
+                    num_bits.saturating_sub(LIMB_BITS * i).min(LIMB_BITS)
+                } else {
+                    num_bits.saturating_sub(LIMB_BITS * i)
+                };
+                let limb_bits = even(limb_bits);
+                let var = self.add_input(F::from(limb.clone()));
+                range.push((var, limb_bits));
+                let max = (BigUint::one() << limb_bits) - 1u32;
+                for (j, modulus_limb) in split(modulus).iter().enumerate() {
+                    let column = &mut columns[i + j];
+                    column.terms.push((-F::from(modulus_limb.clone()), var));
+                    column.value -= BigInt::from(modulus_limb * limb);
+                    column.bound += modulus_limb * &max;
+                }
+            }
+        }
+
+        for (coeff, a, b) in &combination.products {
+            let field_coeff = to_field::<F>(&BigInt::from(*coeff));
+            for (i, a_limb) in a.limbs.iter().enumerate() {
+                let a_value: BigUint = self.value_of_var(*a_limb).into();
+                for (j, b_limb) in b.limbs.iter().enumerate() {
+                    let b_value: BigUint = self.value_of_var(*b_limb).into();
+                    let column = &mut columns[i + j];
+                    column.products.push((field_coeff, *a_limb, *b_limb));
+                    column.value += *coeff * BigInt::from(&a_value * b_value);
+                    column.bound +=
+                        coeff.unsigned_abs() * &max_limb * &max_limb;
+                }
+            }
+        }
+        for (coeff, a) in &combination.terms {
+            let field_coeff = to_field::<F>(&BigInt::from(*coeff));
+            for (i, limb) in a.limbs.iter().enumerate() {
+                let limb_value: BigUint = self.value_of_var(*limb).into();
+                let column = &mut columns[i];
+                column.terms.push((field_coeff, *limb));
+                column.value += *coeff * BigInt::from(limb_value);
+                column.bound += coeff.unsigned_abs() * &max_limb;
+            }
+        }
+        let sign = if constant.sign() == Sign::Minus {
+            -BigInt::one()
+        } else {
+            BigInt::one()
+        };
+        let constant_limbs = constant.magnitude().to_u64_digits();
+        if columns.len() < constant_limbs.len() {
+            columns.resize_with(constant_limbs.len(), Default::default);
+        }
+        for (i, limb) in constant_limbs.into_iter().enumerate() {
+            let limb = &sign * BigInt::from(limb);
+            columns[i].value += &limb;
+            columns[i].bound += limb.magnitude();
+            columns[i].constant += limb;
+        }
+
+        // Merge the columns in pairs of 128 bits.
+        let mut pairs = Vec::new();
+        let mut columns = columns.into_iter();
+        while let Some(mut low) = columns.next() {
+            if let Some(high) = columns.next() {
+                low.merge(high, 1);
+            }
+            pairs.push(low);
+        }
+
+        let capacity = BigUint::one() << F::Params::CAPACITY;
+        let mut carry: Option<(Variable, BigUint, usize)> = None;
+        let mut carry_value = BigInt::zero();
+        let mut carry_bound = BigUint::zero();
+        let num_pairs = pairs.len();
+        for (index, mut pair) in pairs.into_iter().enumerate() {
+            // The shifted carries are range-checked, which bounds the
+            // absolute value of each equation over the integers.
+            let mut equation_bound = pair.bound.clone();
+            if let Some((var, offset, num_bits)) = carry.take() {
+                pair.terms.push((F::one(), var));
+                pair.constant -= BigInt::from(offset);
+                equation_bound += BigUint::one() << num_bits;
+            }
+            if index < num_pairs - 1 {
+                let total = &pair.value + &carry_value;
+                carry_value = total >> (2 * LIMB_BITS);
+                carry_bound = (&pair.bound + &carry_bound) >> (2 * LIMB_BITS);
+                let num_bits = even((&carry_bound << 1u32).bits() as usize);
+                let var = self.add_input(to_field(
+                    &(&carry_value + BigInt::from(carry_bound.clone())),
+                ));
+                range.push((var, num_bits));
+                let scale = F::from(2u64).pow([2 * LIMB_BITS as u64]);
+                pair.terms.push((-scale, var));
+                pair.constant += BigInt::from(&carry_bound << (2 * LIMB_BITS));
+                equation_bound += BigUint::one() << (2 * LIMB_BITS + num_bits);
+                carry = Some((var, carry_bound.clone(), num_bits));
+            }
+            assert!(
+                equation_bound < capacity,
+                "The native field is too small for non-native arithmetic"
+            );
+            self.assert_sum_zero(
+                &pair.products,
+                &pair.terms,
+                to_field(&pair.constant),
+            );
+        }
+
+        let (values, bit_widths): (Vec<_>, Vec<_>) = range.into_iter().unzip();
+        self.multi_range(&values, &bit_widths);
+    }
+
+    /// Constrains `sum c * a * b + sum d * e + constant` to be zero.
+    ///
+    /// Every gate adds a product or two terms to a running sum, except for
+    /// the first one which takes an extra term through its fourth wire.
+    fn assert_sum_zero(
+        &mut self,
+        mut products: &[(F, Variable, Variable)],
+        mut terms: &[(F, Variable)],
+        constant: F,
+    ) {
+        assert!(
+            !products.is_empty() || !terms.is_empty(),
+            "Cannot constrain an empty sum"
+        );
+        let zero = self.zero_var;
+        let next_term = |terms: &mut &[(F, Variable)]| match terms.split_first()
+        {
+            Some((term, rest)) => {
+                *terms = rest;
+                *term
+            }
+            None => (F::zero(), zero),
+        };
+        let mut sum = None;
+        let mut q_c = constant;
+        loop {
+            let (q_4, w_4) = match sum {
+                Some(sum) => (F::one(), sum),
+                None => next_term(&mut terms),
+            };
+            let (q_m, (q_l, w_l), (q_r, w_r)) = match products.split_first() {
+                Some(((c, a, b), rest)) => {
+                    products = rest;
+                    (*c, (F::zero(), *a), (F::zero(), *b))
+                }
+                None => {
+                    (F::zero(), next_term(&mut terms), next_term(&mut terms))
+                }
+            };
+            let last = products.is_empty() && terms.is_empty();
+            let output = self.arithmetic_gate(|gate| {
+                gate.witness(w_l, w_r, last.then_some(zero))
+                    .mul(q_m)
+                    .add(q_l, q_r)
+                    .fan_in_3(q_4, w_4)
+                    .constant(q_c)
+            });
+            if last {
+                break;
+            }
+            sum = Some(output);
+            q_c = F::zero();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test,
+        constraint_system::{
+            ecdsa::{EcdsaCurve, Secp256k1},
+            helper::*,
+        },
+        proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    /// Constrains `element` to hold `value`.
+    fn assert_value<F, P>(
+        composer: &mut StandardComposer<F, P>,
+        element: NonNativeElement,
+        value: &BigUint,
+    ) where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        for (limb, value) in element.limbs().iter().zip(split(value)) {
+            composer.constrain_to_constant(*limb, F::from(value), None);
+        }
+    }
+
+    fn test_nonnative_mul<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                // Unreduced limbs of secp256k1 base field elements.
+                let p = from_limbs(&Secp256k1::BASE_MODULUS);
+                let a = composer.add_nonnative_input([u64::MAX; 4]);
+                let b = composer.add_nonnative_input(Secp256k1::GENERATOR.0);
+                let product = composer.nonnative_mul(a, b, &p);
+                let expected = from_limbs(&[u64::MAX; 4])
+                    * from_limbs(&Secp256k1::GENERATOR.0)
+                    % &p;
+                assert_value(composer, product, &expected);
+
+                // The order of secp256k1 isn't smaller than itself.
+                let n = from_limbs(&Secp256k1::SCALAR_MODULUS);
+                let max = composer.nonnative_constant(&(&n - 1u32));
+                composer.assert_nonnative_less_than(max, &n);
+            },
+            512,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the product is congruent to the expected one but
+        // the limbs are different.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let p = from_limbs(&Secp256k1::BASE_MODULUS);
+                let a = composer.add_nonnative_input([2, 0, 0, 0]);
+                let b = composer.add_nonnative_input(Secp256k1::BASE_MODULUS);
+                let product = composer.nonnative_mul(a, b, &p);
+                assert_value(composer, product, &p);
+            },
+            512,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the modulus isn't smaller than itself.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let n = from_limbs(&Secp256k1::SCALAR_MODULUS);
+                let a = composer.add_nonnative_input(Secp256k1::SCALAR_MODULUS);
+                composer.assert_nonnative_less_than(a, &n);
+            },
+            512,
+        );
+        assert!(res.is_err());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_nonnative_mul],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_nonnative_mul],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
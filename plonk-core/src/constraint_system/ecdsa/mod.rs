@@ -0,0 +1,391 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! ECDSA Gates
+//!
+//! Verification of ECDSA signatures over curves which aren't embedded in the
+//! circuit, such as secp256k1, whose base and scalar fields are emulated
+//! with [`NonNativeElement`]s.
+
+mod field;
+mod point;
+
+pub use field::NonNativeElement;
+pub use point::NonNativePoint;
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use field::{from_limbs, inverse, Combination, LIMB_BITS};
+use num_bigint::{BigInt, BigUint};
+use num_traits::One;
+use point::{add_affine, base_modulus, mul_affine, offset_point};
+
+/// Short Weierstrass curve `y^2 = x^3 + a * x + b` of prime order, whose
+/// ECDSA signatures can be verified with
+/// [`StandardComposer::ecdsa_verify`].
+///
+/// The constants are integers of at most 256 bits, given as little-endian
+/// 64-bit limbs.
+pub trait EcdsaCurve {
+    /// Modulus of the base field
+    const BASE_MODULUS: [u64; 4];
+
+    /// Order of the curve, which is the modulus of its scalar field
+    const SCALAR_MODULUS: [u64; 4];
+
+    /// Coefficient `a` of the curve equation
+    const COEFF_A: [u64; 4];
+
+    /// Coefficient `b` of the curve equation
+    const COEFF_B: [u64; 4];
+
+    /// Coordinates of the generator
+    const GENERATOR: ([u64; 4], [u64; 4]);
+}
+
+/// The secp256k1 curve `y^2 = x^3 + 7` used by Bitcoin and Ethereum.
+#[derive(Clone, Copy, Debug)]
+pub struct Secp256k1;
+
+impl EcdsaCurve for Secp256k1 {
+    const BASE_MODULUS: [u64; 4] = [
+        0xfffffffefffffc2f,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+    ];
+    const SCALAR_MODULUS: [u64; 4] = [
+        0xbfd25e8cd0364141,
+        0xbaaedce6af48a03b,
+        0xfffffffffffffffe,
+        0xffffffffffffffff,
+    ];
+    const COEFF_A: [u64; 4] = [0, 0, 0, 0];
+    const COEFF_B: [u64; 4] = [7, 0, 0, 0];
+    const GENERATOR: ([u64; 4], [u64; 4]) = (
+        [
+            0x59f2815b16f81798,
+            0x029bfcdb2dce28d9,
+            0x55a06295ce870b07,
+            0x79be667ef9dcbbac,
+        ],
+        [
+            0x9c47d08ffb10d4b8,
+            0xfd17b448a6855419,
+            0x5da4fbfc0e1108a8,
+            0x483ada7726a3c465,
+        ],
+    );
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Constrains `(r, s)` to be a valid ECDSA signature of the message hash
+    /// `message_hash` for the public key `public_key` over the curve `C`.
+    ///
+    /// This checks that `public_key` is on the curve, that `r` and `s` are
+    /// inside of the range \[1, n - 1\] where `n` is the order of the curve,
+    /// and the verification equation `r = (u1 * G + u2 * Q).x mod n` where
+    /// `u1 = z / s mod n` and `u2 = r / s mod n`, `z` being the message hash,
+    /// `G` the generator and `Q` the public key. The message hash is taken
+    /// as is, so it must already be truncated to the bit length of `n`.
+    ///
+    /// Both scalar multiplications share their doublings, adding one of four
+    /// precomputed points to an accumulator depending on the bits of `u1`
+    /// and `u2`. Each bit costs a doubling and an addition of non-native
+    /// points, for a total of a few hundred thousand gates for a curve of 256
+    /// bits.
+    pub fn ecdsa_verify<C>(
+        &mut self,
+        public_key: NonNativePoint<C>,
+        message_hash: NonNativeElement,
+        r: NonNativeElement,
+        s: NonNativeElement,
+    ) where
+        C: EcdsaCurve,
+    {
+        let n = from_limbs(&C::SCALAR_MODULUS);
+        self.assert_nonnative_on_curve(public_key);
+
+        // r and s are reduced, r isn't zero and s has an inverse.
+        self.assert_nonnative_less_than(r, &n);
+        self.assert_nonnative_less_than(s, &n);
+        let limb_sum = self
+            .weighted_sum(&r.limbs().map(|limb| (F::one(), limb)), F::zero());
+        let r_is_zero = self.is_zero_with_output(limb_sum);
+        self.constrain_to_constant(r_is_zero, F::zero(), None);
+        let s_inverse =
+            self.nonnative_witness(&inverse(&self.nonnative_value(s), &n));
+        self.assert_nonnative_zero(
+            &Combination::default()
+                .product(1, s, s_inverse)
+                .constant(-BigInt::one()),
+            Some(&n),
+            &[s_inverse],
+        );
+        let u_1 = self.nonnative_mul(message_hash, s_inverse, &n);
+        let u_2 = self.nonnative_mul(r, s_inverse, &n);
+
+        let point = self.nonnative_double_scalar_mul(u_1, u_2, public_key);
+
+        // The x-coordinate is reduced modulo the base field, whose modulus
+        // is smaller than twice n, so it's congruent to r modulo n iff it's
+        // r or r + n.
+        self.assert_nonnative_less_than(*point.x(), &base_modulus::<C>());
+        self.assert_nonnative_zero(
+            &Combination::default().term(1, *point.x()).term(-1, r),
+            Some(&n),
+            &[],
+        );
+    }
+
+    /// Returns `u_1 * G + u_2 * q`, where `G` is the generator of `C`.
+    ///
+    /// For every pair of bits `b_1` and `b_2` of `u_1` and `u_2`, the
+    /// accumulator is doubled and `H + b_1 * G + b_2 * q` added to it, `H`
+    /// being the [`offset_point`] of `C`. The accumulator starts from `H`,
+    /// and `(2^(k + 1) - 1) * H` is subtracted at the end for scalars of `k`
+    /// bits. The discrete logarithm of `H` is unknown, so none of the
+    /// additions is between points with the same x-coordinate, even when
+    /// `q` is `G` or `-G`, but the last one if the result is the identity.
+    fn nonnative_double_scalar_mul<C>(
+        &mut self,
+        u_1: NonNativeElement,
+        u_2: NonNativeElement,
+        q: NonNativePoint<C>,
+    ) -> NonNativePoint<C>
+    where
+        C: EcdsaCurve,
+    {
+        let n = from_limbs(&C::SCALAR_MODULUS);
+        let generator =
+            (from_limbs(&C::GENERATOR.0), from_limbs(&C::GENERATOR.1));
+        let offset = offset_point::<C>();
+        let u_1_bits = self.nonnative_bits(u_1);
+        let u_2_bits = self.nonnative_bits(u_2);
+        let multiple = ((BigUint::one() << (u_1_bits.len() + 1)) - 1u32) % &n;
+        let correction =
+            mul_affine::<C>(&offset, &(&n - multiple)).unwrap_or_default();
+        let g_plus_offset =
+            add_affine::<C>(&Some(generator), &Some(offset.clone()))
+                .unwrap_or_default();
+
+        let h = self.nonnative_point_constant(&offset);
+        let g_plus_h = self.nonnative_point_constant(&g_plus_offset);
+        let q_plus_h = self.nonnative_point_add(q, h);
+        let g_plus_q_plus_h = self.nonnative_point_add(q, g_plus_h);
+
+        let mut acc = h;
+        for (bit_1, bit_2) in u_1_bits.into_iter().zip(u_2_bits).rev() {
+            acc = self.nonnative_point_double(acc);
+            let without_q = self.nonnative_point_select(bit_1, g_plus_h, h);
+            let with_q =
+                self.nonnative_point_select(bit_1, g_plus_q_plus_h, q_plus_h);
+            let addend = self.nonnative_point_select(bit_2, with_q, without_q);
+            acc = self.nonnative_point_add(acc, addend);
+        }
+
+        let correction = self.nonnative_point_constant(&correction);
+        self.nonnative_point_add(acc, correction)
+    }
+
+    /// Returns the little-endian bits of the integer `a` holds.
+    fn nonnative_bits(&mut self, a: NonNativeElement) -> Vec<Variable> {
+        let mut bits = Vec::with_capacity(4 * LIMB_BITS);
+        for limb in a.limbs() {
+            let value = self.value_of_var(*limb).into_repr().as_ref()[0];
+            let limb_bits = (0..LIMB_BITS)
+                .map(|i| self.add_input(F::from((value >> i) & 1)))
+                .collect::<Vec<_>>();
+            let mut power = F::one();
+            let terms = limb_bits
+                .iter()
+                .map(|bit| {
+                    self.boolean_gate(*bit);
+                    let term = (power, *bit);
+                    power.double_in_place();
+                    term
+                })
+                .collect::<Vec<_>>();
+            let packed = self.weighted_sum(&terms, F::zero());
+            self.assert_equal(packed, *limb);
+            bits.extend(limb_bits);
+        }
+        bits
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::batch_test_field_params;
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    /// Public key of a secp256k1 signature generated with OpenSSL.
+    const PUBLIC_KEY: ([u64; 4], [u64; 4]) = (
+        [
+            0x11db81e0c0e4e6d0,
+            0xbfa6fd8a7aa05261,
+            0x5dce22b3093f5c82,
+            0xa75963a79d5471dc,
+        ],
+        [
+            0x63bde28125c0087d,
+            0x332dc713363bc20c,
+            0xbbb4fa79a587f050,
+            0x1a5f87bb97a56f6b,
+        ],
+    );
+
+    /// SHA-256 hash of the signed message, `"ark-plonk ecdsa"`.
+    const MESSAGE_HASH: [u64; 4] = [
+        0x4e7229eb68879e41,
+        0x59986604ace33f3c,
+        0xbf8e20f523e9797d,
+        0x1d807cbbfe13a4ad,
+    ];
+
+    /// Signature `(r, s)` of the message hash.
+    const SIGNATURE: ([u64; 4], [u64; 4]) = (
+        [
+            0x65b639ff72e9fd5d,
+            0x9d2659d2abd31d49,
+            0x2a99b0a9f50c71e1,
+            0xa3e75767978e127a,
+        ],
+        [
+            0x788508f8ff4acd20,
+            0x94c4b16ca8bb2b9e,
+            0xb00979cf4af0ecd7,
+            0xe67eecdf3f8e404b,
+        ],
+    );
+
+    /// Returns the little-endian 64-bit limbs of `value`.
+    fn to_limbs(value: &BigUint) -> [u64; 4] {
+        let mut limbs = [0; 4];
+        for (limb, digit) in limbs.iter_mut().zip(value.to_u64_digits()) {
+            *limb = digit;
+        }
+        limbs
+    }
+
+    /// Adds the verification of the signature `(r, s)` of `message_hash` by
+    /// `public_key` to a composer, and checks that the circuit is satisfied.
+    fn verify_signature<F, P>(
+        public_key: ([u64; 4], [u64; 4]),
+        message_hash: [u64; 4],
+        (r, s): ([u64; 4], [u64; 4]),
+    ) where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let public_key = NonNativePoint::<Secp256k1>::new(
+            composer.add_nonnative_input(public_key.0),
+            composer.add_nonnative_input(public_key.1),
+        );
+        let message_hash = composer.add_nonnative_input(message_hash);
+        let r = composer.add_nonnative_input(r);
+        let s = composer.add_nonnative_input(s);
+        composer.ecdsa_verify(public_key, message_hash, r, s);
+        composer.check_circuit_satisfied();
+    }
+
+    fn test_ecdsa_verify<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        // The signature is valid out of the circuit.
+        let n = from_limbs(&Secp256k1::SCALAR_MODULUS);
+        let s_inverse = inverse(&from_limbs(&SIGNATURE.1), &n);
+        let u_1 = from_limbs(&MESSAGE_HASH) * &s_inverse % &n;
+        let u_2 = from_limbs(&SIGNATURE.0) * &s_inverse % &n;
+        let generator = (
+            from_limbs(&Secp256k1::GENERATOR.0),
+            from_limbs(&Secp256k1::GENERATOR.1),
+        );
+        let public_key = (from_limbs(&PUBLIC_KEY.0), from_limbs(&PUBLIC_KEY.1));
+        let point = point::add_affine::<Secp256k1>(
+            &mul_affine::<Secp256k1>(&generator, &u_1),
+            &mul_affine::<Secp256k1>(&public_key, &u_2),
+        )
+        .unwrap();
+        assert_eq!(point.0 % &n, from_limbs(&SIGNATURE.0));
+
+        verify_signature::<F, P>(PUBLIC_KEY, MESSAGE_HASH, SIGNATURE);
+    }
+
+    fn test_ecdsa_verify_other_message<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let mut message_hash = MESSAGE_HASH;
+        message_hash[0] ^= 1;
+        verify_signature::<F, P>(PUBLIC_KEY, message_hash, SIGNATURE);
+    }
+
+    fn test_ecdsa_verify_other_r<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        // r + n is congruent to r, but isn't reduced.
+        let n = from_limbs(&Secp256k1::SCALAR_MODULUS);
+        let r = to_limbs(&(from_limbs(&SIGNATURE.0) + n));
+        verify_signature::<F, P>(PUBLIC_KEY, MESSAGE_HASH, (r, SIGNATURE.1));
+    }
+
+    fn test_ecdsa_verify_generator_public_key<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        // The private keys 1 and n - 1 have G and -G as public keys.
+        let n = from_limbs(&Secp256k1::SCALAR_MODULUS);
+        let generator = (
+            from_limbs(&Secp256k1::GENERATOR.0),
+            from_limbs(&Secp256k1::GENERATOR.1),
+        );
+        let nonce = from_limbs(&MESSAGE_HASH) + 1u32;
+        let r = mul_affine::<Secp256k1>(&generator, &nonce).unwrap().0 % &n;
+        for private_key in [BigUint::one(), &n - 1u32] {
+            let public_key =
+                mul_affine::<Secp256k1>(&generator, &private_key).unwrap();
+            let s = inverse(&nonce, &n)
+                * (from_limbs(&MESSAGE_HASH) + &r * private_key)
+                % &n;
+            verify_signature::<F, P>(
+                (to_limbs(&public_key.0), to_limbs(&public_key.1)),
+                MESSAGE_HASH,
+                (to_limbs(&r), to_limbs(&s)),
+            );
+        }
+    }
+
+    // Bls12-381 tests
+    batch_test_field_params!(
+        [test_ecdsa_verify, test_ecdsa_verify_generator_public_key],
+        [test_ecdsa_verify_other_message, test_ecdsa_verify_other_r] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test_field_params!(
+        [test_ecdsa_verify, test_ecdsa_verify_generator_public_key],
+        [test_ecdsa_verify_other_message, test_ecdsa_verify_other_r] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
@@ -0,0 +1,482 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Non-native elliptic curve arithmetic
+//!
+//! The formulas are the affine ones, which are incomplete: they neither
+//! handle the identity nor the addition of two points with the same
+//! x-coordinate. Additions constrain the x-coordinates to differ, without
+//! which the slope of the line through two equal points would be
+//! unconstrained.
+
+use super::{
+    field::{from_limbs, inverse, Combination, NonNativeElement},
+    EcdsaCurve,
+};
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use core::marker::PhantomData;
+use num_bigint::{BigInt, BigUint};
+use num_traits::{One, Zero};
+
+/// Affine coordinates of a point of an [`EcdsaCurve`], outside of the
+/// circuit.
+pub(crate) type Affine = (BigUint, BigUint);
+
+/// Represents a point of the [`EcdsaCurve`] `C` in the circuit, with
+/// [`NonNativeElement`] coordinates.
+#[derive(derivative::Derivative)]
+#[derivative(Clone, Copy, Debug)]
+pub struct NonNativePoint<C>
+where
+    C: EcdsaCurve,
+{
+    /// `X`-coordinate
+    x: NonNativeElement,
+
+    /// `Y`-coordinate
+    y: NonNativeElement,
+
+    /// Type Parameter Marker
+    __: PhantomData<C>,
+}
+
+impl<C> NonNativePoint<C>
+where
+    C: EcdsaCurve,
+{
+    /// Builds a new [`NonNativePoint`] from `X` and `Y` coordinates.
+    ///
+    /// The coordinates aren't constrained to be on the curve, which
+    /// [`StandardComposer::ecdsa_verify`] checks for the public key.
+    pub fn new(x: NonNativeElement, y: NonNativeElement) -> Self {
+        Self {
+            x,
+            y,
+            __: PhantomData,
+        }
+    }
+
+    /// Returns the `X`-coordinate of `self`.
+    pub fn x(&self) -> &NonNativeElement {
+        &self.x
+    }
+
+    /// Returns the `Y`-coordinate of `self`.
+    pub fn y(&self) -> &NonNativeElement {
+        &self.y
+    }
+}
+
+/// Returns the modulus of the base field of `C`.
+pub(crate) fn base_modulus<C>() -> BigUint
+where
+    C: EcdsaCurve,
+{
+    from_limbs(&C::BASE_MODULUS)
+}
+
+/// Returns the sum of the points `a` and `b` of `C`, `None` standing for the
+/// identity.
+pub(crate) fn add_affine<C>(
+    a: &Option<Affine>,
+    b: &Option<Affine>,
+) -> Option<Affine>
+where
+    C: EcdsaCurve,
+{
+    let p = base_modulus::<C>();
+    let ((x_1, y_1), (x_2, y_2)) = match (a, b) {
+        (None, _) => return b.clone(),
+        (_, None) => return a.clone(),
+        (Some(a), Some(b)) => (a, b),
+    };
+    let slope = if x_1 == x_2 {
+        if (y_1 + y_2) % &p == BigUint::zero() {
+            return None;
+        }
+        let a = from_limbs(&C::COEFF_A);
+        (3u32 * x_1 * x_1 + a) * inverse(&(2u32 * y_1), &p) % &p
+    } else {
+        (y_2 + &p - y_1) * inverse(&((x_2 + &p - x_1) % &p), &p) % &p
+    };
+    let x_3 = (&slope * &slope + 2u32 * &p - x_1 - x_2) % &p;
+    let y_3 = (slope * ((x_1 + &p - &x_3) % &p) + &p - y_1) % &p;
+    Some((x_3, y_3))
+}
+
+/// Returns `scalar` times the point `point` of `C`, `None` standing for the
+/// identity.
+pub(crate) fn mul_affine<C>(point: &Affine, scalar: &BigUint) -> Option<Affine>
+where
+    C: EcdsaCurve,
+{
+    let point = Some(point.clone());
+    (0..scalar.bits()).rev().fold(None, |acc, i| {
+        let acc = add_affine::<C>(&acc, &acc);
+        if scalar.bit(i) {
+            add_affine::<C>(&acc, &point)
+        } else {
+            acc
+        }
+    })
+}
+
+/// Returns a square root of `value` modulo the odd prime `p` if it's a
+/// quadratic residue, with the Tonelli-Shanks algorithm.
+fn sqrt(value: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let one = BigUint::one();
+    let value = value % p;
+    if value.is_zero() {
+        return Some(value);
+    }
+    let p_minus_one = p - &one;
+    let is_residue = |a: &BigUint| a.modpow(&(&p_minus_one >> 1u32), p) == one;
+    if !is_residue(&value) {
+        return None;
+    }
+    let two_adicity = p_minus_one.trailing_zeros().unwrap_or_default();
+    let odd = &p_minus_one >> two_adicity;
+    let mut non_residue = BigUint::from(2u32);
+    while is_residue(&non_residue) {
+        non_residue += 1u32;
+    }
+
+    let mut m = two_adicity;
+    let mut c = non_residue.modpow(&odd, p);
+    let mut t = value.modpow(&odd, p);
+    let mut root = value.modpow(&((&odd + 1u32) >> 1u32), p);
+    while t != one {
+        // Least i such that t^(2^i) = 1, which is less than m.
+        let mut i = 0;
+        let mut power = t.clone();
+        while power != one {
+            power = &power * &power % p;
+            i += 1;
+        }
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), p);
+        m = i;
+        c = &b * &b % p;
+        t = t * &c % p;
+        root = root * b % p;
+    }
+    Some(root)
+}
+
+/// Returns the point of `C` with the least positive x-coordinate, which is
+/// used as an offset whose discrete logarithm is unknown.
+pub(crate) fn offset_point<C>() -> Affine
+where
+    C: EcdsaCurve,
+{
+    let p = base_modulus::<C>();
+    let a = from_limbs(&C::COEFF_A);
+    let b = from_limbs(&C::COEFF_B);
+    let mut x = BigUint::one();
+    loop {
+        if let Some(y) = sqrt(&((&x * &x * &x + &a * &x + &b) % &p), &p) {
+            return (x, y);
+        }
+        x += 1u32;
+    }
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Adds the point `point` of `C` to the circuit description as a
+    /// constant [`NonNativePoint`].
+    pub(crate) fn nonnative_point_constant<C>(
+        &mut self,
+        point: &Affine,
+    ) -> NonNativePoint<C>
+    where
+        C: EcdsaCurve,
+    {
+        NonNativePoint::new(
+            self.nonnative_constant(&point.0),
+            self.nonnative_constant(&point.1),
+        )
+    }
+
+    /// Returns the affine coordinates of `point`, reduced modulo the base
+    /// field of `C`.
+    fn nonnative_point_value<C>(&self, point: NonNativePoint<C>) -> Affine
+    where
+        C: EcdsaCurve,
+    {
+        let p = base_modulus::<C>();
+        (
+            self.nonnative_value(point.x) % &p,
+            self.nonnative_value(point.y) % &p,
+        )
+    }
+
+    /// Constrains `point` to be on the curve `C`, i.e. its coordinates to
+    /// satisfy `y^2 = x^3 + a * x + b`.
+    pub(crate) fn assert_nonnative_on_curve<C>(
+        &mut self,
+        point: NonNativePoint<C>,
+    ) where
+        C: EcdsaCurve,
+    {
+        let p = base_modulus::<C>();
+        let square = self.nonnative_mul(point.x, point.x, &p);
+        let mut equation = Combination::default()
+            .product(1, point.y, point.y)
+            .product(-1, square, point.x)
+            .constant(-BigInt::from(from_limbs(&C::COEFF_B)));
+        let coeff_a = from_limbs(&C::COEFF_A);
+        if !coeff_a.is_zero() {
+            let coeff_a = self.nonnative_constant(&coeff_a);
+            equation = equation.product(-1, coeff_a, point.x);
+        }
+        self.assert_nonnative_zero(&equation, Some(&p), &[]);
+    }
+
+    /// Returns `2 * point`.
+    ///
+    /// The curve `C` has a prime order, so it has no point of order two and
+    /// `point` never has a zero y-coordinate, unless it isn't on the curve.
+    pub(crate) fn nonnative_point_double<C>(
+        &mut self,
+        point: NonNativePoint<C>,
+    ) -> NonNativePoint<C>
+    where
+        C: EcdsaCurve,
+    {
+        let p = base_modulus::<C>();
+        let value = Some(self.nonnative_point_value(point));
+        let doubled = add_affine::<C>(&value, &value).unwrap_or_default();
+        let (x, y) = value.unwrap_or_default();
+        let coeff_a = from_limbs(&C::COEFF_A);
+        let slope = (3u32 * &x * &x + &coeff_a) * inverse(&(2u32 * y), &p) % &p;
+        let slope = self.nonnative_witness(&slope);
+        let result = NonNativePoint::new(
+            self.nonnative_witness(&doubled.0),
+            self.nonnative_witness(&doubled.1),
+        );
+
+        // slope * 2y = 3x^2 + a
+        self.assert_nonnative_zero(
+            &Combination::default()
+                .product(2, slope, point.y)
+                .product(-3, point.x, point.x)
+                .constant(-BigInt::from(coeff_a)),
+            Some(&p),
+            &[slope],
+        );
+        self.assert_slope_line(slope, point, point, result);
+        result
+    }
+
+    /// Returns `a + b`, constraining `a` and `b` to have different
+    /// x-coordinates.
+    pub(crate) fn nonnative_point_add<C>(
+        &mut self,
+        a: NonNativePoint<C>,
+        b: NonNativePoint<C>,
+    ) -> NonNativePoint<C>
+    where
+        C: EcdsaCurve,
+    {
+        let p = base_modulus::<C>();
+        let (x_1, y_1) = self.nonnative_point_value(a);
+        let (x_2, y_2) = self.nonnative_point_value(b);
+        let inv = inverse(&((&x_2 + &p - &x_1) % &p), &p);
+        let slope = (&y_2 + &p - &y_1) * &inv % &p;
+        let sum = add_affine::<C>(&Some((x_1, y_1)), &Some((x_2, y_2)))
+            .unwrap_or_default();
+        let inv = self.nonnative_witness(&inv);
+        let slope = self.nonnative_witness(&slope);
+        let result = NonNativePoint::new(
+            self.nonnative_witness(&sum.0),
+            self.nonnative_witness(&sum.1),
+        );
+
+        // inv * (x_2 - x_1) = 1, so that the x-coordinates differ.
+        self.assert_nonnative_zero(
+            &Combination::default()
+                .product(1, inv, b.x)
+                .product(-1, inv, a.x)
+                .constant(-BigInt::from(1)),
+            Some(&p),
+            &[inv],
+        );
+        // slope * (x_2 - x_1) = y_2 - y_1
+        self.assert_nonnative_zero(
+            &Combination::default()
+                .product(1, slope, b.x)
+                .product(-1, slope, a.x)
+                .term(-1, b.y)
+                .term(1, a.y),
+            Some(&p),
+            &[slope],
+        );
+        self.assert_slope_line(slope, a, b, result);
+        result
+    }
+
+    /// Constrains `result` to be the reflection of the third intersection of
+    /// the curve with the line of slope `slope` through `a` and `b`, i.e.
+    /// `x_3 = slope^2 - x_1 - x_2` and `y_3 = slope * (x_1 - x_3) - y_1`.
+    fn assert_slope_line<C>(
+        &mut self,
+        slope: NonNativeElement,
+        a: NonNativePoint<C>,
+        b: NonNativePoint<C>,
+        result: NonNativePoint<C>,
+    ) where
+        C: EcdsaCurve,
+    {
+        let p = base_modulus::<C>();
+        self.assert_nonnative_zero(
+            &Combination::default()
+                .product(1, slope, slope)
+                .term(-1, a.x)
+                .term(-1, b.x)
+                .term(-1, result.x),
+            Some(&p),
+            &[result.x],
+        );
+        self.assert_nonnative_zero(
+            &Combination::default()
+                .product(1, slope, a.x)
+                .product(-1, slope, result.x)
+                .term(-1, a.y)
+                .term(-1, result.y),
+            Some(&p),
+            &[result.y],
+        );
+    }
+
+    /// Returns `a` if `bit == 1` and `b` if `bit == 0`, see
+    /// [`StandardComposer::conditional_select`].
+    pub(crate) fn nonnative_point_select<C>(
+        &mut self,
+        bit: Variable,
+        a: NonNativePoint<C>,
+        b: NonNativePoint<C>,
+    ) -> NonNativePoint<C>
+    where
+        C: EcdsaCurve,
+    {
+        let mut select = |a: NonNativeElement, b: NonNativeElement| {
+            let mut limbs = *a.limbs();
+            for (limb, b_limb) in limbs.iter_mut().zip(b.limbs()) {
+                *limb = self.conditional_select(bit, *limb, *b_limb);
+            }
+            limbs
+        };
+        let x = select(a.x, b.x);
+        let y = select(a.y, b.y);
+        NonNativePoint::new(NonNativeElement::new(x), NonNativeElement::new(y))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test,
+        constraint_system::{ecdsa::Secp256k1, helper::*},
+        proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use core::iter::repeat;
+
+    /// Returns the generator of secp256k1.
+    fn generator() -> Affine {
+        (
+            from_limbs(&Secp256k1::GENERATOR.0),
+            from_limbs(&Secp256k1::GENERATOR.1),
+        )
+    }
+
+    /// Constrains `point` to have the coordinates of `expected`.
+    fn assert_point<F, P>(
+        composer: &mut StandardComposer<F, P>,
+        point: NonNativePoint<Secp256k1>,
+        expected: &Affine,
+    ) where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        for (a, b) in [(point.x, &expected.0), (point.y, &expected.1)] {
+            let digits = b.to_u64_digits().into_iter().chain(repeat(0));
+            for (limb, value) in a.limbs().iter().zip(digits) {
+                composer.constrain_to_constant(*limb, F::from(value), None);
+            }
+        }
+    }
+
+    fn test_nonnative_point_arithmetic<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let g = composer
+                    .nonnative_point_constant::<Secp256k1>(&generator());
+                let double = composer.nonnative_point_double(g);
+                let triple = composer.nonnative_point_add(double, g);
+                assert_point(
+                    composer,
+                    triple,
+                    &mul_affine::<Secp256k1>(&generator(), &3u32.into())
+                        .unwrap(),
+                );
+            },
+            1024,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the point isn't on the curve.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let (x, y) = generator();
+                let point = composer
+                    .nonnative_point_constant::<Secp256k1>(&(x, y + 1u32));
+                composer.assert_nonnative_on_curve(point);
+            },
+            512,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the points have the same x-coordinate.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let g = composer
+                    .nonnative_point_constant::<Secp256k1>(&generator());
+                composer.nonnative_point_add(g, g);
+            },
+            1024,
+        );
+        assert!(res.is_err());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_nonnative_point_arithmetic],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_nonnative_point_arithmetic],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
@@ -0,0 +1,410 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Arithmetic Expression Trees
+//!
+//! An [`Expr`] describes an arithmetic expression over circuit
+//! [`Variable`]s which [`StandardComposer::eval_expr`] compiles into gates.
+//! Linear parts of the tree are accumulated without emitting any gate and
+//! multiplications are fused with the surrounding additions, so a whole
+//! `q_m·a·b + q_l·a + q_r·b + q_4·d + q_c` expression costs a single
+//! arithmetic gate.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use core::ops::{Add, Mul, Sub};
+
+/// Arithmetic expression over circuit [`Variable`]s.
+#[derive(Clone, Debug)]
+pub enum Expr<F>
+where
+    F: PrimeField,
+{
+    /// Constant value.
+    Const(F),
+
+    /// Variable of the circuit.
+    Var(Variable),
+
+    /// Sum of two expressions.
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+
+    /// Product of two expressions.
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+
+    /// Difference of two expressions.
+    Sub(Box<Expr<F>>, Box<Expr<F>>),
+}
+
+impl<F> From<Variable> for Expr<F>
+where
+    F: PrimeField,
+{
+    fn from(var: Variable) -> Self {
+        Self::Var(var)
+    }
+}
+
+impl<F> Add for Expr<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F> Sub for Expr<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F> Mul for Expr<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+/// Partially compiled expression which hasn't been assigned to a gate yet.
+enum Lowered<F>
+where
+    F: PrimeField,
+{
+    /// `sum coeff_i * var_i + constant`
+    Linear(Vec<(F, Variable)>, F),
+
+    /// `q_m * a * b + sum coeff_i * var_i + constant`
+    Quadratic {
+        q_m: F,
+        a: Variable,
+        b: Variable,
+        linear: Vec<(F, Variable)>,
+        constant: F,
+    },
+}
+
+impl<F> Lowered<F>
+where
+    F: PrimeField,
+{
+    fn scale(self, k: F) -> Self {
+        let scale_terms = |terms: Vec<(F, Variable)>| {
+            terms.into_iter().map(|(c, v)| (c * k, v)).collect()
+        };
+        match self {
+            Self::Linear(terms, constant) => {
+                Self::Linear(scale_terms(terms), constant * k)
+            }
+            Self::Quadratic {
+                q_m,
+                a,
+                b,
+                linear,
+                constant,
+            } => Self::Quadratic {
+                q_m: q_m * k,
+                a,
+                b,
+                linear: scale_terms(linear),
+                constant: constant * k,
+            },
+        }
+    }
+
+    fn as_constant(&self) -> Option<F> {
+        match self {
+            Self::Linear(terms, constant) if terms.is_empty() => {
+                Some(*constant)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Adds `coeff * var` to `terms`, merging it with an existing term on the
+/// same variable.
+fn push_term<F>(terms: &mut Vec<(F, Variable)>, coeff: F, var: Variable)
+where
+    F: PrimeField,
+{
+    match terms.iter_mut().find(|(_, v)| *v == var) {
+        Some((c, _)) => *c += coeff,
+        None => terms.push((coeff, var)),
+    }
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Adds the gates computing `expr` to the circuit description and
+    /// returns the [`Variable`] holding its value.
+    ///
+    /// Additions, subtractions and multiplications by constants don't cost
+    /// any gate by themselves: they're accumulated until a multiplication of
+    /// two non-constant operands or the end of the expression forces them
+    /// into a gate, where they're fused with the multiplication whenever the
+    /// wires allow it.
+    pub fn eval_expr(&mut self, expr: &Expr<F>) -> Variable {
+        let lowered = self.lower_expr(expr);
+        self.materialize_expr(lowered)
+    }
+
+    fn lower_expr(&mut self, expr: &Expr<F>) -> Lowered<F> {
+        match expr {
+            Expr::Const(c) => Lowered::Linear(vec![], *c),
+            Expr::Var(v) => Lowered::Linear(vec![(F::one(), *v)], F::zero()),
+            Expr::Add(lhs, rhs) => {
+                let lhs = self.lower_expr(lhs);
+                let rhs = self.lower_expr(rhs);
+                self.add_lowered(lhs, rhs)
+            }
+            Expr::Sub(lhs, rhs) => {
+                let lhs = self.lower_expr(lhs);
+                let rhs = self.lower_expr(rhs).scale(-F::one());
+                self.add_lowered(lhs, rhs)
+            }
+            Expr::Mul(lhs, rhs) => {
+                let lhs = self.lower_expr(lhs);
+                let rhs = self.lower_expr(rhs);
+                self.mul_lowered(lhs, rhs)
+            }
+        }
+    }
+
+    fn add_lowered(&mut self, lhs: Lowered<F>, rhs: Lowered<F>) -> Lowered<F> {
+        // At most one multiplication fits in a gate, so a second one has to
+        // be assigned first.
+        let (quadratic, (terms, c)) = match (lhs, rhs) {
+            (Lowered::Linear(mut lhs, c_l), Lowered::Linear(rhs, c_r)) => {
+                for (coeff, var) in rhs {
+                    push_term(&mut lhs, coeff, var);
+                }
+                return Lowered::Linear(lhs, c_l + c_r);
+            }
+            (q @ Lowered::Quadratic { .. }, Lowered::Linear(terms, c))
+            | (Lowered::Linear(terms, c), q @ Lowered::Quadratic { .. }) => {
+                (q, (terms, c))
+            }
+            (lhs, rhs) => {
+                let rhs = self.materialize_expr(rhs);
+                (lhs, (vec![(F::one(), rhs)], F::zero()))
+            }
+        };
+        match quadratic {
+            Lowered::Quadratic {
+                q_m,
+                a,
+                b,
+                mut linear,
+                constant,
+            } => {
+                for (coeff, var) in terms {
+                    push_term(&mut linear, coeff, var);
+                }
+                Lowered::Quadratic {
+                    q_m,
+                    a,
+                    b,
+                    linear,
+                    constant: constant + c,
+                }
+            }
+            Lowered::Linear(..) => unreachable!(),
+        }
+    }
+
+    fn mul_lowered(&mut self, lhs: Lowered<F>, rhs: Lowered<F>) -> Lowered<F> {
+        if let Some(k) = lhs.as_constant() {
+            return rhs.scale(k);
+        }
+        if let Some(k) = rhs.as_constant() {
+            return lhs.scale(k);
+        }
+        let (k_a, a, c_a) = self.single_term(lhs);
+        let (k_b, b, c_b) = self.single_term(rhs);
+        // (k_a·a + c_a)·(k_b·b + c_b)
+        let mut linear = Vec::new();
+        push_term(&mut linear, k_a * c_b, a);
+        push_term(&mut linear, k_b * c_a, b);
+        Lowered::Quadratic {
+            q_m: k_a * k_b,
+            a,
+            b,
+            linear,
+            constant: c_a * c_b,
+        }
+    }
+
+    /// Rewrites `lowered` as `k·v + c`, assigning it to a new variable if it
+    /// isn't already of that form.
+    fn single_term(&mut self, lowered: Lowered<F>) -> (F, Variable, F) {
+        match lowered {
+            Lowered::Linear(terms, c) if terms.len() == 1 => {
+                (terms[0].0, terms[0].1, c)
+            }
+            lowered => (F::one(), self.materialize_expr(lowered), F::zero()),
+        }
+    }
+
+    fn materialize_expr(&mut self, lowered: Lowered<F>) -> Variable {
+        match lowered {
+            Lowered::Linear(terms, c)
+                if terms.len() == 1
+                    && terms[0].0 == F::one()
+                    && c.is_zero() =>
+            {
+                terms[0].1
+            }
+            Lowered::Linear(terms, c) => self.weighted_sum(&terms, c),
+            Lowered::Quadratic {
+                q_m,
+                a,
+                b,
+                linear,
+                constant,
+            } => {
+                let zero = self.zero_var;
+                let (mut q_l, mut q_r) = (F::zero(), F::zero());
+                let mut rest = Vec::new();
+                for (coeff, var) in linear {
+                    if var == a {
+                        q_l += coeff;
+                    } else if var == b {
+                        q_r += coeff;
+                    } else {
+                        rest.push((coeff, var));
+                    }
+                }
+                let (q_4, d) = match rest.len() {
+                    0 => (F::zero(), zero),
+                    1 => rest[0],
+                    _ => (F::one(), self.weighted_sum(&rest, F::zero())),
+                };
+                self.arithmetic_gate(|gate| {
+                    gate.witness(a, b, None)
+                        .mul(q_m)
+                        .add(q_l, q_r)
+                        .fan_in_3(q_4, d)
+                        .constant(constant)
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test, commitment::HomomorphicCommitment,
+        constraint_system::helper::*, proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    fn test_eval_expr<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(4u64));
+                let b = composer.add_input(F::from(5u64));
+                let c = composer.add_input(F::from(6u64));
+
+                // (a + 3) * b - c
+                let expr = (Expr::from(a) + Expr::Const(F::from(3u64)))
+                    * Expr::from(b)
+                    - Expr::from(c);
+                let out = composer.eval_expr(&expr);
+                composer.constrain_to_constant(out, F::from(29u64), None);
+
+                // (a - b) * (a + b * 2) + (c * c) - 1
+                let expr = (Expr::from(a) - Expr::from(b))
+                    * (Expr::from(a)
+                        + Expr::from(b) * Expr::Const(F::from(2u64)))
+                    + Expr::from(c) * Expr::from(c)
+                    - Expr::Const(F::one());
+                let out = composer.eval_expr(&expr);
+                composer.constrain_to_constant(out, F::from(21u64), None);
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_eval_expr_gate_count<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::from(4u64));
+        let b = composer.add_input(F::from(5u64));
+        let c = composer.add_input(F::from(6u64));
+        let zero = composer.zero_var();
+
+        // Naive emission: one gate per node of `(a + 3) * b - c`.
+        let start = composer.n;
+        let a_3 = composer.arithmetic_gate(|gate| {
+            gate.witness(a, zero, None)
+                .add(F::one(), F::zero())
+                .constant(F::from(3u64))
+        });
+        let prod = composer
+            .arithmetic_gate(|gate| gate.witness(a_3, b, None).mul(F::one()));
+        let naive = composer.arithmetic_gate(|gate| {
+            gate.witness(prod, c, None).add(F::one(), -F::one())
+        });
+        let naive_gates = composer.n - start;
+
+        let start = composer.n;
+        let expr = (Expr::from(a) + Expr::Const(F::from(3u64))) * Expr::from(b)
+            - Expr::from(c);
+        let fused = composer.eval_expr(&expr);
+        let fused_gates = composer.n - start;
+
+        assert_eq!(naive_gates, 3);
+        assert_eq!(fused_gates, 1);
+        assert_eq!(composer.variables[&naive], composer.variables[&fused]);
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_eval_expr, test_eval_expr_gate_count],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_eval_expr, test_eval_expr_gate_count],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
@@ -0,0 +1,294 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Groth16 Gates
+//!
+//! Verification of [`ark_groth16`] proofs over a BLS12 curve in a circuit
+//! over the base field of the curve, such as proofs over BLS12-377 in a
+//! circuit over the scalar field of BW6-761, with the
+//! [pairing gates](super::pairing).
+
+use crate::constraint_system::{
+    pairing::{G1Var, G2Var},
+    StandardComposer, Variable,
+};
+use ark_ec::{
+    bls12::{Bls12, Bls12Parameters, G1Affine},
+    AffineCurve, ModelParameters, PairingEngine, ProjectiveCurve,
+    TEModelParameters,
+};
+use ark_ff::{BigInteger, One, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+
+/// Represents a Groth16 proof over the BLS12 curve `B` in the circuit.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""), Debug(bound = ""))]
+pub struct Groth16ProofVar<B>
+where
+    B: Bls12Parameters,
+{
+    /// `A` element in `G1`
+    a: G1Var<B>,
+
+    /// `B` element in `G2`
+    b: G2Var<B>,
+
+    /// `C` element in `G1`
+    c: G1Var<B>,
+}
+
+impl<B> Groth16ProofVar<B>
+where
+    B: Bls12Parameters,
+{
+    /// Returns the `A` element of `self`.
+    pub fn a(&self) -> &G1Var<B> {
+        &self.a
+    }
+
+    /// Returns the `B` element of `self`.
+    pub fn b(&self) -> &G2Var<B> {
+        &self.b
+    }
+
+    /// Returns the `C` element of `self`.
+    pub fn c(&self) -> &G1Var<B> {
+        &self.c
+    }
+}
+
+/// Returns the point of the curve of `G1` with the least positive
+/// x-coordinate, which is used as an offset whose discrete logarithm is
+/// unknown.
+fn offset_point<B>() -> G1Affine<B>
+where
+    B: Bls12Parameters,
+{
+    let mut x = B::Fp::one();
+    loop {
+        if let Some(point) = G1Affine::<B>::get_point_from_x(x, false) {
+            return point;
+        }
+        x += B::Fp::one();
+    }
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Adds the Groth16 proof `proof` to the circuit as a witness
+    /// [`Groth16ProofVar`], constraining its points to be on the curve.
+    pub fn add_groth16_proof<B>(
+        &mut self,
+        proof: &Proof<Bls12<B>>,
+    ) -> Groth16ProofVar<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        Groth16ProofVar {
+            a: self.add_g1_input::<B>(proof.a),
+            b: self.add_g2_input::<B>(proof.b),
+            c: self.add_g1_input::<B>(proof.c),
+        }
+    }
+
+    /// Constrains `proof` to be a valid Groth16 proof for the public inputs
+    /// `public_inputs` under the verifying key `vk`, which is a constant of
+    /// the circuit, as [`ark_groth16::verify_proof`] checks it.
+    ///
+    /// This checks `e(A, B) * e(-vk_x, gamma) * e(-C, delta) = e(alpha,
+    /// beta)` where `vk_x = gamma_abc_0 + sum x_i * gamma_abc_(i + 1)` for the
+    /// public inputs `x_i`, `e(alpha, beta)` being computed out of the
+    /// circuit. The public inputs are constrained to fit in the bit length of
+    /// the scalar field of `B`, and taken modulo its order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of public inputs doesn't match `vk`.
+    pub fn groth16_verify<B>(
+        &mut self,
+        vk: &VerifyingKey<Bls12<B>>,
+        proof: Groth16ProofVar<B>,
+        public_inputs: &[Variable],
+    ) where
+        B: Bls12Parameters<Fp = F>,
+    {
+        assert_eq!(
+            public_inputs.len() + 1,
+            vk.gamma_abc_g1.len(),
+            "The verifying key doesn't match the number of public inputs"
+        );
+        let vk_x = self.g1_fixed_base_msm(&vk.gamma_abc_g1, public_inputs);
+        let minus_vk_x = self.g1_neg(vk_x);
+        let minus_c = self.g1_neg(proof.c);
+        self.assert_pairing_product(
+            &[(proof.a, proof.b)],
+            &[(minus_vk_x, vk.gamma_g2), (minus_c, vk.delta_g2)],
+            Bls12::<B>::pairing(vk.alpha_g1, vk.beta_g2),
+        );
+    }
+
+    /// Returns `bases[0] + sum scalars_i * bases[i + 1]` for constant points
+    /// `bases` of `G1`, the scalars being constrained to fit in the bit
+    /// length of the scalar field of `B`.
+    ///
+    /// The accumulator starts from `bases[0] + H`, `H` being the
+    /// [`offset_point`] of `B`, whose discrete logarithm is unknown. Every
+    /// bit of the scalars selects whether a multiple of a base is added to
+    /// it, and `H` is subtracted at the end. None of the additions is then
+    /// between points with the same x-coordinate, but the last one if the
+    /// result is the identity or `-2 * H`.
+    fn g1_fixed_base_msm<B>(
+        &mut self,
+        bases: &[G1Affine<B>],
+        scalars: &[Variable],
+    ) -> G1Var<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let num_bits =
+            <B::G1Parameters as ModelParameters>::ScalarField::size_in_bits();
+        let offset = offset_point::<B>();
+        let mut acc = self.g1_constant::<B>(bases[0] + offset);
+        for (scalar, base) in scalars.iter().zip(&bases[1..]) {
+            let value = self.value_of_var(*scalar).into_repr();
+            let bits = (0..num_bits)
+                .map(|i| {
+                    let bit = self.add_input(F::from(value.get_bit(i)));
+                    self.boolean_gate(bit)
+                })
+                .collect::<Vec<_>>();
+            let mut power = F::one();
+            let terms = bits
+                .iter()
+                .map(|bit| {
+                    let term = (power, *bit);
+                    power.double_in_place();
+                    term
+                })
+                .collect::<Vec<_>>();
+            let packed = self.weighted_sum(&terms, F::zero());
+            self.assert_equal(packed, *scalar);
+
+            let mut multiple = base.into_projective();
+            for bit in bits {
+                let addend = self.g1_constant::<B>(multiple.into_affine());
+                let sum = self.g1_add(acc, addend);
+                acc = self.g1_select(bit, sum, acc);
+                multiple.double_in_place();
+            }
+        }
+        let correction = self.g1_constant::<B>(-offset);
+        self.g1_add(acc, correction)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_377::{Bls12_377, Fq, Fr, Parameters};
+    use ark_ed_on_bw6_761::EdwardsParameters;
+    use ark_ff::{Field, UniformRand};
+    use ark_groth16::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key,
+        verify_proof,
+    };
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+    };
+    use rand_core::OsRng;
+
+    /// Circuit proving the knowledge of a square root `x` of the public
+    /// input `y`, whose cube is the public input `z`.
+    struct SquareRoot {
+        /// Witness
+        x: Option<Fr>,
+    }
+
+    impl ConstraintSynthesizer<Fr> for SquareRoot {
+        fn generate_constraints(
+            self,
+            cs: ConstraintSystemRef<Fr>,
+        ) -> Result<(), SynthesisError> {
+            let value = |power| {
+                self.x
+                    .map(|x| x.pow([power]))
+                    .ok_or(SynthesisError::AssignmentMissing)
+            };
+            let y = cs.new_input_variable(|| value(2))?;
+            let z = cs.new_input_variable(|| value(3))?;
+            let x = cs.new_witness_variable(|| value(1))?;
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + y)?;
+            cs.enforce_constraint(lc!() + x, lc!() + y, lc!() + z)
+        }
+    }
+
+    /// Verifies in a composer the Groth16 proof `proof` for the public
+    /// inputs `public_inputs`, and checks that the circuit is satisfied.
+    fn verify(
+        vk: &VerifyingKey<Bls12_377>,
+        proof: &Proof<Bls12_377>,
+        public_inputs: &[Fr],
+    ) {
+        let mut composer = StandardComposer::<Fq, EdwardsParameters>::new();
+        let proof = composer.add_groth16_proof(proof);
+        let public_inputs = public_inputs
+            .iter()
+            .map(|input| {
+                composer.add_input(Fq::from_le_bytes_mod_order(
+                    &input.into_repr().to_bytes_le(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        composer.groth16_verify(vk, proof, &public_inputs);
+        composer.check_circuit_satisfied();
+    }
+
+    /// Returns a verifying key, a proof, and its public inputs.
+    fn prove() -> (VerifyingKey<Bls12_377>, Proof<Bls12_377>, [Fr; 2]) {
+        let pk = generate_random_parameters::<Bls12_377, _, _>(
+            SquareRoot { x: None },
+            &mut OsRng,
+        )
+        .unwrap();
+        let x = Fr::rand(&mut OsRng);
+        let proof =
+            create_random_proof(SquareRoot { x: Some(x) }, &pk, &mut OsRng)
+                .unwrap();
+        (pk.vk, proof, [x.square(), x.pow([3])])
+    }
+
+    #[test]
+    fn test_groth16_verify() {
+        let (vk, proof, public_inputs) = prove();
+        assert!(verify_proof(
+            &prepare_verifying_key(&vk),
+            &proof,
+            &public_inputs
+        )
+        .unwrap());
+        verify(&vk, &proof, &public_inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn test_groth16_verify_other_input() {
+        let (vk, proof, mut public_inputs) = prove();
+        public_inputs[1] += Fr::one();
+        verify(&vk, &proof, &public_inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn test_groth16_verify_tampered_proof() {
+        let (vk, mut proof, public_inputs) = prove();
+        proof.a = proof.a + G1Affine::<Parameters>::prime_subgroup_generator();
+        verify(&vk, &proof, &public_inputs);
+    }
+}
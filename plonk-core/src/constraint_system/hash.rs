@@ -55,6 +55,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
 
         self.perm
             .add_variables_to_map(vars[0], vars[1], w4_var, vars[2], self.n);
@@ -108,6 +109,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
 
         self.perm
             .add_variables_to_map(vars[0], vars[1], w4_var, vars[2], self.n);
@@ -121,8 +123,8 @@ where
 mod test {
     use super::*;
     use crate::{
-        batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::gadget_tester,
+        batch_test, constraint_system::helper::gadget_tester,
+        proof_system::ProverCommitment,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -131,7 +133,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -6,9 +6,8 @@
 
 use super::StandardComposer;
 use crate::{
-    commitment::HomomorphicCommitment,
     error::{to_pc_error, Error},
-    proof_system::{Prover, Verifier},
+    proof_system::{Prover, ProverCommitment, Verifier},
 };
 use ark_ec::TEModelParameters;
 use ark_ff::PrimeField;
@@ -42,7 +41,7 @@ pub(crate) fn gadget_tester<F, P, PC>(
 where
     F: PrimeField,
     P: TEModelParameters<BaseField = F>,
-    PC: HomomorphicCommitment<F>,
+    PC: ProverCommitment<F>,
 {
     // Common View
     let universal_params =
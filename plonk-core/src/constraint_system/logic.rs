@@ -249,6 +249,7 @@ where
             self.q_fixed_group_add.push(F::zero());
             self.q_variable_group_add.push(F::zero());
             self.q_lookup.push(F::zero());
+            self.q_multiset.push(F::zero());
 
             // add high degree selectors
             self.q_hl.push(F::zero());
@@ -277,6 +278,7 @@ where
         self.q_fixed_group_add.push(F::zero());
         self.q_variable_group_add.push(F::zero());
         self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::zero());
         self.q_c.push(F::zero());
         self.q_logic.push(F::zero());
 
@@ -359,8 +361,8 @@ where
 #[cfg(test)]
 mod test {
     use crate::{
-        batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*, constraint_system::StandardComposer,
+        batch_test, constraint_system::helper::*,
+        constraint_system::StandardComposer, proof_system::ProverCommitment,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -370,7 +372,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // Should pass since the XOR result is correct and the bit-num is even.
         let res = gadget_tester::<F, P, PC>(
@@ -446,7 +448,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // Should fail since the bit-num is odd.
         let _ = gadget_tester::<F, P, PC>(
@@ -4,9 +4,13 @@
 //
 // Copyright (c) ZK-Garage. All rights reserved.
 
-use crate::constraint_system::{StandardComposer, Variable};
+use crate::{
+    constraint_system::{StandardComposer, Variable},
+    lookup::lookup_table::MAP_TABLE_TAG,
+};
 use ark_ec::TEModelParameters;
 use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, UVPolynomial};
 
 impl<F, P> StandardComposer<F, P>
 where
@@ -55,6 +59,7 @@ where
         // For a lookup gate, only one selector poly is
         // turned on as the output is inputted directly
         self.q_lookup.push(F::one());
+        self.q_multiset.push(F::zero());
 
         if let Some(pi) = pi {
             self.add_pi(self.n, &pi).unwrap_or_else(|_| {
@@ -68,14 +73,118 @@ where
 
         c
     }
+
+    /// Looks up the value associated to `key` in the rows of the composer's
+    /// lookup table inserted with
+    /// [`LookupTable::insert_map_row`](crate::lookup::LookupTable::insert_map_row)
+    /// and returns it.
+    ///
+    /// The table is committed to during preprocessing and the lookup is
+    /// checked by the plookup argument, so the cost is two gates regardless
+    /// of the table size. If `key` isn't in the table the returned value is
+    /// zero and the proof won't verify.
+    pub fn lookup_map(&mut self, key: Variable) -> Variable {
+        let tag = F::from(MAP_TABLE_TAG);
+        let value = self
+            .lookup_table
+            .lookup(self.value_of_var(key), F::zero(), tag)
+            .unwrap_or_else(|_| F::zero());
+        let value = self.add_input(value);
+        self.assert_map_entry(key, value);
+        value
+    }
+
+    /// Constrains `(key, value)` to be one of the rows of the composer's
+    /// lookup table inserted with
+    /// [`LookupTable::insert_map_row`](crate::lookup::LookupTable::insert_map_row).
+    pub fn assert_map_entry(&mut self, key: Variable, value: Variable) {
+        let zero = self.zero_var;
+        // The tag is fixed so that rows of other tables can't be used.
+        let tag =
+            self.add_witness_to_circuit_description(F::from(MAP_TABLE_TAG));
+        self.lookup_gate(key, zero, value, Some(tag), None);
+    }
+
+    /// Looks up the value associated to `key` in a small constant `table` of
+    /// `(key, value)` pairs and returns it.
+    ///
+    /// The lookup is performed by interpolating a polynomial through the table
+    /// entries and evaluating it at `key` in-circuit, after asserting that
+    /// `key` is one of the table keys with
+    /// [`StandardComposer::assert_in_set`]. The cost is roughly two gates per
+    /// table entry, so this is only meant for tiny tables such as 4-bit
+    /// S-boxes; bigger tables should use [`StandardComposer::lookup_gate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `table` is empty or contains duplicated keys.
+    pub fn lookup_const_table(
+        &mut self,
+        key: Variable,
+        table: &[(F, F)],
+    ) -> Variable {
+        let keys = table.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+        self.assert_in_set(key, &keys);
+
+        let coeffs = interpolate(table).coeffs;
+        let zero = self.zero_var;
+        // Evaluate the interpolated polynomial at `key` using Horner's rule.
+        match coeffs.len() {
+            0 => zero,
+            1 => self.arithmetic_gate(|gate| {
+                gate.witness(zero, zero, None).constant(coeffs[0])
+            }),
+            len => {
+                let mut acc = self.arithmetic_gate(|gate| {
+                    gate.witness(key, zero, None)
+                        .add(coeffs[len - 1], F::zero())
+                        .constant(coeffs[len - 2])
+                });
+                for coeff in coeffs[..len - 2].iter().rev() {
+                    acc = self.arithmetic_gate(|gate| {
+                        gate.witness(acc, key, None)
+                            .mul(F::one())
+                            .constant(*coeff)
+                    });
+                }
+                acc
+            }
+        }
+    }
+}
+
+/// Computes the Lagrange interpolation polynomial going through all the `(x,
+/// y)` points of `table`.
+fn interpolate<F>(table: &[(F, F)]) -> DensePolynomial<F>
+where
+    F: PrimeField,
+{
+    let mut result = DensePolynomial::<F>::from_coefficients_vec(vec![]);
+    for (i, (x_i, y_i)) in table.iter().enumerate() {
+        let mut basis = DensePolynomial::from_coefficients_vec(vec![*y_i]);
+        for (j, (x_j, _)) in table.iter().enumerate() {
+            if i != j {
+                let denominator = (*x_i - x_j)
+                    .inverse()
+                    .expect("Lookup table keys must be distinct");
+                basis = &basis
+                    * &DensePolynomial::from_coefficients_vec(vec![
+                        -*x_j * denominator,
+                        denominator,
+                    ]);
+            }
+        }
+        result += &basis;
+    }
+    result
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::{
-        batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*, lookup::LookupTable,
+        batch_test, constraint_system::helper::*, lookup::LookupTable,
+        proof_system::ProverCommitment,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
@@ -85,7 +194,7 @@ mod test {
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         let res = gadget_tester::<F, P, PC>(
             |composer: &mut StandardComposer<F, P>| {
@@ -148,10 +257,110 @@ mod test {
         assert!(res.is_ok(), "{:?}", res.err().unwrap());
     }
 
+    fn test_lookup_map<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // x -> x^2 + 1 for x in 0..16
+        let entries = (0..16u64)
+            .map(|x| (F::from(x), F::from(x * x + 1)))
+            .collect::<Vec<_>>();
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let entries = (0..16u64)
+                    .map(|x| (F::from(x), F::from(x * x + 1)))
+                    .collect::<Vec<_>>();
+                composer.lookup_table = LookupTable::map_table(&entries);
+                for x in [0u64, 3, 9, 15] {
+                    let key = composer.add_input(F::from(x));
+                    let value = composer.lookup_map(key);
+                    assert_eq!(
+                        composer.value_of_var(value),
+                        entries[x as usize].1
+                    );
+                    composer.constrain_to_constant(
+                        value,
+                        F::from(x * x + 1),
+                        None,
+                    );
+                }
+            },
+            64,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+        assert_eq!(LookupTable::map_table(&entries).size(), 16);
+
+        // An absent pair is rejected.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let entries = (0..16u64)
+                    .map(|x| (F::from(x), F::from(x * x + 1)))
+                    .collect::<Vec<_>>();
+                composer.lookup_table = LookupTable::map_table(&entries);
+                let key = composer.add_input(F::from(3u64));
+                let value = composer.add_input(F::from(11u64));
+                composer.assert_map_entry(key, value);
+            },
+            64,
+        );
+        assert!(res.is_err());
+
+        // So is a key outside of the table.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let entries = (0..16u64)
+                    .map(|x| (F::from(x), F::from(x * x + 1)))
+                    .collect::<Vec<_>>();
+                composer.lookup_table = LookupTable::map_table(&entries);
+                let key = composer.add_input(F::from(16u64));
+                composer.lookup_map(key);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_lookup_const_table<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let table = [(1u64, 12u64), (2, 5), (7, 9), (11, 0)]
+                    .map(|(k, v)| (F::from(k), F::from(v)));
+                for (k, v) in table {
+                    let key = composer.add_input(k);
+                    let value = composer.lookup_const_table(key, &table);
+                    assert_eq!(composer.value_of_var(value), v);
+                    composer.constrain_to_constant(value, v, None);
+                }
+            },
+            64,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let table = [(1u64, 12u64), (2, 5), (7, 9), (11, 0)]
+                    .map(|(k, v)| (F::from(k), F::from(v)));
+                let key = composer.add_input(F::from(3u64));
+                composer.lookup_const_table(key, &table);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
     // Bls12-381 tests
     batch_test!(
         [
-            test_plookup_xor
+            test_plookup_xor,
+            test_lookup_map,
+            test_lookup_const_table
         ],
         [] => (
             Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
@@ -161,7 +370,9 @@ mod test {
     // Bls12-377 tests
     batch_test!(
         [
-            test_plookup_xor
+            test_plookup_xor,
+            test_lookup_map,
+            test_lookup_const_table
         ],
         [] => (
             Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
@@ -8,21 +8,34 @@
 //! [`StandardComposer`], as well as the circuit tools and abstractions, used by
 //! the Composer to generate, build, preprocess circuits.
 
+mod aes;
 mod arithmetic;
 mod boolean;
+mod comparison;
+mod counter;
+mod crc;
+mod expr;
 mod hash;
 mod logic;
 mod lookup;
 mod range;
+mod report;
+mod set;
 
 pub(crate) mod composer;
 pub(crate) mod helper;
 pub(crate) mod variable;
 
+pub mod bls_sig;
 pub mod ecc;
+pub mod ecdsa;
+pub mod groth16;
+pub mod pairing;
 
 pub(crate) use hash::SBOX_ALPHA;
 pub(crate) use variable::WireData;
 
 pub use composer::StandardComposer;
+pub use counter::{ConstraintCounter, GadgetScope};
+pub use expr::Expr;
 pub use variable::Variable;
@@ -0,0 +1,977 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Pairing Gates
+//!
+//! Pairings of a BLS12 curve computed in a circuit over the base field of
+//! the curve, such as BLS12-377 in a circuit over the scalar field of
+//! BW6-761. The points of `G1` have their coordinates in the circuit field
+//! and the points of `G2` in its quadratic extension. The elements of the
+//! degree-12 extension are kept as their 12 coefficients over the circuit
+//! field, in the order of [`Field::from_base_prime_field_elems`], and every
+//! product of two coefficients costs a gate.
+//!
+//! The Miller loop and the final exponentiation follow the ones of
+//! [`Bls12`], so that every intermediate value matches.
+//!
+//! [`Bls12`]: ark_ec::bls12::Bls12
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::{
+    bls12::{Bls12Parameters, G1Affine, G2Affine, G2Prepared, TwistType},
+    models::SWModelParameters,
+    TEModelParameters,
+};
+use ark_ff::{
+    fields::Fp2Parameters, BitIteratorBE, Field, Fp12, Fp2, One, PrimeField,
+    Zero,
+};
+use core::marker::PhantomData;
+
+/// Element of the quadratic extension of the base field of `B`.
+type Fqe<B> = Fp2<<B as Bls12Parameters>::Fp2Params>;
+
+/// Element of the target field of the pairing of `B`.
+pub type Fqk<B> = Fp12<<B as Bls12Parameters>::Fp12Params>;
+
+/// Coefficients of an element of [`Fqk`] in the circuit.
+type Fp12Var = [Variable; 12];
+
+/// Coefficients of an element of [`Fqk`] as a sparse operand of a product,
+/// `Some((c, v))` standing for the coefficient `c * v`.
+type Fp12Operand<F> = [Option<(F, Variable)>; 12];
+
+/// Represents a point of `G1` of the BLS12 curve `B` in the circuit, with
+/// affine coordinates.
+///
+/// The identity has no affine coordinates, so it can't be represented.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""), Debug(bound = ""))]
+pub struct G1Var<B>
+where
+    B: Bls12Parameters,
+{
+    /// `X`-coordinate
+    x: Variable,
+
+    /// `Y`-coordinate
+    y: Variable,
+
+    /// Type Parameter Marker
+    __: PhantomData<B>,
+}
+
+impl<B> G1Var<B>
+where
+    B: Bls12Parameters,
+{
+    /// Returns the `X`-coordinate of `self`.
+    pub fn x(&self) -> &Variable {
+        &self.x
+    }
+
+    /// Returns the `Y`-coordinate of `self`.
+    pub fn y(&self) -> &Variable {
+        &self.y
+    }
+}
+
+/// Represents a point of `G2` of the BLS12 curve `B` in the circuit, with
+/// affine coordinates in the quadratic extension of the circuit field.
+///
+/// The identity has no affine coordinates, so it can't be represented.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""), Debug(bound = ""))]
+pub struct G2Var<B>
+where
+    B: Bls12Parameters,
+{
+    /// `X`-coordinate
+    x: [Variable; 2],
+
+    /// `Y`-coordinate
+    y: [Variable; 2],
+
+    /// Type Parameter Marker
+    __: PhantomData<B>,
+}
+
+impl<B> G2Var<B>
+where
+    B: Bls12Parameters,
+{
+    /// Returns the `X`-coordinate of `self`.
+    pub fn x(&self) -> &[Variable; 2] {
+        &self.x
+    }
+
+    /// Returns the `Y`-coordinate of `self`.
+    pub fn y(&self) -> &[Variable; 2] {
+        &self.y
+    }
+}
+
+/// Returns the coefficients of `value`.
+fn fp12_coeffs<B>(value: &Fqk<B>) -> [B::Fp; 12]
+where
+    B: Bls12Parameters,
+{
+    let mut coeffs = [B::Fp::zero(); 12];
+    for (i, fp6) in [value.c0, value.c1].iter().enumerate() {
+        for (j, fp2) in [fp6.c0, fp6.c1, fp6.c2].iter().enumerate() {
+            coeffs[6 * i + 2 * j] = fp2.c0;
+            coeffs[6 * i + 2 * j + 1] = fp2.c1;
+        }
+    }
+    coeffs
+}
+
+/// Returns the element of [`Fqk`] with the coefficients `coeffs`.
+fn fp12_from_coeffs<B>(coeffs: &[B::Fp; 12]) -> Fqk<B>
+where
+    B: Bls12Parameters,
+{
+    Fqk::<B>::from_base_prime_field_elems(coeffs)
+        .expect("Fp12 has 12 coefficients")
+}
+
+/// Returns the element of the basis of [`Fqk`] with the index `index`.
+fn fp12_basis<B>(index: usize) -> Fqk<B>
+where
+    B: Bls12Parameters,
+{
+    let mut coeffs = [B::Fp::zero(); 12];
+    coeffs[index] = B::Fp::one();
+    fp12_from_coeffs::<B>(&coeffs)
+}
+
+/// Structure constants of the multiplication in [`Fqk`]: the coefficient
+/// `k` of a product `a * b` is the sum of `c * a_i * b_j` over the
+/// `(i, j, c)` of `terms[k]`.
+struct Fp12Table<F> {
+    /// Terms of every coefficient of a product
+    terms: Vec<Vec<(usize, usize, F)>>,
+}
+
+impl<F> Fp12Table<F>
+where
+    F: PrimeField,
+{
+    /// Computes the structure constants of [`Fqk`] from the products of
+    /// its basis elements.
+    fn new<B>() -> Self
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let mut terms = vec![Vec::new(); 12];
+        for i in 0..12 {
+            for j in 0..12 {
+                let product = fp12_basis::<B>(i) * fp12_basis::<B>(j);
+                for (k, c) in fp12_coeffs::<B>(&product).iter().enumerate() {
+                    if !c.is_zero() {
+                        terms[k].push((i, j, *c));
+                    }
+                }
+            }
+        }
+        Self { terms }
+    }
+}
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Adds the point `point` of `G1` to the circuit as a witness
+    /// [`G1Var`], constraining it to be on the curve.
+    ///
+    /// The point isn't constrained to be in the subgroup of prime order.
+    pub fn add_g1_input<B>(&mut self, point: G1Affine<B>) -> G1Var<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let point = G1Var {
+            x: self.add_input(point.x),
+            y: self.add_input(point.y),
+            __: PhantomData,
+        };
+
+        // y^2 = x^3 + b
+        let x_square = self.arithmetic_gate(|gate| {
+            gate.witness(point.x, point.x, None).mul(F::one())
+        });
+        let x_cube = self.arithmetic_gate(|gate| {
+            gate.witness(x_square, point.x, None).mul(F::one())
+        });
+        self.arithmetic_gate(|gate| {
+            gate.witness(point.y, point.y, Some(x_cube))
+                .mul(F::one())
+                .constant(-B::G1Parameters::COEFF_B)
+        });
+        point
+    }
+
+    /// Adds the point `point` of `G2` to the circuit as a witness
+    /// [`G2Var`], constraining it to be on the curve.
+    ///
+    /// The point isn't constrained to be in the subgroup of prime order.
+    pub fn add_g2_input<B>(&mut self, point: G2Affine<B>) -> G2Var<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let x = [self.add_input(point.x.c0), self.add_input(point.x.c1)];
+        let y = [self.add_input(point.y.c0), self.add_input(point.y.c1)];
+
+        // y^2 = x^3 + b
+        let x_square = self.fp2_square::<B>(x);
+        let x_cube = self.fp2_mul::<B>(x_square, x);
+        let y_square = self.fp2_square::<B>(y);
+        let zero = self.zero_var;
+        let coeff_b = B::G2Parameters::COEFF_B;
+        for (i, constant) in [coeff_b.c0, coeff_b.c1].into_iter().enumerate() {
+            self.arithmetic_gate(|gate| {
+                gate.witness(y_square[i], x_cube[i], Some(zero))
+                    .add(F::one(), -F::one())
+                    .constant(-constant)
+            });
+        }
+        G2Var {
+            x,
+            y,
+            __: PhantomData,
+        }
+    }
+
+    /// Adds the point `point` of `G1` to the circuit description as a
+    /// constant [`G1Var`].
+    pub(crate) fn g1_constant<B>(&mut self, point: G1Affine<B>) -> G1Var<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        G1Var {
+            x: self.add_witness_to_circuit_description(point.x),
+            y: self.add_witness_to_circuit_description(point.y),
+            __: PhantomData,
+        }
+    }
+
+    /// Returns `-point`.
+    pub(crate) fn g1_neg<B>(&mut self, point: G1Var<B>) -> G1Var<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        G1Var {
+            x: point.x,
+            y: self.weighted_sum(&[(-F::one(), point.y)], F::zero()),
+            __: PhantomData,
+        }
+    }
+
+    /// Returns `a + b`, constraining `a` and `b` to have different
+    /// x-coordinates, without which the slope of the line through two equal
+    /// points would be unconstrained.
+    pub(crate) fn g1_add<B>(&mut self, a: G1Var<B>, b: G1Var<B>) -> G1Var<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let zero = self.zero_var;
+        let (x_1, y_1) = (self.value_of_var(a.x), self.value_of_var(a.y));
+        let (x_2, y_2) = (self.value_of_var(b.x), self.value_of_var(b.y));
+        let inv = (x_2 - x_1).inverse().unwrap_or_default();
+        let slope = self.add_input((y_2 - y_1) * inv);
+        let inv = self.add_input(inv);
+
+        // inv * (x_2 - x_1) = 1
+        let diff = self.arithmetic_gate(|gate| {
+            gate.witness(b.x, a.x, None).add(F::one(), -F::one())
+        });
+        self.arithmetic_gate(|gate| {
+            gate.witness(inv, diff, Some(zero))
+                .mul(F::one())
+                .constant(-F::one())
+        });
+        // slope * (x_2 - x_1) = y_2 - y_1
+        self.arithmetic_gate(|gate| {
+            gate.witness(slope, diff, Some(b.y))
+                .mul(F::one())
+                .fan_in_3(F::one(), a.y)
+        });
+        // x_3 = slope^2 - x_1 - x_2
+        let x_sum = self.arithmetic_gate(|gate| {
+            gate.witness(a.x, b.x, None).add(F::one(), F::one())
+        });
+        let x = self.arithmetic_gate(|gate| {
+            gate.witness(slope, slope, None)
+                .mul(F::one())
+                .fan_in_3(-F::one(), x_sum)
+        });
+        // y_3 = slope * (x_1 - x_3) - y_1
+        let x_diff = self.arithmetic_gate(|gate| {
+            gate.witness(a.x, x, None).add(F::one(), -F::one())
+        });
+        let y = self.arithmetic_gate(|gate| {
+            gate.witness(slope, x_diff, None)
+                .mul(F::one())
+                .fan_in_3(-F::one(), a.y)
+        });
+        G1Var {
+            x,
+            y,
+            __: PhantomData,
+        }
+    }
+
+    /// Returns `a` if `bit == 1` and `b` if `bit == 0`, see
+    /// [`StandardComposer::conditional_select`].
+    pub(crate) fn g1_select<B>(
+        &mut self,
+        bit: Variable,
+        a: G1Var<B>,
+        b: G1Var<B>,
+    ) -> G1Var<B>
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        G1Var {
+            x: self.conditional_select(bit, a.x, b.x),
+            y: self.conditional_select(bit, a.y, b.y),
+            __: PhantomData,
+        }
+    }
+
+    /// Constrains the product of the pairings `e(p, q)` of the pairs of
+    /// points `pairs` and `fixed_pairs` to be `expected`.
+    ///
+    /// The points of `G2` of `fixed_pairs` are constants of the circuit, so
+    /// their lines are computed out of it and only evaluated at the points of
+    /// `G1` in the circuit, which is much cheaper than for `pairs`.
+    pub fn assert_pairing_product<B>(
+        &mut self,
+        pairs: &[(G1Var<B>, G2Var<B>)],
+        fixed_pairs: &[(G1Var<B>, G2Affine<B>)],
+        expected: Fqk<B>,
+    ) where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let table = Fp12Table::new::<B>();
+        let f = self.miller_loop(&table, pairs, fixed_pairs);
+        let f = self.final_exponentiation::<B>(&table, &f);
+        for (coeff, value) in f.iter().zip(fp12_coeffs::<B>(&expected)) {
+            self.constrain_to_constant(*coeff, value, None);
+        }
+    }
+
+    /// Returns the Miller loop of the pairs of points `pairs` and
+    /// `fixed_pairs`, as computed by [`Bls12::miller_loop`].
+    ///
+    /// [`Bls12::miller_loop`]: ark_ec::PairingEngine::miller_loop
+    fn miller_loop<B>(
+        &mut self,
+        table: &Fp12Table<F>,
+        pairs: &[(G1Var<B>, G2Var<B>)],
+        fixed_pairs: &[(G1Var<B>, G2Affine<B>)],
+    ) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let one = self.add_witness_to_circuit_description(F::one());
+        let fp2_one = [one, self.zero_var];
+        let mut points = pairs
+            .iter()
+            .map(|(_, q)| (q.x, q.y, fp2_one))
+            .collect::<Vec<_>>();
+        let fixed_coeffs = fixed_pairs
+            .iter()
+            .map(|(_, q)| G2Prepared::<B>::from(*q).ell_coeffs)
+            .collect::<Vec<_>>();
+        let mut step = 0;
+
+        let mut f = [self.zero_var; 12];
+        f[0] = one;
+        for bit in BitIteratorBE::new(B::X).skip(1) {
+            f = self.fp12_square(table, &f);
+            for ((p, _), r) in pairs.iter().zip(&mut points) {
+                let coeffs = self.doubling_step::<B>(r);
+                f = self.ell_variable::<B>(table, &f, coeffs, *p);
+            }
+            for ((p, _), coeffs) in fixed_pairs.iter().zip(&fixed_coeffs) {
+                f = self.ell_fixed::<B>(table, &f, &coeffs[step], *p, one);
+            }
+            step += 1;
+            if bit {
+                for ((p, q), r) in pairs.iter().zip(&mut points) {
+                    let coeffs = self.addition_step::<B>(r, q);
+                    f = self.ell_variable::<B>(table, &f, coeffs, *p);
+                }
+                for ((p, _), coeffs) in fixed_pairs.iter().zip(&fixed_coeffs) {
+                    f = self.ell_fixed::<B>(table, &f, &coeffs[step], *p, one);
+                }
+                step += 1;
+            }
+        }
+        if B::X_IS_NEGATIVE {
+            f = self.fp12_conjugate::<B>(&f);
+        }
+        f
+    }
+
+    /// Returns `f^((p^12 - 1) / r)`, as computed by
+    /// [`Bls12::final_exponentiation`].
+    ///
+    /// [`Bls12::final_exponentiation`]:
+    /// ark_ec::PairingEngine::final_exponentiation
+    fn final_exponentiation<B>(
+        &mut self,
+        table: &Fp12Table<F>,
+        f: &Fp12Var,
+    ) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        // Easy part: r = f^((p^6 - 1) * (p^2 + 1))
+        let f_1 = self.fp12_conjugate::<B>(f);
+        let f_2 = self.fp12_inverse::<B>(table, f);
+        let r = self.fp12_mul(table, &f_1, &operand(&f_2));
+        let f_2 = r;
+        let r = self.fp12_frobenius::<B>(&r, 2);
+        let r = self.fp12_mul(table, &r, &operand(&f_2));
+
+        // Hard part
+        let y_0 = self.fp12_square(table, &r);
+        let y_1 = self.exp_by_x::<B>(table, &r);
+        let y_2 = self.fp12_conjugate::<B>(&r);
+        let y_1 = self.fp12_mul(table, &y_1, &operand(&y_2));
+        let y_2 = self.exp_by_x::<B>(table, &y_1);
+        let y_1 = self.fp12_conjugate::<B>(&y_1);
+        let y_1 = self.fp12_mul(table, &y_1, &operand(&y_2));
+        let y_2 = self.exp_by_x::<B>(table, &y_1);
+        let y_1 = self.fp12_frobenius::<B>(&y_1, 1);
+        let y_1 = self.fp12_mul(table, &y_1, &operand(&y_2));
+        let r = self.fp12_mul(table, &r, &operand(&y_0));
+        let y_0 = self.exp_by_x::<B>(table, &y_1);
+        let y_2 = self.exp_by_x::<B>(table, &y_0);
+        let y_0 = self.fp12_frobenius::<B>(&y_1, 2);
+        let y_1 = self.fp12_conjugate::<B>(&y_1);
+        let y_1 = self.fp12_mul(table, &y_1, &operand(&y_2));
+        let y_1 = self.fp12_mul(table, &y_1, &operand(&y_0));
+        self.fp12_mul(table, &r, &operand(&y_1))
+    }
+
+    /// Returns `f^x` for the parameter `x` of `B`, `f` being in the
+    /// cyclotomic subgroup, whose inverses are conjugates.
+    fn exp_by_x<B>(&mut self, table: &Fp12Table<F>, f: &Fp12Var) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let mut result = *f;
+        for bit in BitIteratorBE::without_leading_zeros(B::X).skip(1) {
+            result = self.fp12_square(table, &result);
+            if bit {
+                result = self.fp12_mul(table, &result, &operand(f));
+            }
+        }
+        if B::X_IS_NEGATIVE {
+            result = self.fp12_conjugate::<B>(&result);
+        }
+        result
+    }
+
+    /// Doubles the point `r` of `G2` in homogeneous projective coordinates,
+    /// returning the coefficients of the tangent line.
+    fn doubling_step<B>(
+        &mut self,
+        r: &mut ([Variable; 2], [Variable; 2], [Variable; 2]),
+    ) -> [[Variable; 2]; 3]
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let (x, y, z) = *r;
+        let two_inv =
+            Fqe::<B>::new(F::from(2u64).inverse().unwrap(), F::zero());
+        let three = Fqe::<B>::new(F::from(3u64), F::zero());
+        let one = Fqe::<B>::one();
+
+        let xy = self.fp2_mul::<B>(x, y);
+        let a = self.fp2_sum::<B>(&[(two_inv, xy)]);
+        let b = self.fp2_square::<B>(y);
+        let c = self.fp2_square::<B>(z);
+        let e = self.fp2_sum::<B>(&[(B::G2Parameters::COEFF_B * three, c)]);
+        let f = self.fp2_sum::<B>(&[(three, e)]);
+        let g = self.fp2_sum::<B>(&[(two_inv, b), (two_inv, f)]);
+        let y_plus_z = self.fp2_sum::<B>(&[(one, y), (one, z)]);
+        let y_plus_z_square = self.fp2_square::<B>(y_plus_z);
+        let h =
+            self.fp2_sum::<B>(&[(one, y_plus_z_square), (-one, b), (-one, c)]);
+        let i = self.fp2_sum::<B>(&[(one, e), (-one, b)]);
+        let j = self.fp2_square::<B>(x);
+        let e_square = self.fp2_square::<B>(e);
+
+        let b_minus_f = self.fp2_sum::<B>(&[(one, b), (-one, f)]);
+        let g_square = self.fp2_square::<B>(g);
+        *r = (
+            self.fp2_mul::<B>(a, b_minus_f),
+            self.fp2_sum::<B>(&[(one, g_square), (-three, e_square)]),
+            self.fp2_mul::<B>(b, h),
+        );
+        let three_j = self.fp2_sum::<B>(&[(three, j)]);
+        let minus_h = self.fp2_sum::<B>(&[(-one, h)]);
+        match B::TWIST_TYPE {
+            TwistType::M => [i, three_j, minus_h],
+            TwistType::D => [minus_h, three_j, i],
+        }
+    }
+
+    /// Adds the affine point `q` of `G2` to the point `r` in homogeneous
+    /// projective coordinates, returning the coefficients of the line
+    /// through them.
+    fn addition_step<B>(
+        &mut self,
+        r: &mut ([Variable; 2], [Variable; 2], [Variable; 2]),
+        q: &G2Var<B>,
+    ) -> [[Variable; 2]; 3]
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let (x, y, z) = *r;
+        let one = Fqe::<B>::one();
+        let two = Fqe::<B>::new(F::from(2u64), F::zero());
+
+        let q_y_z = self.fp2_mul::<B>(q.y, z);
+        let theta = self.fp2_sum::<B>(&[(one, y), (-one, q_y_z)]);
+        let q_x_z = self.fp2_mul::<B>(q.x, z);
+        let lambda = self.fp2_sum::<B>(&[(one, x), (-one, q_x_z)]);
+        let c = self.fp2_square::<B>(theta);
+        let d = self.fp2_square::<B>(lambda);
+        let e = self.fp2_mul::<B>(lambda, d);
+        let f = self.fp2_mul::<B>(z, c);
+        let g = self.fp2_mul::<B>(x, d);
+        let h = self.fp2_sum::<B>(&[(one, e), (one, f), (-two, g)]);
+
+        let g_minus_h = self.fp2_sum::<B>(&[(one, g), (-one, h)]);
+        let theta_g_minus_h = self.fp2_mul::<B>(theta, g_minus_h);
+        let e_y = self.fp2_mul::<B>(e, y);
+        *r = (
+            self.fp2_mul::<B>(lambda, h),
+            self.fp2_sum::<B>(&[(one, theta_g_minus_h), (-one, e_y)]),
+            self.fp2_mul::<B>(z, e),
+        );
+        let theta_q_x = self.fp2_mul::<B>(theta, q.x);
+        let lambda_q_y = self.fp2_mul::<B>(lambda, q.y);
+        let j = self.fp2_sum::<B>(&[(one, theta_q_x), (-one, lambda_q_y)]);
+        let minus_theta = self.fp2_sum::<B>(&[(-one, theta)]);
+        match B::TWIST_TYPE {
+            TwistType::M => [j, minus_theta, lambda],
+            TwistType::D => [lambda, minus_theta, j],
+        }
+    }
+
+    /// Multiplies `f` by the line with the coefficients `coeffs`, computed in
+    /// the circuit, evaluated at `p`.
+    fn ell_variable<B>(
+        &mut self,
+        table: &Fp12Table<F>,
+        f: &Fp12Var,
+        coeffs: [[Variable; 2]; 3],
+        p: G1Var<B>,
+    ) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let mut scale = |coeff: [Variable; 2], by: Variable| {
+            coeff.map(|c| {
+                let product = self.arithmetic_gate(|gate| {
+                    gate.witness(c, by, None).mul(F::one())
+                });
+                Some((F::one(), product))
+            })
+        };
+        let [c_0, c_1, c_2] = coeffs;
+        let line = match B::TWIST_TYPE {
+            TwistType::M => {
+                let c_2 = scale(c_2, p.y);
+                let c_1 = scale(c_1, p.x);
+                line_operand::<B, F>(c_0.map(|c| Some((F::one(), c))), c_1, c_2)
+            }
+            TwistType::D => {
+                let c_0 = scale(c_0, p.y);
+                let c_1 = scale(c_1, p.x);
+                line_operand::<B, F>(c_0, c_1, c_2.map(|c| Some((F::one(), c))))
+            }
+        };
+        self.fp12_mul(table, f, &line)
+    }
+
+    /// Multiplies `f` by the constant line with the coefficients `coeffs`
+    /// evaluated at `p`, `one` being a constant variable of value one.
+    fn ell_fixed<B>(
+        &mut self,
+        table: &Fp12Table<F>,
+        f: &Fp12Var,
+        coeffs: &(Fqe<B>, Fqe<B>, Fqe<B>),
+        p: G1Var<B>,
+        one: Variable,
+    ) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let scale = |coeff: &Fqe<B>, by: Variable| {
+            [coeff.c0, coeff.c1].map(|c| (!c.is_zero()).then_some((c, by)))
+        };
+        let (c_0, c_1, c_2) = coeffs;
+        let line = match B::TWIST_TYPE {
+            TwistType::M => line_operand::<B, F>(
+                scale(c_0, one),
+                scale(c_1, p.x),
+                scale(c_2, p.y),
+            ),
+            TwistType::D => line_operand::<B, F>(
+                scale(c_0, p.y),
+                scale(c_1, p.x),
+                scale(c_2, one),
+            ),
+        };
+        self.fp12_mul(table, f, &line)
+    }
+
+    /// Returns `a * b` for elements of the quadratic extension field.
+    fn fp2_mul<B>(
+        &mut self,
+        a: [Variable; 2],
+        b: [Variable; 2],
+    ) -> [Variable; 2]
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let nonresidue = B::Fp2Params::NONRESIDUE;
+        // (a_0 + a_1 * u) * (b_0 + b_1 * u)
+        //      = a_0 * b_0 + nonresidue * a_1 * b_1 + (a_0 * b_1 + a_1 * b_0) *
+        // u
+        let mut product = |terms: [(F, Variable, Variable); 2]| {
+            let [(c, a_0, b_0), (d, a_1, b_1)] = terms;
+            let first = self
+                .arithmetic_gate(|gate| gate.witness(a_0, b_0, None).mul(c));
+            self.arithmetic_gate(|gate| {
+                gate.witness(a_1, b_1, None)
+                    .mul(d)
+                    .fan_in_3(F::one(), first)
+            })
+        };
+        [
+            product([(F::one(), a[0], b[0]), (nonresidue, a[1], b[1])]),
+            product([(F::one(), a[0], b[1]), (F::one(), a[1], b[0])]),
+        ]
+    }
+
+    /// Returns `a^2` for an element of the quadratic extension field.
+    fn fp2_square<B>(&mut self, a: [Variable; 2]) -> [Variable; 2]
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let nonresidue = B::Fp2Params::NONRESIDUE;
+        let a_0_square = self.arithmetic_gate(|gate| {
+            gate.witness(a[0], a[0], None).mul(F::one())
+        });
+        let c_0 = self.arithmetic_gate(|gate| {
+            gate.witness(a[1], a[1], None)
+                .mul(nonresidue)
+                .fan_in_3(F::one(), a_0_square)
+        });
+        let c_1 = self.arithmetic_gate(|gate| {
+            gate.witness(a[0], a[1], None).mul(F::from(2u64))
+        });
+        [c_0, c_1]
+    }
+
+    /// Returns the linear combination `sum c_i * a_i` of elements of the
+    /// quadratic extension field, with constant coefficients `c_i`.
+    fn fp2_sum<B>(&mut self, terms: &[(Fqe<B>, [Variable; 2])]) -> [Variable; 2]
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let nonresidue = B::Fp2Params::NONRESIDUE;
+        let mut c_0 = Vec::with_capacity(2 * terms.len());
+        let mut c_1 = Vec::with_capacity(2 * terms.len());
+        for (c, a) in terms {
+            c_0.extend([(c.c0, a[0]), (nonresidue * c.c1, a[1])]);
+            c_1.extend([(c.c1, a[0]), (c.c0, a[1])]);
+        }
+        c_0.retain(|(c, _)| !c.is_zero());
+        c_1.retain(|(c, _)| !c.is_zero());
+        [
+            self.weighted_sum(&c_0, F::zero()),
+            self.weighted_sum(&c_1, F::zero()),
+        ]
+    }
+
+    /// Returns `a * b` for elements of [`Fqk`], with one gate per product of
+    /// coefficients of `a` and non-zero coefficients of `b`.
+    fn fp12_mul(
+        &mut self,
+        table: &Fp12Table<F>,
+        a: &Fp12Var,
+        b: &Fp12Operand<F>,
+    ) -> Fp12Var {
+        let mut result = [self.zero_var; 12];
+        for (coeff, terms) in result.iter_mut().zip(&table.terms) {
+            for (i, j, c) in terms {
+                if let Some((d, b_j)) = b[*j] {
+                    let sum = *coeff;
+                    *coeff = self.arithmetic_gate(|gate| {
+                        gate.witness(a[*i], b_j, None)
+                            .mul(*c * d)
+                            .fan_in_3(F::one(), sum)
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns `a^2` for an element of [`Fqk`], which takes about half the
+    /// gates of [`StandardComposer::fp12_mul`].
+    fn fp12_square(&mut self, table: &Fp12Table<F>, a: &Fp12Var) -> Fp12Var {
+        let mut result = [self.zero_var; 12];
+        for (coeff, terms) in result.iter_mut().zip(&table.terms) {
+            for (i, j, c) in terms.iter().filter(|(i, j, _)| i <= j) {
+                let c = if i == j { *c } else { c.double() };
+                let sum = *coeff;
+                *coeff = self.arithmetic_gate(|gate| {
+                    gate.witness(a[*i], a[*j], None)
+                        .mul(c)
+                        .fan_in_3(F::one(), sum)
+                });
+            }
+        }
+        result
+    }
+
+    /// Returns `map(a)` for an element `a` of [`Fqk`] and a map which is
+    /// linear over the circuit field.
+    fn fp12_linear<B>(
+        &mut self,
+        a: &Fp12Var,
+        map: impl Fn(Fqk<B>) -> Fqk<B>,
+    ) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let images = (0..12)
+            .map(|i| fp12_coeffs::<B>(&map(fp12_basis::<B>(i))))
+            .collect::<Vec<_>>();
+        let mut result = [self.zero_var; 12];
+        for (k, coeff) in result.iter_mut().enumerate() {
+            let terms = images
+                .iter()
+                .zip(a)
+                .filter(|(image, _)| !image[k].is_zero())
+                .map(|(image, a_i)| (image[k], *a_i))
+                .collect::<Vec<_>>();
+            *coeff = self.weighted_sum(&terms, F::zero());
+        }
+        result
+    }
+
+    /// Returns the conjugate of `a`, which is its inverse when it's in the
+    /// cyclotomic subgroup.
+    fn fp12_conjugate<B>(&mut self, a: &Fp12Var) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        self.fp12_linear::<B>(a, |mut value| {
+            value.conjugate();
+            value
+        })
+    }
+
+    /// Returns `a^(p^power)`.
+    fn fp12_frobenius<B>(&mut self, a: &Fp12Var, power: usize) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        self.fp12_linear::<B>(a, |mut value| {
+            value.frobenius_map(power);
+            value
+        })
+    }
+
+    /// Returns the inverse of `a`, constraining their product to be one.
+    fn fp12_inverse<B>(&mut self, table: &Fp12Table<F>, a: &Fp12Var) -> Fp12Var
+    where
+        B: Bls12Parameters<Fp = F>,
+    {
+        let value = fp12_from_coeffs::<B>(&a.map(|a_i| self.value_of_var(a_i)));
+        let inverse = fp12_coeffs::<B>(&value.inverse().unwrap_or_default())
+            .map(|coeff| self.add_input(coeff));
+        let product = self.fp12_mul(table, a, &operand(&inverse));
+        for (i, coeff) in product.iter().enumerate() {
+            let value = if i == 0 { F::one() } else { F::zero() };
+            self.constrain_to_constant(*coeff, value, None);
+        }
+        inverse
+    }
+}
+
+/// Returns `a` as an operand of [`StandardComposer::fp12_mul`].
+fn operand<F>(a: &Fp12Var) -> Fp12Operand<F>
+where
+    F: PrimeField,
+{
+    a.map(|a_i| Some((F::one(), a_i)))
+}
+
+/// Returns the sparse operand of a line of the Miller loop of `B`, whose
+/// coefficients in the quadratic extension field are the ones of
+/// `mul_by_014` or `mul_by_034` of [`Fqk`], depending on the twist.
+fn line_operand<B, F>(
+    c_0: [Option<(F, Variable)>; 2],
+    c_1: [Option<(F, Variable)>; 2],
+    c_2: [Option<(F, Variable)>; 2],
+) -> Fp12Operand<F>
+where
+    B: Bls12Parameters<Fp = F>,
+    F: PrimeField,
+{
+    // Indices of the coefficients in the quadratic extension field
+    let indices = match B::TWIST_TYPE {
+        TwistType::M => [0, 1, 4],
+        TwistType::D => [0, 3, 4],
+    };
+    let mut line = [None; 12];
+    for (index, coeff) in indices.into_iter().zip([c_0, c_1, c_2]) {
+        line[2 * index] = coeff[0];
+        line[2 * index + 1] = coeff[1];
+    }
+    line
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bls12_377::{Bls12_377, Fq, G1Affine, G2Affine, Parameters};
+    use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+    use ark_ed_on_bw6_761::EdwardsParameters;
+    use ark_ff::UniformRand;
+    use rand_core::OsRng;
+
+    type Composer = StandardComposer<Fq, EdwardsParameters>;
+
+    /// Returns a random point of `G1` and a random point of `G2`.
+    fn random_points() -> (G1Affine, G2Affine) {
+        (
+            G1Affine::prime_subgroup_generator()
+                .mul(<Bls12_377 as PairingEngine>::Fr::rand(&mut OsRng))
+                .into_affine(),
+            G2Affine::prime_subgroup_generator()
+                .mul(<Bls12_377 as PairingEngine>::Fr::rand(&mut OsRng))
+                .into_affine(),
+        )
+    }
+
+    #[test]
+    fn test_fp12_mul() {
+        let mut composer = Composer::new();
+        let table = Fp12Table::new::<Parameters>();
+        let a = Fqk::<Parameters>::rand(&mut OsRng);
+        let b = Fqk::<Parameters>::rand(&mut OsRng);
+        let a_var =
+            fp12_coeffs::<Parameters>(&a).map(|c| composer.add_input(c));
+        let b_var =
+            fp12_coeffs::<Parameters>(&b).map(|c| composer.add_input(c));
+        let product = composer.fp12_mul(&table, &a_var, &operand(&b_var));
+        let square = composer.fp12_square(&table, &a_var);
+        let value = |composer: &Composer, var: &Fp12Var| {
+            fp12_from_coeffs::<Parameters>(
+                &var.map(|v| composer.value_of_var(v)),
+            )
+        };
+        assert_eq!(value(&composer, &product), a * b);
+        assert_eq!(value(&composer, &square), a.square());
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    fn test_miller_loop() {
+        let mut composer = Composer::new();
+        let table = Fp12Table::new::<Parameters>();
+        let (p_1, q_1) = random_points();
+        let (p_2, q_2) = random_points();
+        let p_1_var = composer.add_g1_input::<Parameters>(p_1);
+        let q_1_var = composer.add_g2_input::<Parameters>(q_1);
+        let p_2_var = composer.add_g1_input::<Parameters>(p_2);
+        let f = composer.miller_loop(
+            &table,
+            &[(p_1_var, q_1_var)],
+            &[(p_2_var, q_2)],
+        );
+        let expected = Bls12_377::miller_loop(&[
+            (p_1.into(), q_1.into()),
+            (p_2.into(), q_2.into()),
+        ]);
+        assert_eq!(
+            fp12_from_coeffs::<Parameters>(
+                &f.map(|v| composer.value_of_var(v))
+            ),
+            expected
+        );
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    fn test_assert_pairing_product() {
+        // e(p, q) * e(-p, q) = 1
+        let mut composer = Composer::new();
+        let (p, q) = random_points();
+        let p_var = composer.add_g1_input::<Parameters>(p);
+        let q_var = composer.add_g2_input::<Parameters>(q);
+        let minus_p = composer.g1_neg(p_var);
+        composer.assert_pairing_product(
+            &[(p_var, q_var)],
+            &[(minus_p, q)],
+            Fqk::<Parameters>::one(),
+        );
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    fn test_assert_pairing_product_expected() {
+        let mut composer = Composer::new();
+        let (p, q) = random_points();
+        let p_var = composer.add_g1_input::<Parameters>(p);
+        let q_var = composer.add_g2_input::<Parameters>(q);
+        composer.assert_pairing_product(
+            &[(p_var, q_var)],
+            &[],
+            Bls12_377::pairing(p, q),
+        );
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn test_assert_pairing_product_wrong_point() {
+        let mut composer = Composer::new();
+        let (p, q) = random_points();
+        let (other, _) = random_points();
+        let p_var = composer.add_g1_input::<Parameters>(other);
+        composer.assert_pairing_product(
+            &[],
+            &[(p_var, q)],
+            Bls12_377::pairing(p, q),
+        );
+        composer.check_circuit_satisfied();
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn test_add_g2_input_off_curve() {
+        let mut composer = Composer::new();
+        let (_, mut q) = random_points();
+        q.y.c0 += Fq::one();
+        composer.add_g2_input::<Parameters>(q);
+        composer.check_circuit_satisfied();
+    }
+}
@@ -6,9 +6,38 @@
 
 //! Range Gate
 
-use crate::constraint_system::{StandardComposer, Variable, WireData};
+use crate::{
+    constraint_system::{StandardComposer, Variable, WireData},
+    lookup::lookup_table::BYTE_TABLE_TAG,
+};
 use ark_ec::TEModelParameters;
-use ark_ff::{BigInteger, PrimeField};
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+
+/// Number of gates added by [`StandardComposer::multi_range`] to range-check
+/// a single value of `num_bits` bits: one row per four quads plus the closing
+/// one, and a boolean check of the leading bit for odd widths.
+fn quad_range_gates(num_bits: usize) -> usize {
+    if num_bits == 0 {
+        return 1;
+    }
+    let num_quads = (num_bits + 1) >> 1;
+    let leading_bit = if num_bits % 2 == 1 { 2 } else { 0 };
+    ((num_quads + 3) >> 2) + 1 + leading_bit
+}
+
+/// Number of gates added by [`StandardComposer::lookup_range_gate`] for a
+/// value of `num_bits` bits: the tag, a lookup per byte plus two gates for
+/// the top byte of widths which aren't a multiple of 8, and for more than one
+/// byte the recomposing [`StandardComposer::weighted_sum`] and the equality.
+fn lookup_range_gates(num_bits: usize) -> usize {
+    let num_bytes = (num_bits + 7) / 8;
+    let top_byte = if num_bits % 8 != 0 { 2 } else { 0 };
+    let recomposition = match num_bytes {
+        1 => 0,
+        _ => 2 + num_bytes.saturating_sub(2) / 2,
+    };
+    1 + num_bytes + top_byte + recomposition
+}
 
 impl<F, P> StandardComposer<F, P>
 where
@@ -25,37 +54,6 @@ where
     /// This function will panic if the num_bits specified is not even, ie.
     /// `num_bits % 2 != 0`.
     pub fn range_gate(&mut self, witness: Variable, num_bits: usize) {
-        // Adds `variable` into the appropriate witness position
-        // based on the accumulator number a_i
-        let add_wire = |composer: &mut StandardComposer<F, P>,
-                        i: usize,
-                        variable: Variable| {
-            // Since four quads can fit into one gate, the gate index does
-            // not change for every four wires
-            let gate_index = composer.n + (i / 4);
-
-            let wire_data = match i % 4 {
-                0 => {
-                    composer.w_4.push(variable);
-                    WireData::Fourth(gate_index)
-                }
-                1 => {
-                    composer.w_o.push(variable);
-                    WireData::Output(gate_index)
-                }
-                2 => {
-                    composer.w_r.push(variable);
-                    WireData::Right(gate_index)
-                }
-                3 => {
-                    composer.w_l.push(variable);
-                    WireData::Left(gate_index)
-                }
-                _ => unreachable!(),
-            };
-            composer.perm.add_variable_to_map(variable, wire_data);
-        };
-
         // Note: A quad is a quaternary digit
         //
         // Number of bits should be even, this means that user must pad the
@@ -139,7 +137,7 @@ where
 
         // First we pad our gates by the necessary amount
         for i in 0..pad {
-            add_wire(self, i, self.zero_var);
+            self.add_range_wire(i, self.zero_var);
         }
 
         for i in pad..=num_quads {
@@ -156,10 +154,538 @@ where
             let accumulator_var = self.add_input(accumulator);
             accumulators.push(accumulator_var);
 
-            add_wire(self, i, accumulator_var);
+            self.add_range_wire(i, accumulator_var);
+        }
+
+        self.add_range_selectors(used_gates);
+
+        // Lastly, we must link the last accumulator value to the initial
+        // witness This last constraint will pass as long as
+        // - The witness is within the number of bits initially specified
+        let last_accumulator = accumulators.len() - 1;
+        self.assert_equal(accumulators[last_accumulator], witness);
+        accumulators[last_accumulator] = witness;
+    }
+
+    /// Constrains `a` to be a byte, i.e. to be inside of the range
+    /// \[0, 255\].
+    ///
+    /// This is a [`StandardComposer::range_gate`] over 8 bits.
+    pub fn assert_byte(&mut self, a: Variable) {
+        self.range_gate(a, 8);
+    }
+
+    /// Range-checks a heterogeneous batch of [`Variable`]s, constraining each
+    /// `values[i]` to be inside of the range \[0, 2^`bit_widths[i]`\).
+    ///
+    /// Instead of adding one [`StandardComposer::range_gate`] per value, the
+    /// quads of all the values are laid out in a single accumulator chain, so
+    /// that the padding and the final row are shared. Each value after the
+    /// first one costs an extra arithmetic gate linking it to the running
+    /// accumulator, and odd bit widths cost two extra gates to constrain the
+    /// leading quad to a single bit. When the total width exceeds the field
+    /// capacity the values are split into several chains.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` and `bit_widths` have different lengths or if a bit
+    /// width is larger than the field capacity.
+    pub fn multi_range(&mut self, values: &[Variable], bit_widths: &[usize]) {
+        assert_eq!(
+            values.len(),
+            bit_widths.len(),
+            "Each value needs a bit width"
+        );
+        let capacity = F::Params::CAPACITY as usize;
+
+        let mut chain = Vec::new();
+        let mut chain_bits = 0;
+        for (&value, &num_bits) in values.iter().zip(bit_widths) {
+            if num_bits == 0 {
+                self.constrain_to_constant(value, F::zero(), None);
+                continue;
+            }
+            let padded_bits = num_bits + (num_bits % 2);
+            assert!(
+                padded_bits <= capacity,
+                "Bit width {} exceeds the field capacity",
+                num_bits
+            );
+            if chain_bits + padded_bits > capacity {
+                self.range_chain(&chain);
+                chain.clear();
+                chain_bits = 0;
+            }
+            chain.push((value, num_bits));
+            chain_bits += padded_bits;
+        }
+        if !chain.is_empty() {
+            self.range_chain(&chain);
+        }
+    }
+
+    /// Constrains `witness` to be inside of the range \[0, 2^`num_bits`\)
+    /// by decomposing it into bytes which are looked up in a table holding
+    /// every byte, and returns the bytes in little-endian order.
+    ///
+    /// The byte rows are added to the lookup table of the composer if they
+    /// aren't already there, which pads the circuit to at least 256 rows.
+    /// Every byte costs a lookup gate, plus an extra arithmetic and lookup
+    /// gate for the top byte when `num_bits` isn't a multiple of 8. A single
+    /// byte is `witness` itself, and more bytes are recomposed with a
+    /// [`StandardComposer::weighted_sum`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` is larger than the field capacity.
+    pub fn lookup_range_gate(
+        &mut self,
+        witness: Variable,
+        num_bits: usize,
+    ) -> Vec<Variable> {
+        assert!(
+            num_bits <= F::Params::CAPACITY as usize,
+            "Bit width {} exceeds the field capacity",
+            num_bits
+        );
+        let tag = F::from(BYTE_TABLE_TAG);
+        if !self.lookup_table.0.iter().any(|row| row[3] == tag) {
+            self.lookup_table.insert_byte_rows();
+        }
+        let tag = self.add_witness_to_circuit_description(tag);
+        let zero = self.zero_var;
+
+        // A single byte is looked up directly, without recomposing it.
+        let num_bytes = (num_bits + 7) / 8;
+        let bytes = self.value_of_var(witness).into_repr().to_bytes_le();
+        let limbs = bytes[..num_bytes]
+            .iter()
+            .map(|byte| {
+                let limb = match num_bytes {
+                    1 => witness,
+                    _ => self.add_input(F::from(*byte)),
+                };
+                self.lookup_gate(limb, zero, zero, Some(tag), None);
+                limb
+            })
+            .collect::<Vec<_>>();
+
+        // The top byte only has `8 - spare_bits` bits, which holds if it's
+        // still a byte once shifted by `spare_bits`.
+        let spare_bits = limbs.len() * 8 - num_bits;
+        if spare_bits != 0 {
+            let shift = F::from(1u64 << spare_bits);
+            let top = *limbs.last().unwrap();
+            let shifted = self.arithmetic_gate(|gate| {
+                gate.witness(top, zero, None).add(shift, F::zero())
+            });
+            self.lookup_gate(shifted, zero, zero, Some(tag), None);
+        }
+
+        if num_bytes != 1 {
+            let terms = limbs
+                .iter()
+                .scan(F::one(), |power, limb| {
+                    let term = (*power, *limb);
+                    *power *= F::from(256u64);
+                    Some(term)
+                })
+                .collect::<Vec<_>>();
+            let recomposed = self.weighted_sum(&terms, F::zero());
+            self.assert_equal(recomposed, witness);
+        }
+        limbs
+    }
+
+    /// Constrains `witness` to be inside of the range \[0, 2^`num_bits`\),
+    /// with whichever of [`StandardComposer::lookup_range_gate`] and the quad
+    /// decomposition of [`StandardComposer::multi_range`] adds fewer gates.
+    ///
+    /// The byte table counts towards the cost of the lookups with its 256
+    /// rows until it's in the lookup table of the composer, like in the
+    /// byte-oriented ciphers. Once it's there, ties go to the lookups, which
+    /// then share it. Since the quads check eight bits per gate, the lookups
+    /// only win for values of at most one byte.
+    pub fn hybrid_range_gate(&mut self, witness: Variable, num_bits: usize) {
+        let tag = F::from(BYTE_TABLE_TAG);
+        let table_rows = if self.lookup_table.0.iter().any(|row| row[3] == tag)
+        {
+            0
+        } else {
+            256
+        };
+        if lookup_range_gates(num_bits) + table_rows
+            <= quad_range_gates(num_bits)
+        {
+            self.lookup_range_gate(witness, num_bits);
+        } else {
+            self.multi_range(&[witness], &[num_bits]);
         }
+    }
+
+    /// Constrains the public input declared at the gate `position` to be
+    /// inside of the range \[0, 2^`num_bits`\), so that the proof is only
+    /// accepted for public inputs of that size.
+    ///
+    /// The gate at `position` enforces `q_m * a * b + q_l * a + q_r * b +
+    /// q_o * c + q_4 * d + q_c + PI = 0`, so the public input is recomputed
+    /// from the wires of that gate in two more gates and range-checked with
+    /// [`StandardComposer::multi_range`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no public input at `position` or if the gate there
+    /// isn't an arithmetic gate without high degree terms, like the ones
+    /// built by [`StandardComposer::arithmetic_gate`] or
+    /// [`StandardComposer::constrain_to_constant`].
+    pub fn constrain_public_input_range(
+        &mut self,
+        position: usize,
+        num_bits: usize,
+    ) {
+        assert!(
+            self.intended_pi_pos.contains(&position),
+            "There is no public input at position {}",
+            position
+        );
+        assert!(
+            self.q_arith[position].is_one()
+                && self.q_hl[position].is_zero()
+                && self.q_hr[position].is_zero()
+                && self.q_h4[position].is_zero(),
+            "The public input at position {} isn't in an arithmetic gate",
+            position
+        );
+        let (a, b, c, d) = (
+            self.w_l[position],
+            self.w_r[position],
+            self.w_o[position],
+            self.w_4[position],
+        );
+        let (q_m, q_l, q_r, q_o, q_4, q_c) = (
+            self.q_m[position],
+            self.q_l[position],
+            self.q_r[position],
+            self.q_o[position],
+            self.q_4[position],
+            self.q_c[position],
+        );
+        let partial = self.arithmetic_gate(|gate| {
+            gate.witness(a, b, None)
+                .mul(q_m)
+                .add(q_l, q_r)
+                .fan_in_3(q_4, d)
+                .constant(q_c)
+        });
+        // PI = -(partial + q_o * c)
+        let public_input = self.arithmetic_gate(|gate| {
+            gate.witness(partial, c, None).add(-F::one(), -q_o)
+        });
+        self.multi_range(&[public_input], &[num_bits]);
+    }
+
+    /// Constrains `a = sum limbs_i * 2^(i * limb_bits)`, with every limb
+    /// range-checked to `limb_bits` bits, so that `limbs` is the
+    /// little-endian decomposition of `a` in base `2^limb_bits`.
+    ///
+    /// The limbs are range-checked with a single
+    /// [`StandardComposer::multi_range`] and recomposed with a
+    /// [`StandardComposer::weighted_sum`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limbs.len() * limb_bits` is larger than the field capacity,
+    /// since the decomposition wouldn't be unique anymore.
+    pub fn assert_limb_decomposition(
+        &mut self,
+        a: Variable,
+        limbs: &[Variable],
+        limb_bits: usize,
+    ) {
+        assert!(
+            limbs.len() * limb_bits <= F::Params::CAPACITY as usize,
+            "The limbs exceed the field capacity"
+        );
+        self.multi_range(limbs, &vec![limb_bits; limbs.len()]);
+        let base = F::from(2u64).pow([limb_bits as u64]);
+        let terms = limbs
+            .iter()
+            .scan(F::one(), |power, limb| {
+                let term = (*power, *limb);
+                *power *= base;
+                Some(term)
+            })
+            .collect::<Vec<_>>();
+        let recomposed = self.weighted_sum(&terms, F::zero());
+        self.assert_equal(recomposed, a);
+    }
+
+    /// Constrains `a` to be a multiple of `modulus` by witnessing the
+    /// quotient `q`, range-checking it to `num_bits` bits and constraining
+    /// `a = q * modulus`.
+    ///
+    /// Only multiples `q * modulus` with `q < 2^num_bits` are accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero or if `q * modulus` may wrap around the
+    /// field modulus, i.e. if `num_bits` plus the bit size of `modulus` is
+    /// larger than the field capacity.
+    pub fn assert_multiple_of(
+        &mut self,
+        a: Variable,
+        modulus: F,
+        num_bits: usize,
+    ) {
+        assert!(!modulus.is_zero(), "Cannot divide by zero");
+        assert!(
+            num_bits + modulus.into_repr().num_bits() as usize
+                <= F::Params::CAPACITY as usize,
+            "The quotient times the modulus exceeds the field capacity"
+        );
+        let zero = self.zero_var;
+        let quotient = self.value_of_var(a) * modulus.inverse().unwrap();
+        let quotient = self.add_input(quotient);
+        self.multi_range(&[quotient], &[num_bits]);
+        self.arithmetic_gate(|gate| {
+            gate.witness(quotient, a, Some(zero))
+                .add(modulus, -F::one())
+        });
+    }
+
+    /// Returns `(a * b) >> frac_bits`, the product of two fixed-point numbers
+    /// with `frac_bits` fractional bits, rounded down.
+    ///
+    /// The product is split as `a * b = q * 2^frac_bits + r`, with the
+    /// truncated bits `r` range-checked to `frac_bits` bits and the result `q`
+    /// to `num_bits` bits, so products whose result doesn't fit in `num_bits`
+    /// bits are rejected.
+    ///
+    /// # Note
+    /// `a * b` must not wrap around the field modulus, which holds if both
+    /// have previously been constrained to be small enough, e.g. with
+    /// [`StandardComposer::range_gate`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits + frac_bits` is larger than the field capacity,
+    /// since the split wouldn't be unique anymore.
+    pub fn fixed_point_mul(
+        &mut self,
+        a: Variable,
+        b: Variable,
+        frac_bits: usize,
+        num_bits: usize,
+    ) -> Variable {
+        assert!(
+            num_bits + frac_bits <= F::Params::CAPACITY as usize,
+            "The rescaled product exceeds the field capacity"
+        );
+        let product =
+            self.arithmetic_gate(|gate| gate.witness(a, b, None).mul(F::one()));
+
+        let scale = F::from(2u64).pow([frac_bits as u64]);
+        let product_value = self.value_of_var(product);
+        let mut quotient = product_value.into_repr();
+        quotient.divn(frac_bits as u32);
+        let quotient = F::from_repr(quotient).unwrap();
+        let remainder = product_value - quotient * scale;
+        let quotient = self.add_input(quotient);
+        let remainder = self.add_input(remainder);
+
+        self.multi_range(&[quotient, remainder], &[num_bits, frac_bits]);
+        self.arithmetic_gate(|gate| {
+            gate.witness(quotient, remainder, Some(product))
+                .add(scale, F::one())
+        });
+        quotient
+    }
+
+    /// Returns `prev + alpha * (sample - prev)`, the next value of an
+    /// exponential moving average with smoothing factor
+    /// `alpha = alpha_num / alpha_den`, rounded down.
+    ///
+    /// The step is computed as the weighted sum
+    /// `w = (alpha_den - alpha_num) * prev + alpha_num * sample`, which never
+    /// goes negative unlike `sample - prev`, divided by `alpha_den`. The
+    /// quotient `q` and remainder `r` of the division are witnessed and
+    /// constrained by `alpha_den * q + r = w`, with `q` range-checked to
+    /// `num_bits` bits and both `r` and `alpha_den - 1 - r` to the bit size
+    /// of `alpha_den - 1`, so that `r < alpha_den`. `prev` and `sample` are
+    /// expected to be fixed-point numbers of at most `num_bits` bits each,
+    /// with any number of fractional bits.
+    ///
+    /// # Note
+    /// `prev` and `sample` must have previously been constrained to
+    /// `num_bits` bits, e.g. with [`StandardComposer::range_gate`], for the
+    /// weighted sum not to wrap around the field modulus.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha_den` is zero, if `alpha_num` is larger than
+    /// `alpha_den`, or if the weighted sum may exceed the field capacity.
+    pub fn ema_step(
+        &mut self,
+        prev: Variable,
+        sample: Variable,
+        alpha_num: F,
+        alpha_den: F,
+        num_bits: usize,
+    ) -> Variable {
+        assert!(
+            !alpha_den.is_zero(),
+            "The denominator of the smoothing factor is zero"
+        );
+        let den_repr = alpha_den.into_repr();
+        assert!(
+            alpha_num.into_repr() <= den_repr,
+            "The smoothing factor is larger than one"
+        );
+        assert!(
+            num_bits + den_repr.num_bits() as usize
+                <= F::Params::CAPACITY as usize,
+            "The weighted sum exceeds the field capacity"
+        );
+        let weighted = self.arithmetic_gate(|gate| {
+            gate.witness(prev, sample, None)
+                .add(alpha_den - alpha_num, alpha_num)
+        });
+
+        let (quotient, remainder) =
+            div_rem(self.value_of_var(weighted), alpha_den);
+        let quotient = self.add_input(quotient);
+        let remainder = self.add_input(remainder);
+        self.arithmetic_gate(|gate| {
+            gate.witness(quotient, remainder, Some(weighted))
+                .add(alpha_den, F::one())
+        });
+
+        // alpha_den - 1 - r only fits in as many bits as alpha_den - 1 if
+        // r < alpha_den, as it wraps around the field modulus otherwise.
+        let max_remainder = alpha_den - F::one();
+        let slack = self.arithmetic_gate(|gate| {
+            gate.witness(remainder, remainder, None)
+                .add(-F::one(), F::zero())
+                .constant(max_remainder)
+        });
+        let remainder_bits =
+            (max_remainder.into_repr().num_bits() as usize).max(1);
+        self.multi_range(
+            &[quotient, remainder, slack],
+            &[num_bits, remainder_bits, remainder_bits],
+        );
+        quotient
+    }
 
-        // Set the selector polynomials for all of the gates we used
+    /// Lays out the quads of all the `(value, num_bits)` pairs in a single
+    /// range accumulator chain, most significant value first.
+    ///
+    /// The accumulator at the boundary of the `i`-th value is the packing
+    /// `P_i = P_{i-1} * 4^{k_i} + v_i` of all the values so far, where `k_i` is
+    /// the number of quads of `v_i`. For the first value `P_0 = v_0`, so it is
+    /// wired directly into the chain.
+    fn range_chain(&mut self, values: &[(Variable, usize)]) {
+        let num_quads: usize =
+            values.iter().map(|(_, num_bits)| (num_bits + 1) >> 1).sum();
+        let num_gates = (num_quads + 3) >> 2;
+        let pad = 1 + (num_gates << 2) - num_quads;
+        let used_gates = num_gates + 1;
+
+        // Zeroed accumulators, including the genesis one.
+        for i in 0..pad {
+            self.add_range_wire(i, self.zero_var);
+        }
+
+        let four = F::from(4u64);
+        let mut position = pad;
+        let mut accumulator = F::zero();
+        let mut boundary = self.zero_var;
+        // Linking gates `(P_{i-1}, v_i, P_i, k_i)` and leading quads
+        // `(acc_before, acc_after)` to be constrained after the range rows.
+        let mut links = Vec::new();
+        let mut leading_quads = Vec::new();
+
+        for (index, &(value, num_bits)) in values.iter().enumerate() {
+            let value_quads = (num_bits + 1) >> 1;
+            let bits = self.variables[&value].into_repr().to_bits_le();
+            let mut leading_accumulator = None;
+            let mut next_boundary = boundary;
+
+            for quad_index in (0..value_quads).rev() {
+                let quad = bits[quad_index << 1] as u64
+                    + 2 * bits[(quad_index << 1) + 1] as u64;
+                accumulator = four * accumulator + F::from(quad);
+
+                let accumulator_var = match (quad_index, index) {
+                    (0, 0) => value,
+                    _ => self.add_input(accumulator),
+                };
+                if leading_accumulator.is_none() {
+                    leading_accumulator = Some(accumulator_var);
+                }
+                self.add_range_wire(position, accumulator_var);
+                next_boundary = accumulator_var;
+                position += 1;
+            }
+
+            if num_bits % 2 == 1 {
+                leading_quads.push((boundary, leading_accumulator.unwrap()));
+            }
+            if index > 0 {
+                links.push((boundary, value, next_boundary, value_quads));
+            }
+            boundary = next_boundary;
+        }
+
+        self.add_range_selectors(used_gates);
+
+        for (previous, value, packed, value_quads) in links {
+            let shift = four.pow([value_quads as u64]);
+            self.arithmetic_gate(|gate| {
+                gate.witness(previous, value, Some(packed))
+                    .add(shift, F::one())
+            });
+        }
+        for (before, after) in leading_quads {
+            let quad = self.arithmetic_gate(|gate| {
+                gate.witness(after, before, None).add(F::one(), -four)
+            });
+            self.boolean_gate(quad);
+        }
+    }
+
+    /// Adds `variable` into the appropriate witness position based on the
+    /// accumulator number `i` of the range gate being laid out.
+    fn add_range_wire(&mut self, i: usize, variable: Variable) {
+        // Since four quads can fit into one gate, the gate index does
+        // not change for every four wires
+        let gate_index = self.n + (i / 4);
+
+        let wire_data = match i % 4 {
+            0 => {
+                self.w_4.push(variable);
+                WireData::Fourth(gate_index)
+            }
+            1 => {
+                self.w_o.push(variable);
+                WireData::Output(gate_index)
+            }
+            2 => {
+                self.w_r.push(variable);
+                WireData::Right(gate_index)
+            }
+            3 => {
+                self.w_l.push(variable);
+                WireData::Left(gate_index)
+            }
+            _ => unreachable!(),
+        };
+        self.perm.add_variable_to_map(variable, wire_data);
+    }
+
+    /// Sets the selector polynomials for the `used_gates` rows of a range
+    /// gate whose wires have already been laid out, and closes the last row.
+    fn add_range_selectors(&mut self, used_gates: usize) {
         let zeros = vec![F::zero(); used_gates];
         let ones = vec![F::one(); used_gates];
 
@@ -175,6 +701,7 @@ where
         self.q_range.extend(ones.iter());
         self.q_logic.extend(zeros.iter());
         self.q_lookup.extend(zeros.iter());
+        self.q_multiset.extend(zeros.iter());
 
         // add high degree selectors
         self.q_hl.extend(zeros.iter());
@@ -191,14 +718,30 @@ where
         self.w_l.push(self.zero_var);
         self.w_r.push(self.zero_var);
         self.w_o.push(self.zero_var);
+    }
+}
 
-        // Lastly, we must link the last accumulator value to the initial
-        // witness This last constraint will pass as long as
-        // - The witness is within the number of bits initially specified
-        let last_accumulator = accumulators.len() - 1;
-        self.assert_equal(accumulators[last_accumulator], witness);
-        accumulators[last_accumulator] = witness;
+/// Returns the quotient and remainder of the Euclidean division of `value`
+/// by `divisor`, both read as integers.
+fn div_rem<F>(value: F, divisor: F) -> (F, F)
+where
+    F: PrimeField,
+{
+    let divisor = divisor.into_repr();
+    let value = value.into_repr();
+    let (mut quotient, mut remainder) = (F::zero(), F::zero());
+    for i in (0..value.num_bits() as usize).rev() {
+        remainder.double_in_place();
+        quotient.double_in_place();
+        if value.get_bit(i) {
+            remainder += F::one();
+        }
+        if remainder.into_repr() >= divisor {
+            remainder -= F::from_repr(divisor).unwrap();
+            quotient += F::one();
+        }
     }
+    (quotient, remainder)
 }
 
 #[cfg(test)]
@@ -206,17 +749,19 @@ mod test {
     use super::*;
     use crate::{
         batch_test, commitment::HomomorphicCommitment,
-        constraint_system::helper::*,
+        constraint_system::helper::*, lookup::LookupTable,
+        proof_system::ProverCommitment,
     };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
     use ark_ec::models::TEModelParameters;
     use ark_ff::PrimeField;
+
     fn test_range_constraint<F, P, PC>()
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // Should fail as the number is not 32 bits
         let res = gadget_tester::<F, P, PC>(
@@ -250,11 +795,84 @@ mod test {
         assert!(res.is_ok());
     }
 
+    fn test_assert_byte<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // Should pass as the numbers fit in a byte
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for value in [0u64, 127, 255] {
+                    let witness = composer.add_input(F::from(value));
+                    composer.assert_byte(witness);
+                }
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as 256 needs 9 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let witness = composer.add_input(F::from(256u64));
+                composer.assert_byte(witness);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_limb_decomposition<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // Should pass as the limbs recompose the value and fit in 16 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(0x1234_5678_9abcu64));
+                let limbs = [0x9abcu64, 0x5678, 0x1234]
+                    .map(|limb| composer.add_input(F::from(limb)));
+                composer.assert_limb_decomposition(a, &limbs, 16);
+            },
+            64,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the limbs don't recompose the value
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(0x1234_5678_9abcu64));
+                let limbs = [0x9abcu64, 0x5678, 0x1235]
+                    .map(|limb| composer.add_input(F::from(limb)));
+                composer.assert_limb_decomposition(a, &limbs, 16);
+            },
+            64,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the first limb doesn't fit in 16 bits, even though
+        // the limbs recompose the value
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(0x1234_5678_9abcu64));
+                let limbs = [0x1_9abcu64, 0x5677, 0x1234]
+                    .map(|limb| composer.add_input(F::from(limb)));
+                composer.assert_limb_decomposition(a, &limbs, 16);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
     fn test_odd_bit_range<F, P, PC>()
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
     {
         // Should fail as the number we we need a even number of bits
         let _ok = gadget_tester::<F, P, PC>(
@@ -267,10 +885,509 @@ mod test {
         );
     }
 
+    fn test_multi_range<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // Should pass as every value is within its own bit width
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(u8::MAX));
+                let b = composer.add_input(F::from(u16::MAX));
+                let c = composer.add_input(F::from(u32::MAX));
+                let d = composer.add_input(F::from(0b101_0101u64));
+                composer.multi_range(&[a, b, c, d], &[8, 16, 32, 7]);
+            },
+            200,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the 8-bit value is out of range
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(1u64 << 8));
+                let b = composer.add_input(F::from(u16::MAX));
+                let c = composer.add_input(F::from(u32::MAX));
+                composer.multi_range(&[a, b, c], &[8, 16, 32]);
+            },
+            200,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the 16-bit value is out of range
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(u8::MAX));
+                let b = composer.add_input(F::from(1u64 << 16));
+                let c = composer.add_input(F::from(u32::MAX));
+                composer.multi_range(&[a, b, c], &[8, 16, 32]);
+            },
+            200,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the 32-bit value is out of range
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(u8::MAX));
+                let b = composer.add_input(F::from(u16::MAX));
+                let c = composer.add_input(F::from(1u64 << 32));
+                composer.multi_range(&[a, b, c], &[8, 16, 32]);
+            },
+            200,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the odd width value is out of range
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(u8::MAX));
+                let d = composer.add_input(F::from(1u64 << 7));
+                composer.multi_range(&[a, d], &[8, 7]);
+            },
+            200,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_assert_multiple_of<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for value in [0u64, 5, 15, 35] {
+                    let a = composer.add_input(F::from(value));
+                    composer.assert_multiple_of(a, F::from(5u64), 3);
+                }
+            },
+            200,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(16u64));
+                composer.assert_multiple_of(a, F::from(5u64), 3);
+            },
+            200,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the quotient is out of range
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(40u64));
+                composer.assert_multiple_of(a, F::from(5u64), 3);
+            },
+            200,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_fixed_point_mul<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // Q16.16 products: 1.5 * 2.25 = 3.375, then two which truncate.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for (a, b, expected) in [
+                    (98304u64, 147456u64, 221184u64),
+                    (21845, 196608, 65535),
+                    (12345, 54321, 10232),
+                ] {
+                    let a = composer.add_input(F::from(a));
+                    let b = composer.add_input(F::from(b));
+                    composer.range_gate(a, 32);
+                    composer.range_gate(b, 32);
+                    let c = composer.fixed_point_mul(a, b, 16, 32);
+                    composer.constrain_to_constant(c, F::from(expected), None);
+                }
+            },
+            256,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the product is rounded down, not up
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(12345u64));
+                let b = composer.add_input(F::from(54321u64));
+                let c = composer.fixed_point_mul(a, b, 16, 32);
+                composer.constrain_to_constant(c, F::from(10233u64), None);
+            },
+            128,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the result doesn't fit in 32 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(1u64 << 31));
+                let b = composer.add_input(F::from(1u64 << 18));
+                composer.fixed_point_mul(a, b, 16, 32);
+            },
+            128,
+        );
+        assert!(res.is_err());
+    }
+
+    /// Q16.16 samples averaged by [`test_ema_step`].
+    const EMA_SAMPLES: [u64; 6] = [
+        10 << 16,
+        (12 << 16) + 12345,
+        40000,
+        (100 << 16) + 1,
+        0,
+        (7 << 16) + 54321,
+    ];
+
+    /// Smoothing factors `(alpha_num, alpha_den)` used by [`test_ema_step`],
+    /// with a denominator which is a power of two and one which isn't.
+    const EMA_ALPHAS: [(u64, u64); 2] = [(3, 16), (1, 10)];
+
+    /// Moving average of [`EMA_SAMPLES`] after every step, rounded down.
+    fn reference_ema(alpha_num: u64, alpha_den: u64) -> Vec<u64> {
+        EMA_SAMPLES
+            .iter()
+            .scan(EMA_SAMPLES[0], |prev, sample| {
+                *prev = ((alpha_den - alpha_num) * *prev + alpha_num * sample)
+                    / alpha_den;
+                Some(*prev)
+            })
+            .collect()
+    }
+
+    fn test_ema_step<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // The rounding error of every step is less than one unit, and is
+        // damped by `1 - alpha` at every following step.
+        for (alpha_num, alpha_den) in EMA_ALPHAS {
+            let alpha = alpha_num as f64 / alpha_den as f64;
+            let mut exact = EMA_SAMPLES[0] as f64;
+            for (sample, rounded) in
+                EMA_SAMPLES.iter().zip(reference_ema(alpha_num, alpha_den))
+            {
+                exact += alpha * (*sample as f64 - exact);
+                assert!((exact - rounded as f64).abs() < 1.0 / alpha);
+            }
+        }
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                for (alpha_num, alpha_den) in EMA_ALPHAS {
+                    let mut prev = composer.add_input(F::from(EMA_SAMPLES[0]));
+                    composer.range_gate(prev, 32);
+                    for (sample, expected) in EMA_SAMPLES
+                        .iter()
+                        .zip(reference_ema(alpha_num, alpha_den))
+                    {
+                        let sample = composer.add_input(F::from(*sample));
+                        composer.range_gate(sample, 32);
+                        prev = composer.ema_step(
+                            prev,
+                            sample,
+                            F::from(alpha_num),
+                            F::from(alpha_den),
+                            32,
+                        );
+                        composer.constrain_to_constant(
+                            prev,
+                            F::from(expected),
+                            None,
+                        );
+                    }
+                }
+
+                // alpha = 1 replaces the average with the sample.
+                let prev = composer.add_input(F::from(5u64));
+                let sample = composer.add_input(F::from(9u64));
+                let next =
+                    composer.ema_step(prev, sample, F::one(), F::one(), 32);
+                composer.assert_equal(next, sample);
+            },
+            1024,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the step is rounded down, not up
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let prev = composer.add_input(F::from(EMA_SAMPLES[0]));
+                let sample = composer.add_input(F::from(EMA_SAMPLES[1]));
+                let next = composer.ema_step(
+                    prev,
+                    sample,
+                    F::from(1u64),
+                    F::from(10u64),
+                    32,
+                );
+                composer.constrain_to_constant(
+                    next,
+                    F::from(reference_ema(1, 10)[1] + 1),
+                    None,
+                );
+            },
+            128,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the average doesn't fit in 32 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let prev = composer.add_input(F::from(1u64 << 33));
+                let sample = composer.add_input(F::from(1u64 << 33));
+                composer.ema_step(
+                    prev,
+                    sample,
+                    F::from(1u64),
+                    F::from(10u64),
+                    32,
+                );
+            },
+            128,
+        );
+        assert!(res.is_err());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_ema_step_alpha_above_one<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let prev = composer.add_input(F::from(10u64));
+        let sample = composer.add_input(F::from(20u64));
+        composer.ema_step(prev, sample, F::from(11u64), F::from(10u64), 32);
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_multi_range_gate_count<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let widths = [8, 16, 32];
+
+        let mut composer = StandardComposer::<F, P>::new();
+        let values = widths.map(|_| composer.add_input(F::one()));
+        let start = composer.n;
+        composer.multi_range(&values, &widths);
+        let multi_range_gates = composer.n - start;
+
+        let mut composer = StandardComposer::<F, P>::new();
+        let values = widths.map(|_| composer.add_input(F::one()));
+        let start = composer.n;
+        for (value, num_bits) in values.iter().zip(widths) {
+            composer.range_gate(*value, num_bits);
+        }
+        let range_gates = composer.n - start;
+
+        // 28 quads fit in 7 rows plus the closing one, with 2 linking gates
+        assert_eq!(multi_range_gates, 10);
+        assert_eq!(range_gates, 13);
+    }
+
+    fn test_lookup_range_gate<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(u64::MAX));
+                composer.hybrid_range_gate(a, 64);
+                let b = composer.add_input(F::from(0x0102_0304u64));
+                let bytes = composer.lookup_range_gate(b, 27);
+                for (byte, expected) in bytes.iter().zip([4u64, 3, 2, 1]) {
+                    composer.constrain_to_constant(
+                        *byte,
+                        F::from(expected),
+                        None,
+                    );
+                }
+                let c = composer.add_input(F::from(1000u64));
+                composer.hybrid_range_gate(c, 10);
+                let d = composer.add_input(F::from(200u64));
+                composer.hybrid_range_gate(d, 8);
+            },
+            512,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the value doesn't fit in 64 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(u64::MAX) + F::one());
+                composer.hybrid_range_gate(a, 64);
+            },
+            512,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the top byte has more than 3 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::from(1u64 << 27));
+                composer.lookup_range_gate(a, 27);
+            },
+            512,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the byte looked up by the hybrid gate is too large
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = composer.add_input(F::one());
+                composer.lookup_range_gate(a, 16);
+                let b = composer.add_input(F::from(256u64));
+                composer.hybrid_range_gate(b, 8);
+            },
+            512,
+        );
+        assert!(res.is_err());
+
+        // Without the byte table, the lookups pay for its rows, so the quads
+        // are used and the lookup table stays empty.
+        for num_bits in 0..=64 {
+            let mut composer = StandardComposer::<F, P>::new();
+            let a = composer.add_input(F::from(u64::MAX));
+            let start = composer.n;
+            composer.hybrid_range_gate(a, num_bits);
+            let hybrid_gates = composer.n - start;
+            assert_eq!(hybrid_gates, quad_range_gates(num_bits));
+            assert!(
+                hybrid_gates
+                    <= quad_range_gates(num_bits)
+                        .min(lookup_range_gates(num_bits))
+            );
+            assert_eq!(composer.lookup_table, LookupTable::new());
+        }
+
+        // Once the table is there, a single byte is looked up.
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::from(u64::MAX));
+        composer.lookup_range_gate(a, 64);
+        assert_eq!(composer.lookup_table, LookupTable::byte_table());
+        let start = composer.n;
+        composer.hybrid_range_gate(a, 8);
+        assert_eq!(composer.n - start, 2);
+        assert_eq!(composer.q_lookup[start + 1], F::one());
+
+        for num_bits in 0..=64 {
+            let start = composer.n;
+            composer.multi_range(&[a], &[num_bits]);
+            let quad_gates = composer.n - start;
+            let start = composer.n;
+            composer.lookup_range_gate(a, num_bits);
+            let lookup_gates = composer.n - start;
+            let start = composer.n;
+            composer.hybrid_range_gate(a, num_bits);
+            let hybrid_gates = composer.n - start;
+
+            assert_eq!(quad_gates, quad_range_gates(num_bits));
+            assert_eq!(lookup_gates, lookup_range_gates(num_bits));
+            assert!(hybrid_gates <= quad_gates.min(lookup_gates));
+        }
+    }
+
+    fn test_constrain_public_input_range<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let zero = composer.zero_var();
+                let a = composer.add_input(F::from(200u64));
+                let position = composer.n;
+                composer.arithmetic_gate(|gate| {
+                    gate.witness(a, zero, Some(zero))
+                        .add(-F::one(), F::zero())
+                        .pi(F::from(200u64))
+                });
+                composer.constrain_public_input_range(position, 8);
+
+                let b = composer.add_input(-F::from(1000u64));
+                let position = composer.n;
+                composer.constrain_to_constant(
+                    b,
+                    F::zero(),
+                    Some(F::from(1000u64)),
+                );
+                composer.constrain_public_input_range(position, 11);
+            },
+            64,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Should fail as the public input doesn't fit in 8 bits
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let zero = composer.zero_var();
+                let a = composer.add_input(F::from(300u64));
+                let position = composer.n;
+                composer.arithmetic_gate(|gate| {
+                    gate.witness(a, zero, Some(zero))
+                        .add(-F::one(), F::zero())
+                        .pi(F::from(300u64))
+                });
+                composer.constrain_public_input_range(position, 8);
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_constrain_public_input_range_no_pi<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::one());
+        let position = composer.n;
+        composer.constrain_to_constant(a, F::one(), None);
+        composer.constrain_public_input_range(position, 8);
+    }
+
     // Test on Bls12-381
     batch_test!(
-        [test_range_constraint],
-        [test_odd_bit_range]
+        [
+            test_range_constraint,
+            test_assert_byte,
+            test_limb_decomposition,
+            test_multi_range,
+            test_multi_range_gate_count,
+            test_assert_multiple_of,
+            test_fixed_point_mul,
+            test_ema_step,
+            test_lookup_range_gate,
+            test_constrain_public_input_range
+        ],
+        [
+            test_odd_bit_range,
+            test_constrain_public_input_range_no_pi,
+            test_ema_step_alpha_above_one
+        ]
         => (
             Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
         )
@@ -278,8 +1395,23 @@ mod test {
 
     // Test on Bls12-377
     batch_test!(
-        [test_range_constraint],
-        [test_odd_bit_range]
+        [
+            test_range_constraint,
+            test_assert_byte,
+            test_limb_decomposition,
+            test_multi_range,
+            test_multi_range_gate_count,
+            test_assert_multiple_of,
+            test_fixed_point_mul,
+            test_ema_step,
+            test_lookup_range_gate,
+            test_constrain_public_input_range
+        ],
+        [
+            test_odd_bit_range,
+            test_constrain_public_input_range_no_pi,
+            test_ema_step_alpha_above_one
+        ]
         => (
             Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
         )
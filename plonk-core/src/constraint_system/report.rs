@@ -0,0 +1,178 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Circuit Reports
+//!
+//! Human readable dumps of a circuit, meant to be looked at when a proof
+//! unexpectedly fails: a breakdown of the gates by kind, a DOT graph of the
+//! gates linked by the variables they share and the values of the witness.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Returns the kind of the gate at `index`, according to the first of
+    /// its selectors which is set.
+    fn gate_kind(&self, index: usize) -> &'static str {
+        [
+            (&self.q_lookup, "lookup"),
+            (&self.q_multiset, "multiset"),
+            (&self.q_range, "range"),
+            (&self.q_logic, "logic"),
+            (&self.q_fixed_group_add, "fixed_base_add"),
+            (&self.q_variable_group_add, "variable_base_add"),
+            (&self.q_arith, "arithmetic"),
+        ]
+        .iter()
+        .find(|(selector, _)| !selector[index].is_zero())
+        .map_or("other", |(_, kind)| kind)
+    }
+
+    /// Writes the number of gates of every kind, followed by the total
+    /// number of gates.
+    pub fn write_gate_stats<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let mut counts: Vec<(&'static str, usize)> = Vec::new();
+        for index in 0..self.n {
+            let kind = self.gate_kind(index);
+            match counts.iter_mut().find(|(name, _)| *name == kind) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((kind, 1)),
+            }
+        }
+        for (kind, count) in counts {
+            writeln!(w, "{}: {} gates", kind, count)?;
+        }
+        writeln!(w, "total: {} gates", self.n)
+    }
+
+    /// Writes the circuit as an undirected DOT graph, with a node for every
+    /// gate and, for every [`Variable`], edges linking the consecutive gates
+    /// it appears in.
+    pub fn write_dot<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "graph circuit {{")?;
+        for index in 0..self.n {
+            writeln!(
+                w,
+                "    g{} [label=\"{}: {}\"];",
+                index,
+                index,
+                self.gate_kind(index)
+            )?;
+        }
+        for var in (0..self.num_variables()).map(Variable) {
+            for pair in self.gates_using(var).windows(2) {
+                writeln!(
+                    w,
+                    "    g{} -- g{} [label=\"v{}\"];",
+                    pair[0], pair[1], var
+                )?;
+            }
+        }
+        writeln!(w, "}}")
+    }
+
+    /// Writes the witness as CSV, with the index and the value of every
+    /// [`Variable`] of the circuit.
+    pub fn write_witness_csv<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "variable,value")?;
+        for (index, value) in self.assignment().iter().enumerate() {
+            writeln!(w, "{},0x{}", index, value.into_repr())?;
+        }
+        Ok(())
+    }
+
+    /// Writes the gate breakdown, the DOT graph and the witness of the
+    /// circuit into `dir`, as `gates.txt`, `circuit.dot` and `witness.csv`
+    /// respectively, creating the directory if needed.
+    ///
+    /// See [`StandardComposer::write_gate_stats`],
+    /// [`StandardComposer::write_dot`] and
+    /// [`StandardComposer::write_witness_csv`].
+    pub fn debug_report(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        let create = |name| File::create(dir.join(name)).map(BufWriter::new);
+
+        let mut gates = create("gates.txt")?;
+        self.write_gate_stats(&mut gates)?;
+        gates.flush()?;
+
+        let mut dot = create("circuit.dot")?;
+        self.write_dot(&mut dot)?;
+        dot.flush()?;
+
+        let mut witness = create("witness.csv")?;
+        self.write_witness_csv(&mut witness)?;
+        witness.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::batch_test_field_params;
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    fn test_debug_report<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::from(3u64));
+        let b = composer.add_input(F::from(5u64));
+        let c = composer
+            .arithmetic_gate(|gate| gate.witness(a, b, None).mul(F::one()));
+        composer.range_gate(c, 8);
+
+        let dir = tempdir::TempDir::new("plonk_report").unwrap();
+        let report_dir = dir.path().join("report");
+        composer.debug_report(&report_dir).unwrap();
+
+        let read = |name| fs::read_to_string(report_dir.join(name)).unwrap();
+        let gates = read("gates.txt");
+        assert!(gates.contains("range: "));
+        assert!(gates.ends_with(&format!("total: {} gates\n", composer.n)));
+
+        let dot = read("circuit.dot");
+        assert!(dot.starts_with("graph circuit {"));
+        assert!(dot.contains(&format!("[label=\"v{}\"]", c)));
+
+        let witness = read("witness.csv");
+        assert_eq!(witness.lines().count(), composer.num_variables() + 1);
+        assert!(witness.contains(&format!(
+            "\n{},0x{}\n",
+            b,
+            F::from(5u64).into_repr()
+        )));
+    }
+
+    // Bls12-381 tests
+    batch_test_field_params!(
+        [test_debug_report],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test_field_params!(
+        [test_debug_report],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
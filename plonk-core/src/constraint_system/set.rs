@@ -0,0 +1,301 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Set Membership and Permutation Gates
+
+use crate::constraint_system::{StandardComposer, Variable};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+
+impl<F, P> StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Constrains `a` to be equal to one of the constants in `set` by
+    /// enforcing `prod (a - s_i) = 0` along a chain of multiplication gates.
+    ///
+    /// The cost is one gate per element of `set`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `set` is empty, since no [`Variable`] can be a member of the
+    /// empty set.
+    pub fn assert_in_set(&mut self, a: Variable, set: &[F]) {
+        assert!(!set.is_empty(), "Cannot assert membership of an empty set");
+        let zero = self.zero_var;
+        let (last, rest) = set.split_last().unwrap();
+        let product = rest
+            .iter()
+            .fold(None, |acc, s| Some(self.set_product_step(acc, a, *s, None)));
+        self.set_product_step(product, a, *last, Some(zero));
+    }
+
+    /// Constrains `a` to be different from every constant in `set` by
+    /// enforcing `prod (a - s_i) != 0`, which is proven by exhibiting the
+    /// inverse of the product.
+    ///
+    /// The cost is one gate per element of `set` plus one for the inverse.
+    /// Any [`Variable`] is trivially outside of an empty set, so no gate is
+    /// added in that case.
+    pub fn assert_not_in_set(&mut self, a: Variable, set: &[F]) {
+        if set.is_empty() {
+            return;
+        }
+        let product = set
+            .iter()
+            .fold(None, |acc, s| Some(self.set_product_step(acc, a, *s, None)))
+            .unwrap();
+        let inverse =
+            self.value_of_var(product).inverse().unwrap_or_else(F::zero);
+        let inverse = self.add_input(inverse);
+        let zero = self.zero_var;
+        self.arithmetic_gate(|gate| {
+            gate.witness(product, inverse, Some(zero))
+                .mul(F::one())
+                .constant(-F::one())
+        });
+    }
+
+    /// Constrains `b` to be a permutation of `a`, i.e. both slices hold the
+    /// same values with the same multiplicities, by enforcing
+    /// `prod (a_i + zeta * t + theta) = prod (b_i + zeta * t + theta)` for
+    /// transcript challenges `zeta` and `theta`.
+    ///
+    /// Each pair `(a_i, b_i)` is placed on a multiset equality gate, whose
+    /// factors are folded into the permutation grand product by the prover.
+    /// The tag `t` is a constant unique to every call, which keeps the
+    /// values of different calls from being mixed together. The cost is one
+    /// gate per element plus one for the tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` and `b` have different lengths.
+    pub fn assert_permutation(&mut self, a: &[Variable], b: &[Variable]) {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "Slices of different lengths can't be permutations of each other"
+        );
+        if a.is_empty() {
+            return;
+        }
+        let tag =
+            self.add_witness_to_circuit_description(F::from(self.n as u64));
+        for (a, b) in a.iter().zip(b) {
+            self.multiset_gate(*a, *b, tag);
+        }
+    }
+
+    /// Adds a multiset equality gate contributing `a + zeta * tag + theta`
+    /// to the numerator and `b + zeta * tag + theta` to the denominator of
+    /// the permutation grand product.
+    fn multiset_gate(&mut self, a: Variable, b: Variable, tag: Variable) {
+        self.w_l.push(a);
+        self.w_r.push(b);
+        self.w_o.push(tag);
+        self.w_4.push(self.zero_var);
+
+        self.q_m.push(F::zero());
+        self.q_l.push(F::zero());
+        self.q_r.push(F::zero());
+        self.q_o.push(F::zero());
+        self.q_c.push(F::zero());
+        self.q_4.push(F::zero());
+        self.q_arith.push(F::zero());
+
+        // add high degree selectors
+        self.q_hl.push(F::zero());
+        self.q_hr.push(F::zero());
+        self.q_h4.push(F::zero());
+
+        self.q_range.push(F::zero());
+        self.q_logic.push(F::zero());
+        self.q_fixed_group_add.push(F::zero());
+        self.q_variable_group_add.push(F::zero());
+        self.q_lookup.push(F::zero());
+        self.q_multiset.push(F::one());
+
+        self.perm
+            .add_variables_to_map(a, b, tag, self.zero_var, self.n);
+
+        self.n += 1;
+    }
+
+    /// Multiplies the running product `acc` by `a - s`, starting the chain
+    /// with `a - s` if there's no product yet. When `out` is given the result
+    /// is constrained to it instead of being allocated.
+    fn set_product_step(
+        &mut self,
+        acc: Option<Variable>,
+        a: Variable,
+        s: F,
+        out: Option<Variable>,
+    ) -> Variable {
+        let zero = self.zero_var;
+        match acc {
+            None => self.arithmetic_gate(|gate| {
+                gate.witness(a, zero, out)
+                    .add(F::one(), F::zero())
+                    .constant(-s)
+            }),
+            Some(acc) => self.arithmetic_gate(|gate| {
+                gate.witness(acc, a, out).mul(F::one()).add(-s, F::zero())
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test, commitment::HomomorphicCommitment,
+        constraint_system::helper::*, proof_system::ProverCommitment,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+
+    fn test_assert_in_set<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let set = [F::from(1u64), F::from(3u64), F::from(5u64)];
+                for value in set {
+                    let a = composer.add_input(value);
+                    composer.assert_in_set(a, &set);
+                }
+                let a = composer.add_input(F::from(7u64));
+                composer.assert_in_set(a, &[F::from(7u64)]);
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let set = [F::from(1u64), F::from(3u64), F::from(5u64)];
+                let a = composer.add_input(F::from(2u64));
+                composer.assert_in_set(a, &set);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_assert_not_in_set<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let set = [F::from(1u64), F::from(3u64), F::from(5u64)];
+                let a = composer.add_input(F::from(2u64));
+                composer.assert_not_in_set(a, &set);
+                composer.assert_not_in_set(a, &[]);
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let set = [F::from(1u64), F::from(3u64), F::from(5u64)];
+                let a = composer.add_input(F::from(3u64));
+                composer.assert_not_in_set(a, &set);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
+    fn test_assert_permutation<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = [4u64, 1, 3, 1].map(|v| composer.add_input(F::from(v)));
+                let b = [1u64, 3, 1, 4].map(|v| composer.add_input(F::from(v)));
+                composer.assert_permutation(&a, &b);
+                composer.assert_permutation(&a, &a);
+                composer.assert_permutation(&[], &[]);
+            },
+            32,
+        );
+        assert!(res.is_ok(), "{:?}", res.err().unwrap());
+
+        // Same values with other multiplicities.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = [4u64, 1, 3, 1].map(|v| composer.add_input(F::from(v)));
+                let b = [4u64, 3, 3, 1].map(|v| composer.add_input(F::from(v)));
+                composer.assert_permutation(&a, &b);
+            },
+            32,
+        );
+        assert!(res.is_err());
+
+        // Other values with the same sum.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = [1u64, 2, 3, 4].map(|v| composer.add_input(F::from(v)));
+                let b = [2u64, 2, 3, 3].map(|v| composer.add_input(F::from(v)));
+                composer.assert_permutation(&a, &b);
+            },
+            32,
+        );
+        assert!(res.is_err());
+
+        // Two calls which only hold together.
+        let res = gadget_tester::<F, P, PC>(
+            |composer: &mut StandardComposer<F, P>| {
+                let a = [1u64, 2].map(|v| composer.add_input(F::from(v)));
+                let b = [1u64, 3].map(|v| composer.add_input(F::from(v)));
+                composer.assert_permutation(&a, &b);
+                composer.assert_permutation(&b, &a);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
+    #[allow(clippy::extra_unused_type_parameters)]
+    fn test_assert_permutation_different_lengths<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let a = composer.add_input(F::one());
+        composer.assert_permutation(&[a, a], &[a]);
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_assert_in_set, test_assert_not_in_set, test_assert_permutation],
+        [test_assert_permutation_different_lengths] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_assert_in_set, test_assert_not_in_set, test_assert_permutation],
+        [test_assert_permutation_different_lengths] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
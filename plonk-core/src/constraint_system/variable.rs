@@ -14,6 +14,15 @@ use std::fmt::Display;
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Variable(pub(crate) usize);
 
+impl Variable {
+    /// Returns the index of the variable, which is its position in the
+    /// assignment given to
+    /// [`Prover::prove_with_assignment`](crate::proof_system::Prover::prove_with_assignment).
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
 impl Display for Variable {
     // This trait requires `fmt` with this exact signature.
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -18,10 +18,22 @@ pub enum Error {
         /// Two adicity generated
         adicity: u32,
     },
+    /// This error occurs when proving with an
+    /// [`FftPlan`](crate::proof_system::FftPlan) of another size than the
+    /// evaluation domain of the circuit.
+    FftPlanSizeMismatch {
+        /// Size of the evaluation domain of the circuit
+        expected: usize,
+        /// Size of the plan
+        found: usize,
+    },
 
     // Prover/Verifier errors
     /// This error occurs when a proof verification fails.
     ProofVerificationError,
+    /// This error occurs when verifying a proof with an opening key trimmed
+    /// from another SRS than the one the circuit was preprocessed against.
+    SrsMismatch,
     /// This error occurs when the circuit is not provided with all of the
     /// required inputs.
     CircuitInputsNotFound,
@@ -32,10 +44,49 @@ pub enum Error {
     InvalidPublicInputBytes,
     /// PublicInput value conversion error
     InvalidPublicInputValue,
+    /// This error occurs when the public inputs given to the verifier aren't
+    /// the ones the proof was made for.
+    PublicInputsDigestMismatch,
+    /// This error occurs when the committed public inputs given to the
+    /// verifier are opened against another commitment than the expected one.
+    PublicInputsCommitmentMismatch,
+    /// This error occurs when a witness assignment doesn't hold one value per
+    /// variable of the circuit.
+    InvalidWitnessLength {
+        /// Number of variables of the circuit
+        expected: usize,
+        /// Number of values in the assignment
+        found: usize,
+    },
+    /// This error occurs when the public inputs given to the prover don't
+    /// hold one value per public input position of the circuit.
+    InvalidPublicInputLength {
+        /// Number of public input positions of the circuit
+        expected: usize,
+        /// Number of public input values given
+        found: usize,
+    },
+    /// This error occurs when a circuit is finalized twice.
+    CircuitAlreadyFinalized,
+    /// This error occurs when proving a circuit which hasn't been finalized.
+    CircuitNotFinalized,
+    /// This error occurs when gates or lookup table rows are added to a
+    /// circuit after it has been finalized.
+    CircuitChangedAfterFinalize,
     /// This error occurs when the Prover structure already contains a
     /// preprocessed circuit inside, but you call preprocess again.
     CircuitAlreadyPreprocessed,
 
+    // Gadget errors
+    /// This error occurs when a gadget is given vectors or matrices whose
+    /// dimensions don't match.
+    DimensionMismatch {
+        /// Length the gadget expected
+        expected: usize,
+        /// Length it was given
+        found: usize,
+    },
+
     // Preprocessing errors
     /// This error occurs when an error triggers during the preprocessing
     /// stage.
@@ -64,6 +115,9 @@ pub enum Error {
     /// This error occurs when the user tries to commit to a polynomial whose
     /// degree is zero.
     PolynomialDegreeIsZero,
+    /// This error occurs when committing with caller-supplied blinding
+    /// factors to a commitment scheme which can only sample them.
+    SuppliedBlindingUnsupported,
     /// This error occurs when the pairing check fails at being equal to the
     /// Identity point.
     PairingCheckFailure,
@@ -118,9 +172,19 @@ impl std::fmt::Display for Error {
             Size: {:?} > TWO_ADICITY = {:?}",
                 log_size_of_group, adicity
             ),
+            Self::FftPlanSizeMismatch { expected, found } => write!(
+                f,
+                "FFT plan of size {} given for a domain of size {}",
+                found, expected
+            ),
             Self::ProofVerificationError => {
                 write!(f, "proof verification failed")
             }
+            Self::SrsMismatch => write!(
+                f,
+                "opening key doesn't match the SRS the circuit was \
+                preprocessed against"
+            ),
             Self::CircuitInputsNotFound => {
                 write!(f, "circuit inputs not found")
             }
@@ -133,15 +197,45 @@ impl std::fmt::Display for Error {
             Self::InvalidPublicInputValue => {
                 write!(f, "public input value conversion error")
             }
+            Self::PublicInputsDigestMismatch => {
+                write!(f, "public inputs digest mismatch")
+            }
+            Self::PublicInputsCommitmentMismatch => {
+                write!(f, "public inputs commitment mismatch")
+            }
+            Self::InvalidWitnessLength { expected, found } => write!(
+                f,
+                "witness holds {} values but the circuit has {} variables",
+                found, expected
+            ),
+            Self::InvalidPublicInputLength { expected, found } => write!(
+                f,
+                "{} public inputs were given but the circuit has {}",
+                found, expected
+            ),
             Self::MismatchedPolyLen => {
                 write!(f, "the length of the wires is not the same")
             }
             Self::PCError { error } => {
                 write!(f, "{:?}", error)
             }
+            Self::CircuitAlreadyFinalized => {
+                write!(f, "circuit has already been finalized")
+            }
+            Self::CircuitNotFinalized => {
+                write!(f, "circuit has not been finalized")
+            }
+            Self::CircuitChangedAfterFinalize => {
+                write!(f, "circuit was changed after being finalized")
+            }
             Self::CircuitAlreadyPreprocessed => {
                 write!(f, "circuit has already been preprocessed")
             }
+            Self::DimensionMismatch { expected, found } => write!(
+                f,
+                "expected a length of {} but found {}",
+                expected, found
+            ),
             Self::DegreeIsZero => {
                 write!(f, "cannot create PublicParameters with max degree 0")
             }
@@ -159,6 +253,10 @@ impl std::fmt::Display for Error {
             Self::PolynomialDegreeIsZero => {
                 write!(f, "cannot commit to polynomial of zero degree")
             }
+            Self::SuppliedBlindingUnsupported => write!(
+                f,
+                "commitment scheme doesn't support supplied blinding factors"
+            ),
             Self::PairingCheckFailure => write!(f, "pairing check failed"),
             Self::NotEnoughBytes => write!(f, "not enough bytes left to read"),
             Self::PointMalformed => write!(f, "point bytes malformed"),
@@ -8,6 +8,31 @@ use crate::error::Error;
 use crate::lookup::MultiSet;
 use ark_ff::Field;
 
+/// Value of the fourth column identifying the rows inserted with
+/// [`LookupTable::insert_map_row`], so that they can't be mistaken for the
+/// rows of an arithmetic or logic table.
+pub(crate) const MAP_TABLE_TAG: u64 = 3;
+
+/// Value of the fourth column identifying the rows inserted with
+/// [`LookupTable::insert_byte_rows`].
+pub(crate) const BYTE_TABLE_TAG: u64 = 4;
+
+/// Value of the fourth column identifying the rows inserted with
+/// [`LookupTable::insert_crc32_rows`].
+pub(crate) const CRC32_TABLE_TAG: u64 = 5;
+
+/// Reversed generator polynomial of the CRC-32 used by Ethernet, zlib and
+/// PNG.
+const CRC32_POLYNOMIAL: u64 = 0xedb8_8320;
+
+/// Returns the remainder of the division of `index` by the CRC-32 polynomial,
+/// i.e. the entry of the table of the byte-wise CRC-32 algorithm at `index`.
+pub(crate) fn crc32_table_entry(index: u64) -> u64 {
+    (0..8).fold(index, |crc, _| {
+        (crc >> 1) ^ if crc & 1 == 1 { CRC32_POLYNOMIAL } else { 0 }
+    })
+}
+
 /// This struct is a table, contaning a vector, of arity 4 where each of the
 /// values is a scalar. The elements of the table are determined by the function
 /// g for g(x,y), used to compute tuples.
@@ -83,6 +108,41 @@ where
         self.insert_row(F::from(a), F::from(b), F::from(c), F::from(2u64));
     }
 
+    /// Insert a new row mapping `key` to `value`.
+    ///
+    /// The row is stored as `(key, 0, value, 3)`, which is looked up by
+    /// [`StandardComposer::lookup_map`](crate::constraint_system::StandardComposer::lookup_map).
+    pub fn insert_map_row(&mut self, key: F, value: F) {
+        self.insert_row(key, F::zero(), value, F::from(MAP_TABLE_TAG));
+    }
+
+    /// Insert a row `(byte, 0, 0, 4)` for every byte, which are looked up by
+    /// [`StandardComposer::lookup_range_gate`](crate::constraint_system::StandardComposer::lookup_range_gate).
+    pub fn insert_byte_rows(&mut self) {
+        for byte in 0..256u64 {
+            self.insert_row(
+                F::from(byte),
+                F::zero(),
+                F::zero(),
+                F::from(BYTE_TABLE_TAG),
+            );
+        }
+    }
+
+    /// Insert a row `(byte, 0, T[byte], 5)` for every byte, where `T` is the
+    /// table of the byte-wise CRC-32 algorithm, which are looked up by
+    /// [`StandardComposer::crc32`](crate::constraint_system::StandardComposer::crc32).
+    pub fn insert_crc32_rows(&mut self) {
+        for byte in 0..256u64 {
+            self.insert_row(
+                F::from(byte),
+                F::zero(),
+                F::from(crc32_table_entry(byte)),
+                F::from(CRC32_TABLE_TAG),
+            );
+        }
+    }
+
     /// Function builds a table from more than one operation. This is denoted
     /// as 'Multiple Tables' in the paper. If, for example, we are using lookup
     /// tables for both XOR and mul operataions, we can create a table where the
@@ -195,6 +255,29 @@ where
         table
     }
 
+    /// Creates a table holding every byte.
+    pub fn byte_table() -> LookupTable<F> {
+        let mut table = LookupTable::new();
+        table.insert_byte_rows();
+        table
+    }
+
+    /// Creates a table holding the CRC-32 remainder of every byte.
+    pub fn crc32_table() -> LookupTable<F> {
+        let mut table = LookupTable::new();
+        table.insert_crc32_rows();
+        table
+    }
+
+    /// Creates a table mapping every key of `entries` to its value.
+    pub fn map_table(entries: &[(F, F)]) -> LookupTable<F> {
+        let mut table = LookupTable::new();
+        for (key, value) in entries {
+            table.insert_map_row(*key, *value);
+        }
+        table
+    }
+
     /// Creates an addition table for addends from the lower bound up to the
     /// upper bound 2^n
     pub fn mul_table(lower_bound: u64, n: u32) -> LookupTable<F> {
@@ -9,7 +9,7 @@
 pub(crate) mod constants;
 
 use crate::constraint_system::{Variable, WireData};
-use ark_ff::FftField;
+use ark_ff::{FftField, Field};
 use ark_poly::{
     domain::{EvaluationDomain, GeneralEvaluationDomain},
     univariate::DensePolynomial,
@@ -45,6 +45,11 @@ impl Permutation {
     /// Creates a new [`Variable`] by incrementing the index of the
     /// `variable_map`. This is correct as whenever we add a new [`Variable`]
     /// into the system It is always allocated in the `variable_map`.
+    ///
+    /// Since entries are never removed from the `variable_map`, indices are
+    /// handed out sequentially in allocation order and don't depend on the
+    /// internals of the `HashMap`, so identical build sequences always yield
+    /// identical [`Variable`]s.
     pub fn new_variable(&mut self) -> Variable {
         // Generate the Variable
         let var = Variable(self.variable_map.keys().len());
@@ -661,6 +666,7 @@ impl Permutation {
             &DensePolynomial<F>,
             &DensePolynomial<F>,
         ),
+        (q_multiset_poly, zeta, theta): (&DensePolynomial<F>, F, F),
     ) -> DensePolynomial<F> {
         let n = domain.size();
 
@@ -679,6 +685,10 @@ impl Permutation {
         // values for a single gate
         let gatewise_wires = izip!(wires.0, wires.1, wires.2, wires.3)
             .map(|(w0, w1, w2, w3)| vec![w0, w1, w2, w3]);
+        let gatewise_multisets =
+            izip!(domain.fft(q_multiset_poly), wires.0, wires.1, wires.2).map(
+                |(q, a, b, c)| multiset_factors(q, (*a, *b, *c), zeta, theta),
+            );
         let gatewise_sigmas = izip!(
             sigma_mappings.0,
             sigma_mappings.1,
@@ -724,6 +734,11 @@ impl Permutation {
                         .product::<F>(),
                 )
             })
+            // Fold in the multiset equality factors of each gate
+            .zip(gatewise_multisets)
+            .map(|((n, d), (multiset_n, multiset_d))| {
+                (n * multiset_n, d * multiset_d)
+            })
             // Divide each pair to get the single scalar representing each gate
             .map(|(n, d)| n * d.inverse().unwrap())
             // Collect into vector intermediary since rayon does not support
@@ -822,6 +837,31 @@ impl Permutation {
     }
 }
 
+/// Computes the multiset equality factors `(N, D)` that a row contributes to
+/// the numerator and denominator of the permutation grand product:
+///
+/// ```text
+/// N = 1 + q_multiset * (a + zeta * c + theta - 1)
+/// D = 1 + q_multiset * (b + zeta * c + theta - 1)
+/// ```
+///
+/// Rows with `q_multiset = 0` contribute `1` to both.
+pub(crate) fn multiset_factors<F>(
+    q_multiset: F,
+    (a, b, c): (F, F, F),
+    zeta: F,
+    theta: F,
+) -> (F, F)
+where
+    F: Field,
+{
+    let shift = zeta * c + theta - F::one();
+    (
+        F::one() + q_multiset * (a + shift),
+        F::one() + q_multiset * (b + shift),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -832,11 +872,53 @@ mod test {
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
     use ark_ec::TEModelParameters;
-    use ark_ff::{Field, PrimeField};
+    use ark_ff::{Field, PrimeField, Zero};
     use ark_poly::univariate::DensePolynomial;
     use ark_poly::Polynomial;
     use rand_core::OsRng;
 
+    fn test_deterministic_variable_indices<F, P>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        fn build<F, P>() -> (StandardComposer<F, P>, Vec<Variable>)
+        where
+            F: PrimeField,
+            P: TEModelParameters<BaseField = F>,
+        {
+            let mut cs = StandardComposer::<F, P>::new();
+            let a = cs.add_input(F::from(3u64));
+            let b = cs.add_input(F::from(5u64));
+            let c = cs
+                .arithmetic_gate(|gate| gate.witness(a, b, None).mul(F::one()));
+            let d = cs.arithmetic_gate(|gate| {
+                gate.witness(c, a, None).add(F::one(), F::one())
+            });
+            cs.range_gate(d, 8);
+            let e = cs.add_input(F::one());
+            cs.boolean_gate(e);
+            cs.assert_equal(d, c);
+            (cs, vec![a, b, c, d, e])
+        }
+
+        let (mut cs_1, vars_1) = build::<F, P>();
+        let (mut cs_2, vars_2) = build::<F, P>();
+
+        assert_eq!(vars_1, vars_2);
+        assert_eq!(cs_1.w_l, cs_2.w_l);
+        assert_eq!(cs_1.w_r, cs_2.w_r);
+        assert_eq!(cs_1.w_o, cs_2.w_o);
+        assert_eq!(cs_1.w_4, cs_2.w_4);
+        assert_eq!(cs_1.perm.variable_map, cs_2.perm.variable_map);
+
+        let n = cs_1.circuit_bound();
+        assert_eq!(
+            cs_1.perm.compute_sigma_permutations(n),
+            cs_2.perm.compute_sigma_permutations(n)
+        );
+    }
+
     fn test_multizip_permutation_poly<F, P>()
     where
         F: PrimeField,
@@ -910,6 +992,7 @@ mod test {
                 &sigma_polys[2],
                 &sigma_polys[3],
             ),
+            (&DensePolynomial::zero(), F::zero(), F::zero()),
         );
 
         let old_z = DensePolynomial::from_coefficients_vec(domain.ifft(
@@ -1405,7 +1488,8 @@ mod test {
 
     // Test on Bls12-381
     batch_test_field_params!(
-        [test_multizip_permutation_poly
+        [test_multizip_permutation_poly,
+        test_deterministic_variable_indices
         ],
         []
         => (
@@ -1416,7 +1500,8 @@ mod test {
 
     // Test on Bls12-377
     batch_test_field_params!(
-        [test_multizip_permutation_poly
+        [test_multizip_permutation_poly,
+        test_deterministic_variable_indices
         ],
         []
         => (
@@ -0,0 +1,311 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Aggregated verification of [`Proof`]s of different circuits.
+//!
+//! Every [`Proof`] is checked up to the openings of its commitments, which
+//! are then verified all at once with a single batch check of the polynomial
+//! commitment scheme. For KZG this is a single pairing check.
+
+use crate::{
+    commitment::HomomorphicCommitment,
+    error::{to_pc_error, Error},
+    proof_system::{
+        pi::PublicInputs,
+        proof::{OpeningClaim, PublicInputsClaim},
+        Proof, Verifier,
+    },
+    transcript::TranscriptProtocol,
+};
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use ark_poly_commit::{Evaluations, LabeledCommitment, QuerySet};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+use merlin::Transcript;
+
+/// Aggregates [`Proof`]s of possibly different circuits into a single batch
+/// verification.
+///
+/// The openings of all the proofs are absorbed into a transcript shared by
+/// the whole batch, from which the random challenges weighting every opening
+/// in the batch check are derived.
+pub struct ProofAggregator<F, PC>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    /// Transcript shared by all the aggregated proofs.
+    transcript: Transcript,
+
+    /// Openings left to be checked, one commitment each.
+    claims: Vec<OpeningClaim<F, PC>>,
+
+    /// Bindings to the SRS of the circuits of the aggregated proofs.
+    srs_bindings: Vec<PC::Commitment>,
+}
+
+impl<F, PC> ProofAggregator<F, PC>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    /// Creates a new `ProofAggregator` instance.
+    pub fn new(label: &'static [u8]) -> Self {
+        Self {
+            transcript: Transcript::new(label),
+            claims: Vec::new(),
+            srs_bindings: Vec::new(),
+        }
+    }
+
+    /// Returns the number of openings left to be checked.
+    pub fn num_openings(&self) -> usize {
+        self.claims.len()
+    }
+
+    /// Adds a [`Proof`] of the circuit preprocessed by `verifier` to the
+    /// batch.
+    ///
+    /// Everything but the openings of the commitments is verified right away,
+    /// so an error is returned if that part of the proof doesn't hold for
+    /// `public_inputs`.
+    pub fn add_proof<P, T>(
+        &mut self,
+        verifier: &Verifier<F, P, PC, T>,
+        proof: &Proof<F, PC>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Result<(), Error>
+    where
+        P: TEModelParameters<BaseField = F>,
+        T: TranscriptProtocol,
+    {
+        let verifier_key = verifier.verifier_key.as_ref().unwrap();
+        let claims = proof.opening_claims::<P, T>(
+            verifier_key,
+            &mut verifier.preprocessed_transcript.clone(),
+            PublicInputsClaim::Revealed(public_inputs),
+        )?;
+        self.srs_bindings.push(verifier_key.srs_binding.clone());
+        for claim in claims {
+            let claim = claim.combine();
+            self.transcript
+                .append(b"commitment", claim.commitments[0].commitment());
+            self.transcript.append(b"point", &claim.point);
+            self.transcript.append(b"value", &claim.values[0]);
+            self.transcript.append(b"opening", &claim.proof);
+            self.claims.push(claim);
+        }
+        Ok(())
+    }
+
+    /// Checks the openings of all the aggregated proofs at once.
+    ///
+    /// All the proofs must have been created with commit keys matching
+    /// `pc_verifier_key`, otherwise [`Error::SrsMismatch`] is returned.
+    pub fn verify(
+        &self,
+        pc_verifier_key: &PC::VerifierKey,
+    ) -> Result<(), Error> {
+        if !self
+            .srs_bindings
+            .iter()
+            .all(|binding| PC::check_srs_binding(pc_verifier_key, binding))
+        {
+            return Err(Error::SrsMismatch);
+        }
+        if self.claims.is_empty() {
+            return Ok(());
+        }
+        let mut transcript = self.transcript.clone();
+        let opening_challenge: F =
+            transcript.challenge_scalar(b"opening_challenge");
+        let mut seed = [0u8; 32];
+        transcript.challenge_bytes(b"batch_randomness", &mut seed);
+        let mut rng = StdRng::from_seed(seed);
+
+        // Every opening gets its own query point label, zero-padded so that
+        // the points are ordered like the opening proofs.
+        let labels = (0..self.claims.len())
+            .map(|i| format!("claim_{:08}", i))
+            .collect::<Vec<_>>();
+        let commitments = labels
+            .iter()
+            .zip(&self.claims)
+            .map(|(label, claim)| {
+                LabeledCommitment::new(
+                    label.clone(),
+                    claim.commitments[0].commitment().clone(),
+                    None,
+                )
+            })
+            .collect::<Vec<_>>();
+        let query_set: QuerySet<F> = labels
+            .iter()
+            .zip(&self.claims)
+            .map(|(label, claim)| (label.clone(), (label.clone(), claim.point)))
+            .collect();
+        let evaluations: Evaluations<F, F> = labels
+            .iter()
+            .zip(&self.claims)
+            .map(|(label, claim)| {
+                ((label.clone(), claim.point), claim.values[0])
+            })
+            .collect();
+        let proofs = self
+            .claims
+            .iter()
+            .map(|claim| claim.proof.clone())
+            .collect::<Vec<_>>();
+
+        match PC::batch_check(
+            pc_verifier_key,
+            &commitments,
+            &query_set,
+            &evaluations,
+            &proofs.into(),
+            opening_challenge,
+            &mut rng,
+        ) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Error::ProofVerificationError),
+            Err(e) => Err(to_pc_error::<F, PC>(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test,
+        constraint_system::{helper::dummy_gadget, StandardComposer},
+        proof_system::{Prover, ProverCommitment},
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use rand_core::OsRng;
+
+    /// Adds `a + b = c` with `c` given as a public input.
+    fn sum_circuit<F, P>(composer: &mut StandardComposer<F, P>)
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let a = composer.add_input(F::from(2u64));
+        let b = composer.add_input(F::from(5u64));
+        let zero = composer.zero_var();
+        composer.arithmetic_gate(|gate| {
+            gate.witness(a, b, Some(zero))
+                .add(F::one(), F::one())
+                .pi(-F::from(7u64))
+        });
+        dummy_gadget(4, composer);
+    }
+
+    /// Adds `x * y = 42` with `x` range-checked to a byte.
+    fn product_circuit<F, P>(composer: &mut StandardComposer<F, P>)
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let x = composer.add_input(F::from(6u64));
+        let y = composer.add_input(F::from(7u64));
+        composer.assert_byte(x);
+        let z = composer
+            .arithmetic_gate(|gate| gate.witness(x, y, None).mul(F::one()));
+        composer.constrain_to_constant(z, F::from(42u64), None);
+        dummy_gadget(20, composer);
+    }
+
+    /// Proves `circuit` and returns the proof, its public inputs and a
+    /// verifier for it.
+    #[allow(clippy::type_complexity)]
+    fn prove<F, P, PC>(
+        ck: &PC::CommitterKey,
+        circuit: fn(&mut StandardComposer<F, P>),
+    ) -> (Proof<F, PC>, PublicInputs<F>, Verifier<F, P, PC>)
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let mut prover = Prover::<F, P, PC>::new(b"aggregation");
+        circuit(prover.mut_cs());
+        let pi = prover.cs.get_pi().clone();
+        let proof = prover.prove(ck).unwrap();
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"aggregation");
+        circuit(verifier.mut_cs());
+        verifier.preprocess(ck).unwrap();
+        (proof, pi, verifier)
+    }
+
+    fn test_aggregate_different_circuits<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let pp = PC::setup(1 << 7, None, &mut OsRng).unwrap();
+        let (ck, vk) = PC::trim(&pp, 1 << 6, 0, None).unwrap();
+        let (sum_proof, sum_pi, sum_verifier) =
+            prove::<F, P, PC>(&ck, sum_circuit);
+        let (product_proof, product_pi, product_verifier) =
+            prove::<F, P, PC>(&ck, product_circuit);
+        assert!(
+            sum_verifier.verifier_key.as_ref().unwrap().n
+                != product_verifier.verifier_key.as_ref().unwrap().n
+        );
+
+        let mut aggregator = ProofAggregator::<F, PC>::new(b"batch");
+        aggregator
+            .add_proof(&sum_verifier, &sum_proof, &sum_pi)
+            .unwrap();
+        aggregator
+            .add_proof(&product_verifier, &product_proof, &product_pi)
+            .unwrap();
+        assert_eq!(aggregator.num_openings(), 4);
+        assert!(aggregator.verify(&vk).is_ok());
+
+        // A proof with a corrupted evaluation fails the batch.
+        let mut corrupted = product_proof;
+        corrupted.evaluations.wire_evals.a_eval += F::one();
+        let mut aggregator = ProofAggregator::<F, PC>::new(b"batch");
+        aggregator
+            .add_proof(&sum_verifier, &sum_proof, &sum_pi)
+            .unwrap();
+        aggregator
+            .add_proof(&product_verifier, &corrupted, &product_pi)
+            .unwrap();
+        assert!(aggregator.verify(&vk).is_err());
+
+        // The public inputs are still checked when a proof is added.
+        assert!(matches!(
+            ProofAggregator::<F, PC>::new(b"batch").add_proof(
+                &sum_verifier,
+                &sum_proof,
+                &product_pi
+            ),
+            Err(Error::PublicInputsDigestMismatch)
+        ));
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_aggregate_different_circuits],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_aggregate_different_circuits],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
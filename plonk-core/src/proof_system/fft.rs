@@ -0,0 +1,219 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Precomputed FFT Plans
+//!
+//! An [`EvaluationDomain`] recomputes the powers of its generator on every
+//! transform. An [`FftPlan`] computes them once, together with the other
+//! tables of a radix-2 FFT of a fixed size, so that they can be shared by
+//! every proof of the circuits of that size.
+
+use crate::util::{powers_of, EvaluationDomainExt};
+use ark_ff::FftField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Precomputed tables of the radix-2 FFTs over the evaluation domain of a
+/// fixed size, giving the same results as the transforms of the
+/// [`GeneralEvaluationDomain`] of that size.
+///
+/// A plan can be handed to a [`Prover`](super::Prover) with
+/// [`Prover::with_fft_plan`](super::Prover::with_fft_plan).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FftPlan<F>
+where
+    F: FftField,
+{
+    /// Evaluation domain the transforms are performed over.
+    domain: GeneralEvaluationDomain<F>,
+
+    /// Powers `w^i` of the generator of the domain, for `i < size / 2`.
+    twiddles: Vec<F>,
+
+    /// Powers `w^(-i)` of the inverse of the generator of the domain, for
+    /// `i < size / 2`.
+    inverse_twiddles: Vec<F>,
+
+    /// Index of every position once its bits are reversed.
+    bit_reversal: Vec<usize>,
+
+    /// Powers `g^i` of the shift `g` of the domain into its coset, for
+    /// `i < size`.
+    coset_shifts: Vec<F>,
+
+    /// Powers `g^(-i)` of the inverse of the coset shift, for `i < size`.
+    inverse_coset_shifts: Vec<F>,
+}
+
+impl<F> FftPlan<F>
+where
+    F: FftField,
+{
+    /// Builds the plan of the evaluation domain of at least `size` elements,
+    /// i.e. the domain of size `size.next_power_of_two()`.
+    ///
+    /// Returns `None` if the field has no such radix-2 domain.
+    pub fn new(size: usize) -> Option<Self> {
+        let domain = GeneralEvaluationDomain::<F>::new(size)?;
+        let size = domain.size();
+        if !size.is_power_of_two() {
+            return None;
+        }
+        let log_size = domain.log_size_of_group();
+        let bit_reversal = (0..size)
+            .map(|i| {
+                if log_size == 0 {
+                    i
+                } else {
+                    i.reverse_bits() >> (usize::BITS - log_size)
+                }
+            })
+            .collect();
+        let coset_shift = domain.coset_shift();
+        let coset_shift_inv =
+            coset_shift.inverse().expect("The coset shift is nonzero");
+        Some(Self {
+            twiddles: powers_of(domain.group_gen()).take(size / 2).collect(),
+            inverse_twiddles: powers_of(domain.group_gen_inv())
+                .take(size / 2)
+                .collect(),
+            bit_reversal,
+            coset_shifts: powers_of(coset_shift).take(size).collect(),
+            inverse_coset_shifts: powers_of(coset_shift_inv)
+                .take(size)
+                .collect(),
+            domain,
+        })
+    }
+
+    /// Returns the size of the evaluation domain.
+    pub fn size(&self) -> usize {
+        self.domain.size()
+    }
+
+    /// Returns the evaluation domain the transforms are performed over.
+    pub fn domain(&self) -> GeneralEvaluationDomain<F> {
+        self.domain
+    }
+
+    /// Evaluates the polynomial with the given `coeffs` over the domain, like
+    /// [`EvaluationDomain::fft`].
+    pub fn fft(&self, coeffs: &[F]) -> Vec<F> {
+        let mut values = self.resized(coeffs);
+        self.transform(&mut values, &self.twiddles);
+        values
+    }
+
+    /// Interpolates the polynomial with the given `evals` over the domain,
+    /// like [`EvaluationDomain::ifft`].
+    pub fn ifft(&self, evals: &[F]) -> Vec<F> {
+        let mut values = self.resized(evals);
+        self.transform(&mut values, &self.inverse_twiddles);
+        let size_inv = self.domain.size_inv();
+        values.iter_mut().for_each(|value| *value *= size_inv);
+        values
+    }
+
+    /// Evaluates the polynomial with the given `coeffs` over the coset of the
+    /// domain, like [`EvaluationDomain::coset_fft`].
+    pub fn coset_fft(&self, coeffs: &[F]) -> Vec<F> {
+        let mut values = self.resized(coeffs);
+        values
+            .iter_mut()
+            .zip(&self.coset_shifts)
+            .for_each(|(value, shift)| *value *= shift);
+        self.transform(&mut values, &self.twiddles);
+        values
+    }
+
+    /// Interpolates the polynomial with the given `evals` over the coset of
+    /// the domain, like [`EvaluationDomain::coset_ifft`].
+    pub fn coset_ifft(&self, evals: &[F]) -> Vec<F> {
+        let mut values = self.ifft(evals);
+        values
+            .iter_mut()
+            .zip(&self.inverse_coset_shifts)
+            .for_each(|(value, shift)| *value *= shift);
+        values
+    }
+
+    /// Copies `values` into a vector of the size of the domain, padding it
+    /// with zeros or truncating it as the transforms of
+    /// [`EvaluationDomain`] do.
+    fn resized(&self, values: &[F]) -> Vec<F> {
+        let mut values = values.to_vec();
+        values.resize(self.size(), F::zero());
+        values
+    }
+
+    /// Performs the iterative Cooley-Tukey transform of `values` in place,
+    /// with the powers of the generator or its inverse as `twiddles`.
+    fn transform(&self, values: &mut [F], twiddles: &[F]) {
+        for (i, j) in self.bit_reversal.iter().enumerate() {
+            if i < *j {
+                values.swap(i, *j);
+            }
+        }
+        let size = values.len();
+        let mut half = 1;
+        while half < size {
+            let stride = size / (2 * half);
+            ark_std::cfg_chunks_mut!(values, 2 * half).for_each(|chunk| {
+                let (low, high) = chunk.split_at_mut(half);
+                for (k, (a, b)) in low.iter_mut().zip(high).enumerate() {
+                    let t = *b * twiddles[k * stride];
+                    *b = *a - t;
+                    *a += t;
+                }
+            });
+            half *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::batch_field_test;
+    use ark_bls12_377::Fr as Bls12_377_scalar_field;
+    use ark_bls12_381::Fr as Bls12_381_scalar_field;
+    use ark_ff::PrimeField;
+    use ark_std::test_rng;
+
+    fn test_fft_plan<F>()
+    where
+        F: PrimeField,
+    {
+        let rng = &mut test_rng();
+        for size in [1, 2, 16, 100] {
+            let plan = FftPlan::<F>::new(size).unwrap();
+            let domain = GeneralEvaluationDomain::<F>::new(size).unwrap();
+            assert_eq!(plan.size(), domain.size());
+            assert_eq!(plan.domain(), domain);
+
+            let values = (0..size).map(|_| F::rand(rng)).collect::<Vec<F>>();
+            assert_eq!(plan.fft(&values), domain.fft(&values));
+            assert_eq!(plan.ifft(&values), domain.ifft(&values));
+            assert_eq!(plan.coset_fft(&values), domain.coset_fft(&values));
+            assert_eq!(plan.coset_ifft(&values), domain.coset_ifft(&values));
+            assert_eq!(plan.ifft(&plan.fft(&values))[..size], values[..]);
+        }
+
+        let two_adicity = <F::FftParams as ark_ff::FftParameters>::TWO_ADICITY;
+        assert!(FftPlan::<F>::new(1 << (two_adicity + 1)).is_none());
+    }
+
+    batch_field_test!(
+        [test_fft_plan],
+        [] => Bls12_381_scalar_field
+    );
+
+    batch_field_test!(
+        [test_fft_plan],
+        [] => Bls12_377_scalar_field
+    );
+}
@@ -68,6 +68,9 @@ where
     /// Evaluation of the permutation polynomial at `z * omega` where `omega`
     /// is a root of unity.
     pub permutation_eval: F,
+
+    /// Evaluation of the multiset equality selector polynomial at `z`.
+    pub q_multiset_eval: F,
 }
 
 // Probably all of these should go into CustomEvals
@@ -170,6 +173,7 @@ pub fn compute<F, P>(
     delta: &F,
     epsilon: &F,
     zeta: &F,
+    theta: &F,
     range_separation_challenge: &F,
     logic_separation_challenge: &F,
     fixed_base_separation_challenge: &F,
@@ -222,12 +226,15 @@ where
     let out_sigma_eval =
         prover_key.permutation.out_sigma.0.evaluate(z_challenge);
     let permutation_eval = z_poly.evaluate(&shifted_z_challenge);
+    let q_multiset_eval =
+        prover_key.permutation.q_multiset.0.evaluate(z_challenge);
 
     let perm_evals = PermutationEvaluations {
         left_sigma_eval,
         right_sigma_eval,
         out_sigma_eval,
         permutation_eval,
+        q_multiset_eval,
     };
 
     // Arith selector evaluation
@@ -334,6 +341,7 @@ where
         (a_eval, b_eval, c_eval, d_eval),
         (left_sigma_eval, right_sigma_eval, out_sigma_eval),
         permutation_eval,
+        (q_multiset_eval, *zeta, *theta),
         z_poly,
     )?;
 
@@ -12,12 +12,20 @@ mod preprocess;
 mod quotient_poly;
 mod widget;
 
+pub mod aggregator;
+pub mod fft;
 pub mod pi;
 pub mod proof;
 pub mod prover;
+pub mod solidity;
 pub mod verifier;
 
+pub use aggregator::ProofAggregator;
+pub use fft::FftPlan;
 pub use proof::*;
-pub use prover::Prover;
+pub use prover::{
+    ProveTimings, Prover, ProverCommitment, ProverConfig, ProverTranscript,
+};
+pub use solidity::{SolidityG1Point, SolidityG2Point, SolidityVerifierParams};
 pub use verifier::Verifier;
 pub use widget::*;
@@ -8,7 +8,10 @@
 
 use crate::{
     error::Error,
-    permutation::constants::{K1, K2, K3},
+    permutation::{
+        constants::{K1, K2, K3},
+        multiset_factors,
+    },
     proof_system::linearisation_poly::ProofEvaluations,
 };
 use ark_ff::FftField;
@@ -43,6 +46,9 @@ where
     /// Fourth Permutation
     pub fourth_sigma: (DensePolynomial<F>, Evaluations<F>),
 
+    /// Multiset Equality Selector
+    pub q_multiset: (DensePolynomial<F>, Evaluations<F>),
+
     /// Linear Evaluations
     pub linear_evaluations: Evaluations<F>,
     /* Evaluations of f(x) = X
@@ -72,7 +78,14 @@ where
         l1_alpha_sq: F,
         beta: F,
         gamma: F,
+        (zeta, theta): (F, F),
     ) -> F {
+        let (multiset_num, multiset_den) = multiset_factors(
+            self.q_multiset.1[index],
+            (w_l_i, w_r_i, w_o_i),
+            zeta,
+            theta,
+        );
         let a = self.compute_quotient_identity_range_check_i(
             index, w_l_i, w_r_i, w_o_i, w_4_i, z_i, alpha, beta, gamma,
         );
@@ -80,7 +93,7 @@ where
             index, w_l_i, w_r_i, w_o_i, w_4_i, z_i_next, alpha, beta, gamma,
         );
         let c = self.compute_quotient_term_check_one_i(z_i, l1_alpha_sq);
-        a + b + c
+        a * multiset_num + b * multiset_den + c
     }
 
     /// Computes the following:
@@ -161,8 +174,15 @@ where
         (a_eval, b_eval, c_eval, d_eval): (F, F, F, F),
         (sigma_1_eval, sigma_2_eval, sigma_3_eval): (F, F, F),
         z_eval: F,
+        (q_multiset_eval, zeta, theta): (F, F, F),
         z_poly: &DensePolynomial<F>,
     ) -> Result<DensePolynomial<F>, Error> {
+        let (multiset_num, multiset_den) = multiset_factors(
+            q_multiset_eval,
+            (a_eval, b_eval, c_eval),
+            zeta,
+            theta,
+        );
         let a = self.compute_lineariser_identity_range_check(
             (a_eval, b_eval, c_eval, d_eval),
             z_challenge,
@@ -189,7 +209,7 @@ where
             alpha.square(),
             z_poly,
         );
-        Ok(&(&a + &b) + &c)
+        Ok(&(&(&a * multiset_num) + &(&b * multiset_den)) + &c)
     }
 
     /// Computes the following:
@@ -317,6 +337,9 @@ where
 
     /// Fourth Permutation Commitment
     pub fourth_sigma: PCC,
+
+    /// Multiset Equality Selector Commitment
+    pub q_multiset: PCC,
 }
 
 impl<PCC> VerifierKey<PCC>
@@ -331,10 +354,21 @@ where
         evaluations: &ProofEvaluations<F>,
         z_challenge: F,
         (alpha, beta, gamma): (F, F, F),
+        (zeta, theta): (F, F),
         l1_eval: F,
         z_comm: PCC,
     ) {
         let alpha_sq = alpha.square();
+        let (multiset_num, multiset_den) = multiset_factors(
+            evaluations.perm_evals.q_multiset_eval,
+            (
+                evaluations.wire_evals.a_eval,
+                evaluations.wire_evals.b_eval,
+                evaluations.wire_evals.c_eval,
+            ),
+            zeta,
+            theta,
+        );
 
         // (a_eval + beta * z + gamma)(b_eval + beta * z * k1 +
         // gamma)(c_eval + beta * k2 * z + gamma)(d_eval + beta
@@ -353,7 +387,7 @@ where
             let q_3 =
                 (evaluations.wire_evals.d_eval + beta_k3_z + gamma) * alpha;
 
-            q_0 * q_1 * q_2 * q_3
+            q_0 * q_1 * q_2 * q_3 * multiset_num
         };
 
         // l1(z) * alpha^2
@@ -377,7 +411,7 @@ where
 
             let q_3 = beta * evaluations.perm_evals.permutation_eval * alpha;
 
-            -(q_0 * q_1 * q_2 * q_3)
+            -(q_0 * q_1 * q_2 * q_3 * multiset_den)
         };
 
         scalars.push(y);
@@ -12,7 +12,7 @@
 //! public inputs in evaluation or coefficient form.
 
 use alloc::collections::BTreeMap;
-use ark_ff::{FftField, ToConstraintField};
+use ark_ff::{FftField, PrimeField, ToConstraintField};
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain,
     UVPolynomial,
@@ -21,8 +21,9 @@ use ark_serialize::{
     CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write,
 };
 use itertools::Itertools;
+use merlin::Transcript;
 
-use crate::prelude::Error;
+use crate::{prelude::Error, transcript::TranscriptProtocol};
 
 ///  Public Inputs
 #[derive(CanonicalDeserialize, CanonicalSerialize, derivative::Derivative)]
@@ -144,6 +145,20 @@ where
     }
 }
 
+impl<F> PublicInputs<F>
+where
+    F: PrimeField,
+{
+    /// Returns a digest of the [`PublicInputs`], used to bind them to a
+    /// [`Proof`](super::Proof) so that it can be told apart from the same
+    /// proof claimed for other public inputs.
+    pub fn digest(&self) -> F {
+        let mut transcript = Transcript::new(b"pi_digest");
+        transcript.append(b"pi", self);
+        transcript.challenge_scalar(b"digest")
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
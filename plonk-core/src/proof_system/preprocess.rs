@@ -13,6 +13,8 @@ use crate::{
     label_polynomial,
     lookup::PreprocessedLookupTable,
     proof_system::{widget, ProverKey},
+    transcript::TranscriptProtocol,
+    util::enter_span,
 };
 use ark_ec::TEModelParameters;
 use ark_ff::{FftField, PrimeField};
@@ -21,7 +23,6 @@ use ark_poly::{
     GeneralEvaluationDomain, UVPolynomial,
 };
 use core::marker::PhantomData;
-use merlin::Transcript;
 
 /// Struct that contains all of the selector and permutation [`Polynomial`]s in
 /// PLONK.
@@ -50,6 +51,7 @@ where
     right_sigma: DensePolynomial<F>,
     out_sigma: DensePolynomial<F>,
     fourth_sigma: DensePolynomial<F>,
+    q_multiset: DensePolynomial<F>,
 }
 
 impl<F, P> StandardComposer<F, P>
@@ -85,6 +87,7 @@ where
         self.q_range.extend(zeroes_scalar.iter());
         self.q_logic.extend(zeroes_scalar.iter());
         self.q_lookup.extend(zeroes_scalar.iter());
+        self.q_multiset.extend(zeroes_scalar.iter());
         self.q_fixed_group_add.extend(zeroes_scalar.iter());
         self.q_variable_group_add.extend(zeroes_scalar.iter());
 
@@ -96,6 +99,51 @@ where
         self.n += diff;
     }
 
+    /// Pads the circuit to the next power of two with gates whose selectors
+    /// are all zero and locks its description.
+    ///
+    /// Since public inputs are attached to gates, this also fixes their
+    /// layout. The circuit is finalized during preprocessing if this wasn't
+    /// called before, and any gate or lookup table row added afterwards makes
+    /// preprocessing and proving fail with
+    /// [`Error::CircuitChangedAfterFinalize`].
+    ///
+    /// Returns [`Error::CircuitAlreadyFinalized`] if called twice.
+    pub fn finalize(&mut self) -> Result<(), Error> {
+        if self.finalized_size.is_some() {
+            return Err(Error::CircuitAlreadyFinalized);
+        }
+        self.check_poly_same_len()?;
+        self.num_padding_gates = self.circuit_bound() - self.n;
+        self.pad(self.num_padding_gates);
+        self.finalized_size = Some(self.n);
+        self.finalized_table_size = self.lookup_table.size();
+        Ok(())
+    }
+
+    /// Returns `true` if [`StandardComposer::finalize`] has been called.
+    pub fn is_finalized(&self) -> bool {
+        self.finalized_size.is_some()
+    }
+
+    /// Checks that the circuit has been finalized and that no gate or lookup
+    /// table row was added since.
+    pub(crate) fn check_finalized(&self) -> Result<(), Error> {
+        match self.finalized_size {
+            None => Err(Error::CircuitNotFinalized),
+            Some(n)
+                if n != self.n
+                    || n != self.w_l.len()
+                    || n != self.circuit_bound()
+                    || self.finalized_table_size
+                        != self.lookup_table.size() =>
+            {
+                Err(Error::CircuitChangedAfterFinalize)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
     /// Checks that all of the wires of the composer have the same
     /// length.
     fn check_poly_same_len(&self) -> Result<(), Error> {
@@ -113,6 +161,7 @@ where
             && self.q_range.len() == k
             && self.q_logic.len() == k
             && self.q_lookup.len() == k
+            && self.q_multiset.len() == k
             && self.q_fixed_group_add.len() == k
             && self.q_variable_group_add.len() == k
             && self.w_l.len() == k
@@ -136,6 +185,7 @@ where
                     self.q_range.len(),
                     self.q_logic.len(),
                     self.q_lookup.len(),
+                    self.q_multiset.len(),
                     self.q_fixed_group_add.len(),
                     self.q_variable_group_add.len(),
                     self.w_l.len(),
@@ -159,15 +209,22 @@ where
     /// Although the prover does not need the verification key, he must compute
     /// the commitments in order to seed the transcript, allowing both the
     /// prover and verifier to have the same view
-    pub fn preprocess_prover<PC>(
+    pub fn preprocess_prover<PC, T>(
         &mut self,
         commit_key: &PC::CommitterKey,
-        transcript: &mut Transcript,
+        transcript: &mut T,
         _pc: PhantomData<PC>,
     ) -> Result<ProverKey<F>, Error>
     where
         PC: HomomorphicCommitment<F>,
+        T: TranscriptProtocol,
     {
+        enter_span!(
+            _preprocess_span,
+            "preprocess",
+            circuit_size = self.n,
+            domain_size = self.circuit_bound(),
+        );
         let (_, selectors, domain, preprocessed_table) =
             self.preprocess_shared(commit_key, transcript, _pc)?;
 
@@ -253,6 +310,10 @@ where
             domain_8n.coset_fft(&selectors.fourth_sigma),
             domain_8n,
         );
+        let q_multiset_eval_8n = Evaluations::from_vec_and_domain(
+            domain_8n.coset_fft(&selectors.q_multiset),
+            domain_8n,
+        );
         // XXX: Remove this and compute it on the fly
         let linear_eval_8n = Evaluations::from_vec_and_domain(
             domain_8n.coset_fft(&[F::zero(), F::one()]),
@@ -284,6 +345,7 @@ where
             (selectors.right_sigma, right_sigma_eval_8n),
             (selectors.out_sigma, out_sigma_eval_8n),
             (selectors.fourth_sigma, fourth_sigma_eval_8n),
+            (selectors.q_multiset, q_multiset_eval_8n),
             linear_eval_8n,
             v_h_coset_8n,
             preprocessed_table.t[0].0.clone(),
@@ -296,14 +358,15 @@ where
     /// The verifier only requires the commitments in order to verify a
     /// [`Proof`](super::Proof) We can therefore speed up preprocessing for the
     /// verifier by skipping the FFTs needed to compute the 8n evaluations.
-    pub fn preprocess_verifier<PC>(
+    pub fn preprocess_verifier<PC, T>(
         &mut self,
         commit_key: &PC::CommitterKey,
-        transcript: &mut Transcript,
+        transcript: &mut T,
         _pc: PhantomData<PC>,
     ) -> Result<widget::VerifierKey<F, PC>, Error>
     where
         PC: HomomorphicCommitment<F>,
+        T: TranscriptProtocol,
     {
         let (verifier_key, _, _, _) =
             self.preprocess_shared(commit_key, transcript, _pc)?;
@@ -315,10 +378,10 @@ where
     /// polynomials in order to commit to them and have the same transcript
     /// view.
     #[allow(clippy::type_complexity)] // FIXME: Add struct for prover side (last two tuple items).
-    fn preprocess_shared<PC>(
+    fn preprocess_shared<PC, T>(
         &mut self,
         commit_key: &PC::CommitterKey,
-        transcript: &mut Transcript,
+        transcript: &mut T,
         _pc: PhantomData<PC>,
     ) -> Result<
         (
@@ -331,7 +394,14 @@ where
     >
     where
         PC: HomomorphicCommitment<F>,
+        T: TranscriptProtocol,
     {
+        // 0. Lock the circuit description, padding it to a power of two.
+        if !self.is_finalized() {
+            self.finalize()?;
+        }
+        self.check_finalized()?;
+
         let domain = GeneralEvaluationDomain::new(self.circuit_bound()).ok_or(Error::InvalidEvalDomainSize {
             log_size_of_group: (self.circuit_bound()).trailing_zeros(),
             adicity:
@@ -345,12 +415,7 @@ where
         )
         .unwrap();
 
-        // Check that the length of the wires is consistent.
-        self.check_poly_same_len()?;
-
-        // 1. Pad circuit to a power of two
-        self.pad(domain.size() - self.n);
-
+        // 1. The circuit has been padded to a power of two when finalized.
         let q_m_poly: DensePolynomial<F> =
             DensePolynomial::from_coefficients_vec(domain.ifft(&self.q_m));
 
@@ -400,6 +465,11 @@ where
                 domain.ifft(&self.q_variable_group_add),
             );
 
+        let q_multiset_poly: DensePolynomial<F> =
+            DensePolynomial::from_coefficients_vec(
+                domain.ifft(&self.q_multiset),
+            );
+
         // 2. Compute the sigma polynomials
         let (
             left_sigma_poly,
@@ -430,6 +500,7 @@ where
                 label_polynomial!(right_sigma_poly),
                 label_polynomial!(out_sigma_poly),
                 label_polynomial!(fourth_sigma_poly),
+                label_polynomial!(q_multiset_poly),
             ]
             .iter(),
             None,
@@ -438,6 +509,7 @@ where
 
         let verifier_key = widget::VerifierKey::from_polynomial_commitments(
             self.n,
+            self.intended_pi_pos.len(),
             commitments[0].commitment().clone(), // q_m
             commitments[1].commitment().clone(), // q_l
             commitments[2].commitment().clone(), // q_r
@@ -457,10 +529,12 @@ where
             commitments[16].commitment().clone(), // right_sigma
             commitments[17].commitment().clone(), // out_sigma
             commitments[18].commitment().clone(), // fourth_sigma
+            commitments[19].commitment().clone(), // q_multiset
             preprocessed_table.t[0].1.clone(),
             preprocessed_table.t[1].1.clone(),
             preprocessed_table.t[2].1.clone(),
             preprocessed_table.t[3].1.clone(),
+            PC::srs_binding(commit_key)?,
         );
 
         let selectors = SelectorPolynomials {
@@ -483,6 +557,7 @@ where
             right_sigma: right_sigma_poly,
             out_sigma: out_sigma_poly,
             fourth_sigma: fourth_sigma_poly,
+            q_multiset: q_multiset_poly,
         };
 
         // Add the circuit description to the transcript
@@ -522,9 +597,15 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{batch_test_field_params, constraint_system::helper::*};
+    use crate::{
+        batch_test, batch_test_field_params,
+        constraint_system::helper::*,
+        proof_system::{Prover, ProverCommitment},
+    };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
+    use merlin::Transcript;
+    use rand_core::OsRng;
 
     /// Tests that the circuit gets padded to the correct length.
     // FIXME: We can do this test without dummy_gadget method.
@@ -561,6 +642,105 @@ mod test {
         assert_eq!(composer.w_o.len(), size);
     }
 
+    fn test_finalize<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let mut composer: StandardComposer<F, P> = StandardComposer::new();
+        dummy_gadget(10, &mut composer);
+        assert!(!composer.is_finalized());
+        composer.finalize().unwrap();
+        assert!(composer.is_finalized());
+        assert!(composer.n.is_power_of_two());
+        assert!(matches!(
+            composer.finalize(),
+            Err(Error::CircuitAlreadyFinalized)
+        ));
+
+        let pp = PC::setup(2 * 30, None, &mut OsRng).unwrap();
+        let (ck, _) = PC::trim(&pp, 2 * 20, 0, None).unwrap();
+        let pk = composer
+            .preprocess_prover(
+                &ck,
+                &mut Transcript::new(b"finalize"),
+                PhantomData::<PC>,
+            )
+            .unwrap();
+
+        // Adding a gate after finalizing is caught by preprocessing.
+        dummy_gadget(1, &mut composer);
+        assert!(matches!(
+            composer.preprocess_prover(
+                &ck,
+                &mut Transcript::new(b"finalize"),
+                PhantomData::<PC>,
+            ),
+            Err(Error::CircuitChangedAfterFinalize)
+        ));
+
+        // So is adding a single lookup table row, even though the table
+        // still fits in the circuit.
+        let mut composer: StandardComposer<F, P> = StandardComposer::new();
+        dummy_gadget(10, &mut composer);
+        composer.lookup_table.insert_xor_row(1, 2, 4);
+        composer.finalize().unwrap();
+        assert!(composer
+            .preprocess_prover(
+                &ck,
+                &mut Transcript::new(b"finalize"),
+                PhantomData::<PC>,
+            )
+            .is_ok());
+        composer.lookup_table.insert_xor_row(2, 3, 4);
+        assert!(composer.lookup_table.size() < composer.n);
+        assert!(matches!(
+            composer.preprocess_prover(
+                &ck,
+                &mut Transcript::new(b"finalize"),
+                PhantomData::<PC>,
+            ),
+            Err(Error::CircuitChangedAfterFinalize)
+        ));
+
+        // Proving requires the circuit to be finalized.
+        let mut prover: Prover<F, P, PC> = Prover::new(b"finalize");
+        dummy_gadget(10, prover.mut_cs());
+        assert!(matches!(
+            prover.prove_with_preprocessed(&ck, &pk, PhantomData::<PC>),
+            Err(Error::CircuitNotFinalized)
+        ));
+        prover.mut_cs().finalize().unwrap();
+        assert!(prover
+            .prove_with_preprocessed(&ck, &pk, PhantomData::<PC>)
+            .is_ok());
+
+        dummy_gadget(1, prover.mut_cs());
+        assert!(matches!(
+            prover.prove_with_preprocessed(&ck, &pk, PhantomData::<PC>),
+            Err(Error::CircuitChangedAfterFinalize)
+        ));
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_finalize],
+        [] => (
+            Bls12_381,
+            ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_finalize],
+        [] => (
+            Bls12_377,
+            ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+
     // Bls12-381 tests
     batch_test_field_params!(
         [test_pad],
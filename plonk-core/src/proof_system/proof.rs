@@ -14,6 +14,7 @@ use crate::{
     commitment::HomomorphicCommitment,
     error::Error,
     label_commitment,
+    permutation::multiset_factors,
     proof_system::{
         ecc::{CurveAddition, FixedBaseScalarMul},
         linearisation_poly::ProofEvaluations,
@@ -28,10 +29,10 @@ use ark_ec::TEModelParameters;
 
 use ark_ff::{fields::batch_inversion, FftField, PrimeField};
 use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_poly_commit::LabeledCommitment;
 use ark_serialize::{
     CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write,
 };
-use merlin::Transcript;
 
 use super::pi::PublicInputs;
 
@@ -112,6 +113,128 @@ where
 
     /// Subset of all of the evaluations added to the proof.
     pub(crate) evaluations: ProofEvaluations<F>,
+
+    /// Digest of the public inputs the proof was made for, left out of the
+    /// proofs of committed public inputs so that it doesn't leak them.
+    pub(crate) pi_digest: Option<F>,
+}
+
+/// Commitment to the public input polynomial of a [`Proof`] together with its
+/// opening at the evaluation challenge of the proof.
+///
+/// The commitment takes the place of the [`PublicInputs`] as the statement
+/// the proof is checked against, which keeps large public inputs out of the
+/// data sent to verifiers. It must therefore come from a source the verifier
+/// trusts, such as a commitment published ahead of time, and not from the
+/// prover: since the public input polynomial enters the quotient at every
+/// row, a prover free to pick it can make any circuit hold. The evaluation
+/// and its opening are produced alongside the proof, see
+/// [`Prover::prove_with_committed_pi`](super::Prover::prove_with_committed_pi).
+#[derive(CanonicalDeserialize, CanonicalSerialize, derivative::Derivative)]
+#[derivative(
+    Clone(bound = "PC::Commitment: Clone, PC::Proof: Clone"),
+    Debug(
+        bound = "PC::Commitment: core::fmt::Debug, PC::Proof: core::fmt::Debug"
+    ),
+    Eq(bound = "PC::Commitment: Eq, PC::Proof: Eq"),
+    PartialEq(bound = "PC::Commitment: PartialEq, PC::Proof: PartialEq")
+)]
+pub struct CommittedPublicInputs<F, PC>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    /// Commitment to the public input polynomial.
+    pub commitment: PC::Commitment,
+
+    /// Evaluation of the public input polynomial at the evaluation
+    /// challenge.
+    pub evaluation: F,
+
+    /// Opening proof of `evaluation`.
+    pub opening: PC::Proof,
+}
+
+/// Openings of commitments at a point with a single opening proof, which are
+/// left to be checked by the polynomial commitment scheme once the rest of a
+/// [`Proof`] is verified.
+pub(crate) struct OpeningClaim<F, PC>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    /// Commitments to the opened polynomials.
+    pub(crate) commitments: Vec<LabeledCommitment<PC::Commitment>>,
+
+    /// Point the polynomials are opened at.
+    pub(crate) point: F,
+
+    /// Claimed evaluations of the polynomials at `point`.
+    pub(crate) values: Vec<F>,
+
+    /// Opening proof of `values`.
+    pub(crate) proof: PC::Proof,
+
+    /// Challenge the polynomials were combined with by the opening proof.
+    pub(crate) challenge: F,
+}
+
+impl<F, PC> OpeningClaim<F, PC>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    /// Combines the openings into the opening of a single commitment,
+    /// which is checked with a unit challenge.
+    pub(crate) fn combine(self) -> Self {
+        let commitments = self
+            .commitments
+            .iter()
+            .map(|c| c.commitment().clone())
+            .collect::<Vec<_>>();
+        let (commitment, value) =
+            PC::combine_openings(&commitments, &self.values, self.challenge);
+        Self {
+            commitments: vec![label_commitment!(commitment)],
+            point: self.point,
+            values: vec![value],
+            proof: self.proof,
+            challenge: F::one(),
+        }
+    }
+
+    /// Checks the claim with the commitment scheme.
+    pub(crate) fn check(
+        &self,
+        verifier_key: &PC::VerifierKey,
+    ) -> Result<(), Error> {
+        match PC::check(
+            verifier_key,
+            &self.commitments,
+            &self.point,
+            self.values.iter().copied(),
+            &self.proof,
+            self.challenge,
+            None,
+        ) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Error::ProofVerificationError),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+}
+
+/// Public inputs of a [`Proof`] as seen by the verifier.
+pub(crate) enum PublicInputsClaim<'a, F, PC>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    /// The public inputs themselves.
+    Revealed(&'a PublicInputs<F>),
+
+    /// A commitment to the public input polynomial with its opening.
+    Committed(&'a CommittedPublicInputs<F, PC>),
 }
 
 impl<F, PC> Proof<F, PC>
@@ -119,25 +242,112 @@ where
     F: PrimeField,
     PC: HomomorphicCommitment<F>,
 {
+    /// Returns the digest of the [`PublicInputs`] the [`Proof`] was made for,
+    /// or `None` if the public inputs were committed to.
+    ///
+    /// It can be compared with [`PublicInputs::digest`] to detect a proof
+    /// being replayed with other public inputs without running the whole
+    /// verification. The verifier always checks it against the public inputs
+    /// it is given.
+    pub fn public_inputs_digest(&self) -> Option<F> {
+        self.pi_digest
+    }
+
     /// Performs the verification of a [`Proof`] returning a boolean result.
-    pub(crate) fn verify<P>(
+    pub(crate) fn verify<P, T>(
         &self,
         plonk_verifier_key: &PlonkVerifierKey<F, PC>,
-        transcript: &mut Transcript,
+        transcript: &mut T,
         verifier_key: &PC::VerifierKey,
         pub_inputs: &PublicInputs<F>,
     ) -> Result<(), Error>
     where
         P: TEModelParameters<BaseField = F>,
+        T: TranscriptProtocol,
     {
+        self.verify_impl::<P, T>(
+            plonk_verifier_key,
+            transcript,
+            verifier_key,
+            PublicInputsClaim::Revealed(pub_inputs),
+        )
+    }
+
+    /// Performs the verification of a [`Proof`] whose public inputs are only
+    /// known through their [`CommittedPublicInputs`].
+    pub(crate) fn verify_with_committed_pi<P, T>(
+        &self,
+        plonk_verifier_key: &PlonkVerifierKey<F, PC>,
+        transcript: &mut T,
+        verifier_key: &PC::VerifierKey,
+        committed_pi: &CommittedPublicInputs<F, PC>,
+    ) -> Result<(), Error>
+    where
+        P: TEModelParameters<BaseField = F>,
+        T: TranscriptProtocol,
+    {
+        self.verify_impl::<P, T>(
+            plonk_verifier_key,
+            transcript,
+            verifier_key,
+            PublicInputsClaim::Committed(committed_pi),
+        )
+    }
+
+    fn verify_impl<P, T>(
+        &self,
+        plonk_verifier_key: &PlonkVerifierKey<F, PC>,
+        transcript: &mut T,
+        verifier_key: &PC::VerifierKey,
+        pub_inputs: PublicInputsClaim<F, PC>,
+    ) -> Result<(), Error>
+    where
+        P: TEModelParameters<BaseField = F>,
+        T: TranscriptProtocol,
+    {
+        if !PC::check_srs_binding(verifier_key, &plonk_verifier_key.srs_binding)
+        {
+            return Err(Error::SrsMismatch);
+        }
+        self.opening_claims::<P, T>(plonk_verifier_key, transcript, pub_inputs)?
+            .iter()
+            .try_for_each(|claim| claim.check(verifier_key))
+    }
+
+    /// Verifies everything in the [`Proof`] but the openings of the
+    /// commitments, which are returned as [`OpeningClaim`]s so that they can
+    /// be checked together with the openings of other proofs.
+    pub(crate) fn opening_claims<P, T>(
+        &self,
+        plonk_verifier_key: &PlonkVerifierKey<F, PC>,
+        transcript: &mut T,
+        pub_inputs: PublicInputsClaim<F, PC>,
+    ) -> Result<Vec<OpeningClaim<F, PC>>, Error>
+    where
+        P: TEModelParameters<BaseField = F>,
+        T: TranscriptProtocol,
+    {
+        // Reject a proof replayed with other public inputs before doing any
+        // expensive work. Committed public inputs are bound by their opening.
+        if let PublicInputsClaim::Revealed(pi) = pub_inputs {
+            if self.pi_digest != Some(pi.digest()) {
+                return Err(Error::PublicInputsDigestMismatch);
+            }
+        }
+
         let domain =
             GeneralEvaluationDomain::<F>::new(plonk_verifier_key.n).ok_or(Error::InvalidEvalDomainSize {
                 log_size_of_group: plonk_verifier_key.n.trailing_zeros(),
                 adicity: <<F as FftField>::FftParams as ark_ff::FftParameters>::TWO_ADICITY,
             })?;
 
-        // Append Public Inputs to the transcript
-        transcript.append(b"pi", pub_inputs);
+        // Append Public Inputs, or a commitment to them, to the transcript
+        match pub_inputs {
+            PublicInputsClaim::Revealed(pi) => transcript.append(b"pi", pi),
+            PublicInputsClaim::Committed(pi) => {
+                transcript.append(b"pi_comm", &pi.commitment)
+            }
+        }
 
         // Subgroup checks are done when the proof is deserialised.
 
@@ -183,6 +393,10 @@ where
         let epsilon = transcript.challenge_scalar(b"epsilon");
         transcript.append(b"epsilon", &epsilon);
 
+        // Compute multiset equality challenge `theta`.
+        let theta = transcript.challenge_scalar(b"theta");
+        transcript.append(b"theta", &theta);
+
         // Challenges must be different
         assert!(beta != gamma, "challenges must be different");
         assert!(beta != delta, "challenges must be different");
@@ -245,15 +459,24 @@ where
         let l1_eval =
             compute_first_lagrange_evaluation(&domain, &z_h_eval, &z_challenge);
 
+        // Compute the public input polynomial evaluated at `z_challenge`
+        let pi_eval = match pub_inputs {
+            PublicInputsClaim::Revealed(pi) => compute_barycentric_eval(
+                &pi.as_evals(domain.size()),
+                z_challenge,
+                &domain,
+            ),
+            PublicInputsClaim::Committed(pi) => pi.evaluation,
+        };
+
         let r0 = self.compute_r0(
-            &domain,
-            &pub_inputs.as_evals(domain.size()),
+            pi_eval,
             alpha,
             beta,
             gamma,
             delta,
             epsilon,
-            z_challenge,
+            (zeta, theta),
             l1_eval,
             self.evaluations.perm_evals.permutation_eval,
             self.evaluations.lookup_evals.z2_next_eval,
@@ -284,6 +507,10 @@ where
             b"perm_eval",
             &self.evaluations.perm_evals.permutation_eval,
         );
+        transcript.append(
+            b"q_multiset_eval",
+            &self.evaluations.perm_evals.q_multiset_eval,
+        );
 
         transcript.append(b"f_eval", &self.evaluations.lookup_evals.f_eval);
         transcript.append(
@@ -319,6 +546,7 @@ where
             delta,
             epsilon,
             zeta,
+            theta,
             range_sep_challenge,
             logic_sep_challenge,
             fixed_base_sep_challenge,
@@ -365,6 +593,7 @@ where
             label_commitment!(plonk_verifier_key.permutation.left_sigma),
             label_commitment!(plonk_verifier_key.permutation.right_sigma),
             label_commitment!(plonk_verifier_key.permutation.out_sigma),
+            label_commitment!(plonk_verifier_key.permutation.q_multiset),
             label_commitment!(self.f_comm),
             label_commitment!(self.h_2_comm),
             label_commitment!(table_comm),
@@ -379,6 +608,7 @@ where
             self.evaluations.perm_evals.left_sigma_eval,
             self.evaluations.perm_evals.right_sigma_eval,
             self.evaluations.perm_evals.out_sigma_eval,
+            self.evaluations.perm_evals.q_multiset_eval,
             self.evaluations.lookup_evals.f_eval,
             self.evaluations.lookup_evals.h2_eval,
             self.evaluations.lookup_evals.table_eval,
@@ -411,46 +641,43 @@ where
             self.evaluations.lookup_evals.table_next_eval,
         ];
 
-        match PC::check(
-            verifier_key,
-            &aw_commits,
-            &z_challenge,
-            aw_evals,
-            &self.aw_opening,
-            aw_challenge,
-            None,
-        ) {
-            Ok(true) => Ok(()),
-            Ok(false) => Err(Error::ProofVerificationError),
-            Err(e) => panic!("{:?}", e),
+        let mut claims = vec![
+            OpeningClaim {
+                commitments: aw_commits.to_vec(),
+                point: z_challenge,
+                values: aw_evals.to_vec(),
+                proof: self.aw_opening.clone(),
+                challenge: aw_challenge,
+            },
+            OpeningClaim {
+                commitments: saw_commits.to_vec(),
+                point: z_challenge * domain.element(1),
+                values: saw_evals.to_vec(),
+                proof: self.saw_opening.clone(),
+                challenge: saw_challenge,
+            },
+        ];
+        if let PublicInputsClaim::Committed(pi) = pub_inputs {
+            claims.push(OpeningClaim {
+                commitments: vec![label_commitment!(pi.commitment)],
+                point: z_challenge,
+                values: vec![pi.evaluation],
+                proof: pi.opening.clone(),
+                challenge: transcript.challenge_scalar(b"pi_opening"),
+            });
         }
-        .and_then(|_| {
-            match PC::check(
-                verifier_key,
-                &saw_commits,
-                &(z_challenge * domain.element(1)),
-                saw_evals,
-                &self.saw_opening,
-                saw_challenge,
-                None,
-            ) {
-                Ok(true) => Ok(()),
-                Ok(false) => Err(Error::ProofVerificationError),
-                Err(e) => panic!("{:?}", e),
-            }
-        })
+        Ok(claims)
     }
 
     fn compute_r0(
         &self,
-        domain: &GeneralEvaluationDomain<F>,
-        pub_inputs: &[F],
+        pi_eval: F,
         alpha: F,
         beta: F,
         gamma: F,
         delta: F,
         epsilon: F,
-        z_challenge: F,
+        (zeta, theta): (F, F),
         l1_eval: F,
         z_hat_eval: F,
         z2_next_eval: F,
@@ -458,9 +685,6 @@ where
         h2_eval: F,
         lookup_sep_challenge: F,
     ) -> F {
-        // Compute the public input polynomial evaluated at `z_challenge`
-        let pi_eval = compute_barycentric_eval(pub_inputs, z_challenge, domain);
-
         let alpha_sq = alpha.square();
 
         let lookup_sep_challenge_sq = lookup_sep_challenge.square();
@@ -483,7 +707,19 @@ where
         let b_3 =
             (self.evaluations.wire_evals.d_eval + gamma) * z_hat_eval * alpha;
 
-        let b = b_0 * b_1 * b_2 * b_3;
+        // 1 + q_multiset * (b + zeta * c + theta - 1)
+        let (_, b_4) = multiset_factors(
+            self.evaluations.perm_evals.q_multiset_eval,
+            (
+                self.evaluations.wire_evals.a_eval,
+                self.evaluations.wire_evals.b_eval,
+                self.evaluations.wire_evals.c_eval,
+            ),
+            zeta,
+            theta,
+        );
+
+        let b = b_0 * b_1 * b_2 * b_3 * b_4;
 
         // l_1(z) * alpha^2
         let c = l1_eval * alpha_sq;
@@ -511,6 +747,7 @@ where
         delta: F,
         epsilon: F,
         zeta: F,
+        theta: F,
         range_sep_challenge: F,
         logic_sep_challenge: F,
         fixed_base_sep_challenge: F,
@@ -591,6 +828,7 @@ where
                 &self.evaluations,
                 z_challenge,
                 (alpha, beta, gamma),
+                (zeta, theta),
                 l1_eval,
                 self.z_comm.clone(),
             );
@@ -704,15 +942,20 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::batch_test_kzg;
+    use crate::{
+        batch_test, batch_test_kzg,
+        constraint_system::{helper::dummy_gadget, StandardComposer},
+        proof_system::{Prover, ProverCommitment, Verifier},
+    };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
+    use rand_core::OsRng;
 
     fn test_serde_proof<F, P, PC>()
     where
         F: PrimeField,
         P: TEModelParameters<BaseField = F>,
-        PC: HomomorphicCommitment<F>,
+        PC: ProverCommitment<F>,
         Proof<F, PC>: std::fmt::Debug + PartialEq,
     {
         let proof =
@@ -731,16 +974,206 @@ mod test {
         assert_eq!(proof, obtained_proof);
     }
 
+    /// Adds `a + b = c` with `c` given as a public input.
+    fn sum_circuit<F, P>(composer: &mut StandardComposer<F, P>, c: u64)
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+    {
+        let a = composer.add_input(F::from(2u64));
+        let b = composer.add_input(F::from(c - 2));
+        let zero = composer.zero_var();
+        composer.arithmetic_gate(|gate| {
+            gate.witness(a, b, Some(zero))
+                .add(F::one(), F::one())
+                .pi(-F::from(c))
+        });
+    }
+
+    fn test_committed_public_inputs<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+        PC::Commitment: PartialEq,
+    {
+        let pp = PC::setup(1 << 6, None, &mut OsRng).unwrap();
+        let prove = |c| {
+            let mut prover = Prover::<F, P, PC>::new(b"committed_pi");
+            sum_circuit(prover.mut_cs(), c);
+            dummy_gadget(4, prover.mut_cs());
+            let (ck, _) =
+                PC::trim(&pp, prover.circuit_bound(), 0, None).unwrap();
+            prover.prove_with_committed_pi(&ck).unwrap()
+        };
+        let (proof, committed_pi) = prove(7);
+
+        // No digest of the hidden public inputs is published with the proof.
+        assert_eq!(proof.public_inputs_digest(), None);
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"committed_pi");
+        sum_circuit(verifier.mut_cs(), 7);
+        dummy_gadget(4, verifier.mut_cs());
+        let (ck, vk) =
+            PC::trim(&pp, verifier.circuit_bound(), 0, None).unwrap();
+        verifier.preprocess(&ck).unwrap();
+
+        // The statement is `c = 7`, committed to by a trusted party.
+        let statement = committed_pi.commitment.clone();
+        assert!(verifier
+            .verify_with_committed_pi(&proof, &vk, &statement, &committed_pi)
+            .is_ok());
+
+        // A proof of `c = 9` holds for the commitment it comes with, so a
+        // verifier taking the commitment from the prover would accept it.
+        let (forged, forged_pi) = prove(9);
+        assert!(verifier
+            .verify_with_committed_pi(
+                &forged,
+                &vk,
+                &forged_pi.commitment,
+                &forged_pi
+            )
+            .is_ok());
+        assert!(matches!(
+            verifier
+                .verify_with_committed_pi(&forged, &vk, &statement, &forged_pi),
+            Err(Error::PublicInputsCommitmentMismatch)
+        ));
+
+        // The proof doesn't hold for the public inputs of another proof.
+        assert!(verifier
+            .verify_with_committed_pi(
+                &proof,
+                &vk,
+                &forged_pi.commitment,
+                &forged_pi
+            )
+            .is_err());
+
+        // The evaluation must match the opening of the commitment.
+        let mut tampered_pi = committed_pi;
+        tampered_pi.evaluation += F::one();
+        assert!(verifier
+            .verify_with_committed_pi(&proof, &vk, &statement, &tampered_pi)
+            .is_err());
+
+        // The transcript differs from the one of a revealed proof.
+        let mut pi = PublicInputs::new();
+        pi.add_input(0, &-F::from(7u64)).unwrap();
+        assert!(verifier.verify(&proof, &vk, &pi).is_err());
+    }
+
+    fn test_public_inputs_digest<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let pp = PC::setup(1 << 6, None, &mut OsRng).unwrap();
+        let mut prover = Prover::<F, P, PC>::new(b"pi_digest");
+        sum_circuit(prover.mut_cs(), 7);
+        dummy_gadget(4, prover.mut_cs());
+        let (ck, _) = PC::trim(&pp, prover.circuit_bound(), 0, None).unwrap();
+        let pi = prover.cs.get_pi().clone();
+        let proof = prover.prove(&ck).unwrap();
+        assert_eq!(proof.public_inputs_digest(), Some(pi.digest()));
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"pi_digest");
+        sum_circuit(verifier.mut_cs(), 7);
+        dummy_gadget(4, verifier.mut_cs());
+        let (ck, vk) =
+            PC::trim(&pp, verifier.circuit_bound(), 0, None).unwrap();
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+
+        let mut other_pi = PublicInputs::new();
+        other_pi.add_input(0, &-F::from(9u64)).unwrap();
+        assert_ne!(proof.public_inputs_digest(), Some(other_pi.digest()));
+        assert!(matches!(
+            verifier.verify(&proof, &vk, &other_pi),
+            Err(Error::PublicInputsDigestMismatch)
+        ));
+
+        // Updating the digest doesn't help, the proof still fails for the
+        // other public inputs.
+        let mut replayed = proof;
+        replayed.pi_digest = Some(other_pi.digest());
+        assert!(matches!(
+            verifier.verify(&replayed, &vk, &other_pi),
+            Err(Error::ProofVerificationError)
+        ));
+    }
+
+    fn test_srs_binding<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        // The IPA generators are derived deterministically, so only the
+        // setup of KZG gives another SRS every time.
+        let pp = PC::setup(1 << 6, None, &mut OsRng).unwrap();
+        let other_pp = PC::setup(1 << 6, None, &mut OsRng).unwrap();
+        let mut prover = Prover::<F, P, PC>::new(b"srs_binding");
+        sum_circuit(prover.mut_cs(), 7);
+        dummy_gadget(4, prover.mut_cs());
+        let (ck, _) = PC::trim(&pp, prover.circuit_bound(), 0, None).unwrap();
+        let pi = prover.cs.get_pi().clone();
+        let proof = prover.prove(&ck).unwrap();
+
+        let preprocess = |pp: &PC::UniversalParams| {
+            let mut verifier = Verifier::<F, P, PC>::new(b"srs_binding");
+            sum_circuit(verifier.mut_cs(), 7);
+            dummy_gadget(4, verifier.mut_cs());
+            let (ck, vk) =
+                PC::trim(pp, verifier.circuit_bound(), 0, None).unwrap();
+            verifier.preprocess(&ck).unwrap();
+            (verifier, vk)
+        };
+        let (verifier, vk) = preprocess(&pp);
+        let (other_verifier, other_vk) = preprocess(&other_pp);
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+
+        // The opening key of another SRS doesn't match the verifier key.
+        assert!(matches!(
+            verifier.verify(&proof, &other_vk, &pi),
+            Err(Error::SrsMismatch)
+        ));
+        assert!(matches!(
+            other_verifier.verify(&proof, &vk, &pi),
+            Err(Error::SrsMismatch)
+        ));
+
+        // The proof doesn't verify under the keys of the other SRS either.
+        assert!(matches!(
+            other_verifier.verify(&proof, &other_vk, &pi),
+            Err(Error::ProofVerificationError)
+        ));
+    }
+
     // Bls12-381 tests
     batch_test_kzg!(
-        [test_serde_proof],
+        [test_serde_proof, test_srs_binding],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+    batch_test!(
+        [test_committed_public_inputs, test_public_inputs_digest],
         [] => (
             Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
         )
     );
     // Bls12-377 tests
     batch_test_kzg!(
-        [test_serde_proof],
+        [test_serde_proof, test_srs_binding],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+    batch_test!(
+        [test_committed_public_inputs, test_public_inputs_digest],
         [] => (
             Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
         )
@@ -13,27 +13,195 @@ use crate::{
     error::{to_pc_error, Error},
     label_polynomial,
     proof_system::{
-        linearisation_poly, proof::Proof, quotient_poly, ProverKey,
+        fft::FftPlan,
+        linearisation_poly,
+        pi::PublicInputs,
+        proof::{CommittedPublicInputs, Proof},
+        quotient_poly, ProverKey, QUOTIENT_PIECES,
     },
     transcript::TranscriptProtocol,
+    util::{enter_span, exit_span},
 };
 use ark_ec::{ModelParameters, TEModelParameters};
 use ark_ff::PrimeField;
 use ark_poly::{
     univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain,
-    UVPolynomial,
+    Polynomial, UVPolynomial,
 };
 use core::marker::PhantomData;
 use itertools::izip;
 use merlin::Transcript;
+use rand_core::OsRng;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Configuration of a [`Prover`], set with [`Prover::with_config`].
+#[derive(Debug, Default)]
+pub struct ProverConfig {
+    /// Thread pool on which every parallel section of the prover (FFTs,
+    /// multi-scalar multiplications, permutation polynomials) runs. The
+    /// global pool is used when it's `None`.
+    #[cfg(feature = "parallel")]
+    pub thread_pool: Option<rayon::ThreadPool>,
+}
+
+/// [`HomomorphicCommitment`] a [`Prover`] can prove with.
+///
+/// With the `parallel` feature, proving on the
+/// [`thread_pool`](ProverConfig::thread_pool) moves the committer key onto
+/// the pool and the commitments and proofs back, so they must be thread-safe.
+/// It's implemented for every such commitment scheme.
+#[cfg(feature = "parallel")]
+pub trait ProverCommitment<F>:
+    HomomorphicCommitment<
+        F,
+        CommitterKey = <Self as ProverCommitment<F>>::SyncCommitterKey,
+        Commitment = <Self as ProverCommitment<F>>::SendCommitment,
+        Proof = <Self as ProverCommitment<F>>::SendProof,
+    > + Sync
+where
+    F: PrimeField,
+    Self::VerifierKey: core::fmt::Debug,
+{
+    /// The [`PolynomialCommitment::CommitterKey`] of the scheme.
+    ///
+    /// [`PolynomialCommitment::CommitterKey`]: ark_poly_commit::PolynomialCommitment::CommitterKey
+    type SyncCommitterKey: ark_poly_commit::PCCommitterKey + Sync;
+
+    /// The [`PolynomialCommitment::Commitment`] of the scheme.
+    ///
+    /// [`PolynomialCommitment::Commitment`]: ark_poly_commit::PolynomialCommitment::Commitment
+    type SendCommitment: ark_poly_commit::PCCommitment + Send;
+
+    /// The [`PolynomialCommitment::Proof`] of the scheme.
+    ///
+    /// [`PolynomialCommitment::Proof`]: ark_poly_commit::PolynomialCommitment::Proof
+    type SendProof: ark_poly_commit::PCProof + Clone + Send;
+}
+
+#[cfg(feature = "parallel")]
+impl<F, PC> ProverCommitment<F> for PC
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F> + Sync,
+    PC::CommitterKey: Sync,
+    PC::Commitment: Send,
+    PC::Proof: Send,
+{
+    type SyncCommitterKey = PC::CommitterKey;
+    type SendCommitment = PC::Commitment;
+    type SendProof = PC::Proof;
+}
+
+/// [`HomomorphicCommitment`] a [`Prover`] can prove with, which is any of
+/// them without the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+pub trait ProverCommitment<F>: HomomorphicCommitment<F>
+where
+    F: PrimeField,
+    Self::VerifierKey: core::fmt::Debug,
+{
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<F, PC> ProverCommitment<F> for PC
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+}
+
+/// [`TranscriptProtocol`] a [`Prover`] can prove with, which must be
+/// thread-safe with the `parallel` feature for the same reason as
+/// [`ProverCommitment`].
+#[cfg(feature = "parallel")]
+pub trait ProverTranscript: TranscriptProtocol + Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T> ProverTranscript for T where T: TranscriptProtocol + Send + Sync {}
+
+/// [`TranscriptProtocol`] a [`Prover`] can prove with, which is any of them
+/// without the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+pub trait ProverTranscript: TranscriptProtocol {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> ProverTranscript for T where T: TranscriptProtocol {}
+
+/// Wall-clock durations of the stages of a proof, as measured by
+/// [`Prover::prove_timed`].
+///
+/// Every polynomial commitment is accounted for in
+/// [`commitments`](Self::commitments) rather than in the stage computing
+/// the committed polynomial.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ProveTimings {
+    /// Time spent preprocessing the circuit, or checking that the prover key
+    /// of a previous proof can be reused, and finalizing the composer.
+    pub preprocessing: Duration,
+
+    /// Time spent interpolating the wire, lookup and permutation polynomials
+    /// from the witness.
+    pub witness_polynomials: Duration,
+
+    /// Time spent committing to polynomials.
+    pub commitments: Duration,
+
+    /// Time spent computing and splitting the quotient polynomial.
+    pub quotient: Duration,
+
+    /// Time spent computing the linearisation polynomial, the evaluations
+    /// and the opening proofs.
+    pub opening: Duration,
+}
+
+impl ProveTimings {
+    /// Runs `op`, adding the time it took to
+    /// [`commitments`](Self::commitments).
+    fn commit<R, OP>(&mut self, op: OP) -> R
+    where
+        OP: FnOnce() -> R,
+    {
+        let start = Instant::now();
+        let result = op();
+        self.commitments += start.elapsed();
+        result
+    }
+
+    /// Returns the start of a stage, along with the time spent in
+    /// commitments so far.
+    fn start(&self) -> (Instant, Duration) {
+        (Instant::now(), self.commitments)
+    }
+
+    /// Returns the time elapsed since the `start` of a stage, excluding the
+    /// time spent in commitments since then.
+    fn stage(&self, (start, commitments): (Instant, Duration)) -> Duration {
+        start
+            .elapsed()
+            .saturating_sub(self.commitments - commitments)
+    }
+}
+
+/// Result of a proof, along with the committed public inputs when they
+/// were committed to.
+type ProveResult<F, PC> =
+    Result<(Proof<F, PC>, Option<CommittedPublicInputs<F, PC>>), Error>;
 
 /// Abstraction structure designed to construct a circuit and generate
 /// [`Proof`]s for it.
-pub struct Prover<F, P, PC>
+///
+/// The Fiat-Shamir transcript defaults to the Merlin [`Transcript`] and can
+/// be replaced by any other [`TranscriptProtocol`] implementation, as long as
+/// the [`Verifier`](super::Verifier) uses the same one.
+pub struct Prover<F, P, PC, T = Transcript>
 where
     F: PrimeField,
     P: ModelParameters<BaseField = F>,
     PC: HomomorphicCommitment<F>,
+    T: TranscriptProtocol,
 {
     /// Proving Key which is used to create proofs about a specific PLONK
     /// circuit.
@@ -45,7 +213,13 @@ where
     /// Store the messages exchanged during the preprocessing stage.
     ///
     /// This is copied each time, we make a proof.
-    pub preprocessed_transcript: Transcript,
+    pub preprocessed_transcript: T,
+
+    /// Configuration of the prover.
+    config: ProverConfig,
+
+    /// FFT plan shared across proofs, set with [`Prover::with_fft_plan`].
+    fft_plan: Option<Arc<FftPlan<F>>>,
 
     _phantom: PhantomData<PC>,
 }
@@ -57,24 +231,59 @@ where
 {
     /// Creates a new `Prover` instance.
     pub fn new(label: &'static [u8]) -> Self {
+        Self::with_transcript(label)
+    }
+
+    /// Creates a new `Prover` object with some expected size.
+    pub fn with_expected_size(label: &'static [u8], size: usize) -> Self {
         Self {
             prover_key: None,
-            cs: StandardComposer::new(),
+            cs: StandardComposer::with_expected_size(size),
             preprocessed_transcript: Transcript::new(label),
+            config: ProverConfig::default(),
+            fft_plan: None,
             _phantom: PhantomData::<PC>,
         }
     }
+}
 
-    /// Creates a new `Prover` object with some expected size.
-    pub fn with_expected_size(label: &'static [u8], size: usize) -> Self {
+impl<F, P, PC, T> Prover<F, P, PC, T>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    PC: HomomorphicCommitment<F>,
+    T: TranscriptProtocol,
+{
+    /// Creates a new `Prover` instance using the transcript `T`.
+    pub fn with_transcript(label: &'static [u8]) -> Self {
         Self {
             prover_key: None,
-            cs: StandardComposer::with_expected_size(size),
-            preprocessed_transcript: Transcript::new(label),
+            cs: StandardComposer::new(),
+            preprocessed_transcript: T::new(label),
+            config: ProverConfig::default(),
+            fft_plan: None,
             _phantom: PhantomData::<PC>,
         }
     }
 
+    /// Sets the [`ProverConfig`] of the prover.
+    pub fn with_config(mut self, config: ProverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Sets the [`FftPlan`] the witness and lookup polynomials are
+    /// interpolated with, instead of recomputing the tables of the
+    /// evaluation domain on every proof.
+    ///
+    /// The plan can be shared by every prover of circuits of the same
+    /// padded size. Proving fails with [`Error::FftPlanSizeMismatch`] if the
+    /// plan is of another size than the evaluation domain of the circuit.
+    pub fn with_fft_plan(mut self, plan: Arc<FftPlan<F>>) -> Self {
+        self.fft_plan = Some(plan);
+        self
+    }
+
     /// Returns a mutable copy of the underlying [`StandardComposer`].
     pub fn mut_cs(&mut self) -> &mut StandardComposer<F, P> {
         &mut self.cs
@@ -85,30 +294,13 @@ where
         self.cs.circuit_bound()
     }
 
-    /// Preprocesses the underlying constraint system.
-    pub fn preprocess(
-        &mut self,
-        commit_key: &PC::CommitterKey,
-    ) -> Result<(), Error> {
-        if self.prover_key.is_some() {
-            return Err(Error::CircuitAlreadyPreprocessed);
-        }
-        let pk = self.cs.preprocess_prover(
-            commit_key,
-            &mut self.preprocessed_transcript,
-            PhantomData::<PC>,
-        )?;
-        self.prover_key = Some(pk);
-        Ok(())
-    }
-
     /// Split `t(X)` poly into 8 n-sized polynomials.
     #[allow(clippy::type_complexity)] // NOTE: This is an ok type for internal use.
     fn split_tx_poly(
         &self,
         n: usize,
         t_x: &DensePolynomial<F>,
-    ) -> [DensePolynomial<F>; 8] {
+    ) -> [DensePolynomial<F>; QUOTIENT_PIECES] {
         let mut buf = t_x.coeffs.to_vec();
         buf.resize(n << 3, F::zero());
 
@@ -124,9 +316,9 @@ where
         ]
     }
 
-    /// Convert variables to their actual witness values.
-    fn to_scalars(&self, vars: &[Variable]) -> Vec<F> {
-        vars.iter().map(|var| self.cs.variables[var]).collect()
+    /// Convert variables to their actual witness values in `assignment`.
+    fn to_scalars(assignment: &[F], vars: &[Variable]) -> Vec<F> {
+        vars.iter().map(|var| assignment[var.0]).collect()
     }
 
     /// Resets the witnesses in the prover object.
@@ -144,65 +336,101 @@ where
     pub fn clear(&mut self) {
         self.clear_witness();
         self.prover_key = None;
-        self.preprocessed_transcript = Transcript::new(b"plonk");
+        self.preprocessed_transcript = T::new(b"plonk");
     }
 
-    /// Keys the [`Transcript`] with additional seed information
-    /// Wrapper around [`Transcript::append_message`].
-    ///
-    /// [`Transcript`]: merlin::Transcript
-    /// [`Transcript::append_message`]: merlin::Transcript::append_message
+    /// Keys the transcript with additional seed information
+    /// Wrapper around [`TranscriptProtocol::append_message`].
     pub fn key_transcript(&mut self, label: &'static [u8], message: &[u8]) {
         self.preprocessed_transcript.append_message(label, message);
     }
 
-    /// Creates a [`Proof]` that demonstrates that a circuit is satisfied.
-    /// # Note
-    /// If you intend to construct multiple [`Proof`]s with different witnesses,
-    /// after calling this method, the user should then call
-    /// [`Prover::clear_witness`].
-    /// This is automatically done when [`Prover::prove`] is called.
-    pub fn prove_with_preprocessed(
+    /// Creates a [`Proof`] for the witness `assignment`, committing to the
+    /// public inputs and opening them at the evaluation challenge if
+    /// `commit_pi` is set, and adding the durations of its stages to
+    /// `timings`.
+    fn prove_impl(
         &self,
         commit_key: &PC::CommitterKey,
         prover_key: &ProverKey<F>,
-        _data: PhantomData<PC>,
-    ) -> Result<Proof<F, PC>, Error> {
+        assignment: &[F],
+        public_inputs: &PublicInputs<F>,
+        commit_pi: bool,
+        timings: &mut ProveTimings,
+    ) -> ProveResult<F, PC> {
+        self.cs.check_finalized()?;
+
         let domain =
             GeneralEvaluationDomain::new(self.cs.circuit_bound()).ok_or(Error::InvalidEvalDomainSize {
                 log_size_of_group: self.cs.circuit_bound().trailing_zeros(),
                 adicity: <<F as ark_ff::FftField>::FftParams as ark_ff::FftParameters>::TWO_ADICITY,
             })?;
         let n = domain.size();
+        let plan = match self.fft_plan.as_deref() {
+            Some(plan) if plan.size() != n => {
+                return Err(Error::FftPlanSizeMismatch {
+                    expected: n,
+                    found: plan.size(),
+                })
+            }
+            plan => plan,
+        };
+        let ifft = |evals: &[F]| match plan {
+            Some(plan) => plan.ifft(evals),
+            None => domain.ifft(evals),
+        };
+        enter_span!(
+            prove_span,
+            "prove",
+            circuit_size = self.cs.n,
+            domain_size = n,
+        );
 
         // Since the caller is passing a pre-processed circuit
         // We assume that the Transcript has been seeded with the preprocessed
         // Commitments
         let mut transcript = self.preprocessed_transcript.clone();
 
-        // Append Public Inputs to the transcript
-        transcript.append(b"pi", self.cs.get_pi());
+        // Compute public inputs polynomial.
+        let pi_poly = public_inputs.into_dense_poly(n);
+
+        // Append Public Inputs, or a commitment to them, to the transcript
+        let pi_commitment = if commit_pi {
+            let (pi_commits, pi_rands) = timings
+                .commit(|| {
+                    PC::commit(commit_key, &[label_polynomial!(pi_poly)], None)
+                })
+                .map_err(to_pc_error::<F, PC>)?;
+            transcript.append(b"pi_comm", pi_commits[0].commitment());
+            Some((pi_commits, pi_rands))
+        } else {
+            transcript.append(b"pi", public_inputs);
+            None
+        };
+
+        enter_span!(witness_span, "witness_commitment");
+        let witness_start = timings.start();
 
         // 1. Compute witness Polynomials
         //
         // Convert Variables to scalars padding them to the
         // correct domain size.
         let pad = vec![F::zero(); n - self.cs.w_l.len()];
-        let w_l_scalar = &[&self.to_scalars(&self.cs.w_l)[..], &pad].concat();
-        let w_r_scalar = &[&self.to_scalars(&self.cs.w_r)[..], &pad].concat();
-        let w_o_scalar = &[&self.to_scalars(&self.cs.w_o)[..], &pad].concat();
-        let w_4_scalar = &[&self.to_scalars(&self.cs.w_4)[..], &pad].concat();
+        let w_l_scalar =
+            &[&Self::to_scalars(assignment, &self.cs.w_l)[..], &pad].concat();
+        let w_r_scalar =
+            &[&Self::to_scalars(assignment, &self.cs.w_r)[..], &pad].concat();
+        let w_o_scalar =
+            &[&Self::to_scalars(assignment, &self.cs.w_o)[..], &pad].concat();
+        let w_4_scalar =
+            &[&Self::to_scalars(assignment, &self.cs.w_4)[..], &pad].concat();
 
         // Witnesses are now in evaluation form, convert them to coefficients
         // so that we may commit to them.
-        let w_l_poly =
-            DensePolynomial::from_coefficients_vec(domain.ifft(w_l_scalar));
-        let w_r_poly =
-            DensePolynomial::from_coefficients_vec(domain.ifft(w_r_scalar));
-        let w_o_poly =
-            DensePolynomial::from_coefficients_vec(domain.ifft(w_o_scalar));
-        let w_4_poly =
-            DensePolynomial::from_coefficients_vec(domain.ifft(w_4_scalar));
+        let w_l_poly = DensePolynomial::from_coefficients_vec(ifft(w_l_scalar));
+        let w_r_poly = DensePolynomial::from_coefficients_vec(ifft(w_r_scalar));
+        let w_o_poly = DensePolynomial::from_coefficients_vec(ifft(w_o_scalar));
+        let w_4_poly = DensePolynomial::from_coefficients_vec(ifft(w_4_scalar));
 
         let w_polys = [
             label_polynomial!(w_l_poly),
@@ -212,7 +440,8 @@ where
         ];
 
         // Commit to witness polynomials.
-        let (w_commits, w_rands) = PC::commit(commit_key, w_polys.iter(), None)
+        let (w_commits, w_rands) = timings
+            .commit(|| PC::commit(commit_key, w_polys.iter(), None))
             .map_err(to_pc_error::<F, PC>)?;
 
         // Add witness polynomial commitments to transcript.
@@ -239,9 +468,9 @@ where
         );
 
         // Compute table poly
-        let table_poly = DensePolynomial::from_coefficients_vec(
-            domain.ifft(&compressed_t_multiset.0),
-        );
+        let table_poly = DensePolynomial::from_coefficients_vec(ifft(
+            &compressed_t_multiset.0,
+        ));
 
         // Compute query table f
         // When q_lookup[i] is zero the wire value is replaced with a dummy
@@ -280,43 +509,46 @@ where
         let compressed_f_multiset = MultiSet::compress(&f_scalars, zeta);
 
         // Compute query poly
-        let f_poly = DensePolynomial::from_coefficients_vec(
-            domain.ifft(&compressed_f_multiset.0),
-        );
+        let f_poly = DensePolynomial::from_coefficients_vec(ifft(
+            &compressed_f_multiset.0,
+        ));
 
         // Add blinders to query polynomials
         // let f_poly = Self::add_blinder(&f_poly, n, 1);
 
         // Commit to query polynomial
-        let (f_poly_commit, _) =
-            PC::commit(commit_key, &[label_polynomial!(f_poly)], None)
-                .map_err(to_pc_error::<F, PC>)?;
+        let (f_poly_commit, _) = timings
+            .commit(|| {
+                PC::commit(commit_key, &[label_polynomial!(f_poly)], None)
+            })
+            .map_err(to_pc_error::<F, PC>)?;
 
         // Add f_poly commitment to transcript
         transcript.append(b"f", f_poly_commit[0].commitment());
 
         // Compute s, as the sorted and concatenated version of f and t
-        let (h_1, h_2) = compressed_t_multiset
-            .combine_split(&compressed_f_multiset)
-            .unwrap();
+        let (h_1, h_2) =
+            compressed_t_multiset.combine_split(&compressed_f_multiset)?;
 
         // Compute h polys
-        let h_1_poly =
-            DensePolynomial::from_coefficients_vec(domain.ifft(&h_1.0));
-        let h_2_poly =
-            DensePolynomial::from_coefficients_vec(domain.ifft(&h_2.0));
+        let h_1_poly = DensePolynomial::from_coefficients_vec(ifft(&h_1.0));
+        let h_2_poly = DensePolynomial::from_coefficients_vec(ifft(&h_2.0));
 
         // Add blinders to h polynomials
         // let h_1_poly = Self::add_blinder(&h_1_poly, n, 1);
         // let h_2_poly = Self::add_blinder(&h_2_poly, n, 1);
 
         // Commit to h polys
-        let (h_1_poly_commit, _) =
-            PC::commit(commit_key, &[label_polynomial!(h_1_poly)], None)
-                .map_err(to_pc_error::<F, PC>)?;
-        let (h_2_poly_commit, _) =
-            PC::commit(commit_key, &[label_polynomial!(h_2_poly)], None)
-                .map_err(to_pc_error::<F, PC>)?;
+        let (h_1_poly_commit, _) = timings
+            .commit(|| {
+                PC::commit(commit_key, &[label_polynomial!(h_1_poly)], None)
+            })
+            .map_err(to_pc_error::<F, PC>)?;
+        let (h_2_poly_commit, _) = timings
+            .commit(|| {
+                PC::commit(commit_key, &[label_polynomial!(h_2_poly)], None)
+            })
+            .map_err(to_pc_error::<F, PC>)?;
 
         // Add h polynomials to transcript
         transcript.append(b"h1", h_1_poly_commit[0].commitment());
@@ -338,6 +570,10 @@ where
         let epsilon = transcript.challenge_scalar(b"epsilon");
         transcript.append(b"epsilon", &epsilon);
 
+        // Compute multiset equality challenge `theta`.
+        let theta = transcript.challenge_scalar(b"theta");
+        transcript.append(b"theta", &theta);
+
         // Challenges must be different
         assert!(beta != gamma, "challenges must be different");
         assert!(beta != delta, "challenges must be different");
@@ -357,12 +593,15 @@ where
                 &prover_key.permutation.out_sigma.0,
                 &prover_key.permutation.fourth_sigma.0,
             ),
+            (&prover_key.permutation.q_multiset.0, zeta, theta),
         );
 
         // Commit to permutation polynomial.
-        let (z_poly_commit, _) =
-            PC::commit(commit_key, &[label_polynomial!(z_poly)], None)
-                .map_err(to_pc_error::<F, PC>)?;
+        let (z_poly_commit, _) = timings
+            .commit(|| {
+                PC::commit(commit_key, &[label_polynomial!(z_poly)], None)
+            })
+            .map_err(to_pc_error::<F, PC>)?;
 
         // Add permutation polynomial commitment to transcript.
         transcript.append(b"z", z_poly_commit[0].commitment());
@@ -386,12 +625,17 @@ where
         // z_2_poly = Self::add_blinder(&z_2_poly, n, 2);
 
         // Commit to lookup permutation polynomial.
-        let (z_2_poly_commit, _) =
-            PC::commit(commit_key, &[label_polynomial!(z_2_poly)], None)
-                .map_err(to_pc_error::<F, PC>)?;
+        let (z_2_poly_commit, _) = timings
+            .commit(|| {
+                PC::commit(commit_key, &[label_polynomial!(z_2_poly)], None)
+            })
+            .map_err(to_pc_error::<F, PC>)?;
+
+        timings.witness_polynomials += timings.stage(witness_start);
+        exit_span!(witness_span);
 
-        // 3. Compute public inputs polynomial.
-        let pi_poly = self.cs.get_pi().into_dense_poly(n);
+        enter_span!(quotient_span, "quotient");
+        let quotient_start = timings.start();
 
         // 4. Compute quotient polynomial
         //
@@ -447,6 +691,7 @@ where
             &delta,
             &epsilon,
             &zeta,
+            &theta,
             &range_sep_challenge,
             &logic_sep_challenge,
             &fixed_base_sep_challenge,
@@ -456,21 +701,24 @@ where
 
         let t_i_polys = self.split_tx_poly(n, &t_poly);
         // Commit to splitted quotient polynomial
-        let (t_commits, _) = PC::commit(
-            commit_key,
-            &[
-                label_polynomial!(t_i_polys[0]),
-                label_polynomial!(t_i_polys[1]),
-                label_polynomial!(t_i_polys[2]),
-                label_polynomial!(t_i_polys[3]),
-                label_polynomial!(t_i_polys[4]),
-                label_polynomial!(t_i_polys[5]),
-                label_polynomial!(t_i_polys[6]),
-                label_polynomial!(t_i_polys[7]),
-            ],
-            None,
-        )
-        .map_err(to_pc_error::<F, PC>)?;
+        let (t_commits, _) = timings
+            .commit(|| {
+                PC::commit(
+                    commit_key,
+                    &[
+                        label_polynomial!(t_i_polys[0]),
+                        label_polynomial!(t_i_polys[1]),
+                        label_polynomial!(t_i_polys[2]),
+                        label_polynomial!(t_i_polys[3]),
+                        label_polynomial!(t_i_polys[4]),
+                        label_polynomial!(t_i_polys[5]),
+                        label_polynomial!(t_i_polys[6]),
+                        label_polynomial!(t_i_polys[7]),
+                    ],
+                    None,
+                )
+            })
+            .map_err(to_pc_error::<F, PC>)?;
 
         // Add quotient polynomial commitments to transcript
         transcript.append(b"t_1", t_commits[0].commitment());
@@ -482,6 +730,12 @@ where
         transcript.append(b"t_7", t_commits[6].commitment());
         transcript.append(b"t_8", t_commits[7].commitment());
 
+        timings.quotient += timings.stage(quotient_start);
+        exit_span!(quotient_span);
+
+        enter_span!(opening_span, "opening");
+        let opening_start = timings.start();
+
         // 4. Compute linearisation polynomial
         //
         // Compute evaluation challenge; `z`.
@@ -497,6 +751,7 @@ where
             &delta,
             &epsilon,
             &zeta,
+            &theta,
             &range_sep_challenge,
             &logic_sep_challenge,
             &fixed_base_sep_challenge,
@@ -541,6 +796,10 @@ where
             .append(b"out_sig_eval", &evaluations.perm_evals.out_sigma_eval);
         transcript
             .append(b"perm_eval", &evaluations.perm_evals.permutation_eval);
+        transcript.append(
+            b"q_multiset_eval",
+            &evaluations.perm_evals.q_multiset_eval,
+        );
 
         // Third lookup evals
         transcript.append(b"f_eval", &evaluations.lookup_evals.f_eval);
@@ -583,12 +842,14 @@ where
             label_polynomial!(prover_key.permutation.left_sigma.0.clone()),
             label_polynomial!(prover_key.permutation.right_sigma.0.clone()),
             label_polynomial!(prover_key.permutation.out_sigma.0.clone()),
+            label_polynomial!(prover_key.permutation.q_multiset.0.clone()),
             label_polynomial!(f_poly),
             label_polynomial!(h_2_poly),
             label_polynomial!(table_poly),
         ];
 
-        let (aw_commits, aw_rands) = PC::commit(commit_key, &aw_polys, None)
+        let (aw_commits, aw_rands) = timings
+            .commit(|| PC::commit(commit_key, &aw_polys, None))
             .map_err(to_pc_error::<F, PC>)?;
 
         let aw_opening = PC::open(
@@ -615,7 +876,8 @@ where
             label_polynomial!(table_poly),
         ];
 
-        let (saw_commits, saw_rands) = PC::commit(commit_key, &saw_polys, None)
+        let (saw_commits, saw_rands) = timings
+            .commit(|| PC::commit(commit_key, &saw_polys, None))
             .map_err(to_pc_error::<F, PC>)?;
 
         let saw_opening = PC::open(
@@ -629,7 +891,34 @@ where
         )
         .map_err(to_pc_error::<F, PC>)?;
 
-        Ok(Proof {
+        let committed_pi = match pi_commitment {
+            Some((pi_commits, pi_rands)) => {
+                let pi_challenge: F =
+                    transcript.challenge_scalar(b"pi_opening");
+                let opening = PC::open(
+                    commit_key,
+                    &[label_polynomial!(pi_poly)],
+                    &pi_commits,
+                    &z_challenge,
+                    pi_challenge,
+                    &pi_rands,
+                    None,
+                )
+                .map_err(to_pc_error::<F, PC>)?;
+                Some(CommittedPublicInputs {
+                    commitment: pi_commits[0].commitment().clone(),
+                    evaluation: pi_poly.evaluate(&z_challenge),
+                    opening,
+                })
+            }
+            None => None,
+        };
+
+        timings.opening += timings.stage(opening_start);
+        exit_span!(opening_span);
+        exit_span!(prove_span);
+
+        let proof = Proof {
             a_comm: w_commits[0].commitment().clone(),
             b_comm: w_commits[1].commitment().clone(),
             c_comm: w_commits[2].commitment().clone(),
@@ -650,7 +939,197 @@ where
             aw_opening,
             saw_opening,
             evaluations,
-        })
+            pi_digest: (!commit_pi).then(|| public_inputs.digest()),
+        };
+        Ok((proof, committed_pi))
+    }
+}
+
+impl<F, P, PC, T> Prover<F, P, PC, T>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    PC: ProverCommitment<F>,
+    T: ProverTranscript,
+{
+    /// Preprocesses the underlying constraint system.
+    pub fn preprocess(
+        &mut self,
+        commit_key: &PC::CommitterKey,
+    ) -> Result<(), Error> {
+        if self.prover_key.is_some() {
+            return Err(Error::CircuitAlreadyPreprocessed);
+        }
+        self.prover_key = Some(self.preprocess_prover(commit_key)?);
+        Ok(())
+    }
+
+    /// Preprocesses the underlying constraint system on the configured
+    /// thread pool, if any, returning its [`ProverKey`].
+    fn preprocess_prover(
+        &mut self,
+        commit_key: &PC::CommitterKey,
+    ) -> Result<ProverKey<F>, Error> {
+        let cs = &mut self.cs;
+        let transcript = &mut self.preprocessed_transcript;
+        #[cfg(feature = "parallel")]
+        if let Some(pool) = &self.config.thread_pool {
+            return pool.install(|| {
+                cs.preprocess_prover(commit_key, transcript, PhantomData::<PC>)
+            });
+        }
+        cs.preprocess_prover(commit_key, transcript, PhantomData::<PC>)
+    }
+
+    /// Creates a [`Proof`] with [`Prover::prove_impl`] on the configured
+    /// thread pool, if any, or on the current thread otherwise.
+    fn prove_on_pool(
+        &self,
+        commit_key: &PC::CommitterKey,
+        prover_key: &ProverKey<F>,
+        assignment: &[F],
+        public_inputs: &PublicInputs<F>,
+        commit_pi: bool,
+        timings: &mut ProveTimings,
+    ) -> ProveResult<F, PC> {
+        #[cfg(feature = "parallel")]
+        if let Some(pool) = &self.config.thread_pool {
+            return pool.install(|| {
+                self.prove_impl(
+                    commit_key,
+                    prover_key,
+                    assignment,
+                    public_inputs,
+                    commit_pi,
+                    timings,
+                )
+            });
+        }
+        self.prove_impl(
+            commit_key,
+            prover_key,
+            assignment,
+            public_inputs,
+            commit_pi,
+            timings,
+        )
+    }
+
+    /// Creates a [`Proof]` that demonstrates that a circuit is satisfied.
+    ///
+    /// The circuit must have been
+    /// [finalized](StandardComposer::finalize), which is done when
+    /// preprocessing it.
+    /// # Note
+    /// If you intend to construct multiple [`Proof`]s with different witnesses,
+    /// after calling this method, the user should then call
+    /// [`Prover::clear_witness`].
+    /// This is automatically done when [`Prover::prove`] is called.
+    pub fn prove_with_preprocessed(
+        &self,
+        commit_key: &PC::CommitterKey,
+        prover_key: &ProverKey<F>,
+        _data: PhantomData<PC>,
+    ) -> Result<Proof<F, PC>, Error> {
+        self.prove_on_pool(
+            commit_key,
+            prover_key,
+            &self.cs.assignment(),
+            self.cs.get_pi(),
+            false,
+            &mut ProveTimings::default(),
+        )
+        .map(|(proof, _)| proof)
+    }
+
+    /// Creates a [`Proof`] in which the public inputs are committed to
+    /// instead of being revealed, together with the
+    /// [`CommittedPublicInputs`] the verifier needs in their place.
+    ///
+    /// The proof must be checked with
+    /// [`Verifier::verify_with_committed_pi`](super::Verifier::verify_with_committed_pi)
+    /// against a commitment the verifier trusts, since the transcript absorbs
+    /// the commitment to the public input polynomial rather than its
+    /// values.
+    ///
+    /// # Note
+    /// If you intend to construct multiple [`Proof`]s with different witnesses,
+    /// after calling this method, the user should then call
+    /// [`Prover::clear_witness`].
+    #[allow(clippy::type_complexity)]
+    pub fn prove_with_preprocessed_and_committed_pi(
+        &self,
+        commit_key: &PC::CommitterKey,
+        prover_key: &ProverKey<F>,
+    ) -> Result<(Proof<F, PC>, CommittedPublicInputs<F, PC>), Error> {
+        self.prove_on_pool(
+            commit_key,
+            prover_key,
+            &self.cs.assignment(),
+            self.cs.get_pi(),
+            true,
+            &mut ProveTimings::default(),
+        )
+        .map(|(proof, committed_pi)| (proof, committed_pi.unwrap()))
+    }
+
+    /// Creates a [`Proof`] for the circuit description held by the prover
+    /// with the witness given as a flat `assignment`, whose `i`-th value is
+    /// the one of the [`Variable`] with [index](Variable::index) `i`.
+    ///
+    /// This lets an external witness generator feed the values directly,
+    /// without going through the composer: the values held by the composer
+    /// are ignored and `public_inputs` replaces the public inputs of the
+    /// circuit, in the order their positions were added. The values assigned
+    /// to the blinding factors reserved by the composer are replaced with
+    /// fresh random ones.
+    ///
+    /// The circuit must have been
+    /// [finalized](StandardComposer::finalize), which is done when
+    /// preprocessing it. Returns [`Error::InvalidWitnessLength`] if
+    /// `assignment` doesn't hold one value per variable of the circuit and
+    /// [`Error::InvalidPublicInputLength`] if `public_inputs` doesn't hold
+    /// one value per public input position.
+    pub fn prove_with_assignment(
+        &self,
+        commit_key: &PC::CommitterKey,
+        prover_key: &ProverKey<F>,
+        assignment: &[F],
+        public_inputs: &[F],
+    ) -> Result<Proof<F, PC>, Error> {
+        let expected = self.cs.num_variables();
+        if assignment.len() != expected {
+            return Err(Error::InvalidWitnessLength {
+                expected,
+                found: assignment.len(),
+            });
+        }
+        let expected = self.cs.intended_pi_pos.len();
+        if public_inputs.len() != expected {
+            return Err(Error::InvalidPublicInputLength {
+                expected,
+                found: public_inputs.len(),
+            });
+        }
+        let public_inputs = PublicInputs::from_val_pos(
+            &self.cs.intended_pi_pos,
+            public_inputs,
+        )?;
+
+        let mut assignment = assignment.to_vec();
+        for value in &mut assignment[1..self.cs.num_reserved_vars] {
+            *value = F::rand(&mut OsRng);
+        }
+
+        self.prove_on_pool(
+            commit_key,
+            prover_key,
+            &assignment,
+            &public_inputs,
+            false,
+            &mut ProveTimings::default(),
+        )
+        .map(|(proof, _)| proof)
     }
 
     /// Proves a circuit is satisfied, then clears the witness variables
@@ -660,28 +1139,73 @@ where
         &mut self,
         commit_key: &PC::CommitterKey,
     ) -> Result<Proof<F, PC>, Error> {
-        if self.prover_key.is_none() {
-            // Preprocess circuit and store preprocessed circuit and transcript
-            // in the Prover.
-            self.prover_key = Some(self.cs.preprocess_prover(
-                commit_key,
-                &mut self.preprocessed_transcript,
-                PhantomData::<PC>,
-            )?);
-        }
+        self.prove_timed(commit_key).map(|(proof, _)| proof)
+    }
+
+    /// Proves a circuit is satisfied like [`Prover::prove`], also returning
+    /// the wall-clock durations of the stages of the proof, e.g. to compare
+    /// the costs of different curves, commitment schemes or features.
+    pub fn prove_timed(
+        &mut self,
+        commit_key: &PC::CommitterKey,
+    ) -> Result<(Proof<F, PC>, ProveTimings), Error> {
+        let mut timings = ProveTimings::default();
+        let start = Instant::now();
+        self.prepare(commit_key)?;
+        timings.preprocessing = start.elapsed();
 
         let prover_key = self.prover_key.as_ref().unwrap();
-        let proof = self.prove_with_preprocessed(
-            commit_key,
-            prover_key,
-            PhantomData::<PC>,
-        )?;
+        let proof = self
+            .prove_on_pool(
+                commit_key,
+                prover_key,
+                &self.cs.assignment(),
+                self.cs.get_pi(),
+                false,
+                &mut timings,
+            )
+            .map(|(proof, _)| proof)?;
 
         // Clear witness and reset composer variables
         self.clear_witness();
 
+        Ok((proof, timings))
+    }
+
+    /// Proves a circuit is satisfied while only committing to its public
+    /// inputs, then clears the witness variables.
+    /// If the circuit is not pre-processed, then the preprocessed circuit will
+    /// also be computed.
+    #[allow(clippy::type_complexity)]
+    pub fn prove_with_committed_pi(
+        &mut self,
+        commit_key: &PC::CommitterKey,
+    ) -> Result<(Proof<F, PC>, CommittedPublicInputs<F, PC>), Error> {
+        self.prepare(commit_key)?;
+
+        let prover_key = self.prover_key.as_ref().unwrap();
+        let proof = self
+            .prove_with_preprocessed_and_committed_pi(commit_key, prover_key)?;
+
+        self.clear_witness();
+
         Ok(proof)
     }
+
+    /// Preprocesses the circuit if it hasn't been yet, storing the prover key
+    /// in the `Prover`, and finalizes the composer.
+    fn prepare(&mut self, commit_key: &PC::CommitterKey) -> Result<(), Error> {
+        if self.prover_key.is_none() {
+            self.prover_key = Some(self.preprocess_prover(commit_key)?);
+        }
+
+        // The composer is fresh when a new witness instance was added after
+        // the previous proof.
+        if !self.cs.is_finalized() {
+            self.cs.finalize()?;
+        }
+        Ok(())
+    }
 }
 
 impl<F, P, PC> Default for Prover<F, P, PC>
@@ -695,3 +1219,326 @@ where
         Prover::new(b"plonk")
     }
 }
+
+#[cfg(all(test, feature = "tracing"))]
+mod test {
+    use super::*;
+    use crate::{batch_test, constraint_system::helper::dummy_gadget};
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use rand_core::OsRng;
+    use std::sync::{Arc, Mutex};
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    /// A span recorded by the [`CapturingSubscriber`].
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct CapturedSpan {
+        name: &'static str,
+        parent: Option<&'static str>,
+        fields: Vec<&'static str>,
+    }
+
+    /// Minimal subscriber recording every span together with its parent.
+    #[derive(Default)]
+    struct CapturingSubscriber {
+        spans: Arc<Mutex<Vec<CapturedSpan>>>,
+        stack: Mutex<Vec<u64>>,
+    }
+
+    struct FieldNames(Vec<&'static str>);
+
+    impl Visit for FieldNames {
+        fn record_debug(&mut self, field: &Field, _: &dyn core::fmt::Debug) {
+            self.0.push(field.name());
+        }
+    }
+
+    impl Subscriber for CapturingSubscriber {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+            let mut spans = self.spans.lock().unwrap();
+            let parent = self
+                .stack
+                .lock()
+                .unwrap()
+                .last()
+                .map(|id| spans[*id as usize - 1].name);
+            let mut fields = FieldNames(Vec::new());
+            attrs.record(&mut fields);
+            spans.push(CapturedSpan {
+                name: attrs.metadata().name(),
+                parent,
+                fields: fields.0,
+            });
+            span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, span: &span::Id) {
+            self.stack.lock().unwrap().push(span.into_u64());
+        }
+
+        fn exit(&self, _: &span::Id) {
+            self.stack.lock().unwrap().pop();
+        }
+    }
+
+    fn test_prover_spans<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let subscriber = CapturingSubscriber::default();
+        let spans = subscriber.spans.clone();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let pp = PC::setup(1 << 6, None, &mut OsRng).unwrap();
+            let mut prover = Prover::<F, P, PC>::new(b"tracing");
+            dummy_gadget(10, prover.mut_cs());
+            let (ck, _) =
+                PC::trim(&pp, prover.circuit_bound(), 0, None).unwrap();
+            prover.preprocess(&ck).unwrap();
+            prover.prove(&ck).unwrap();
+        });
+
+        let sizes = vec!["circuit_size", "domain_size"];
+        let span = |name, parent, fields| CapturedSpan {
+            name,
+            parent,
+            fields,
+        };
+        assert_eq!(
+            *spans.lock().unwrap(),
+            vec![
+                span("preprocess", None, sizes.clone()),
+                span("prove", None, sizes),
+                span("witness_commitment", Some("prove"), vec![]),
+                span("quotient", Some("prove"), vec![]),
+                span("opening", Some("prove"), vec![]),
+            ]
+        );
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_prover_spans],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_prover_spans],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod thread_pool_test {
+    use super::*;
+    use crate::{
+        batch_test, constraint_system::helper::dummy_gadget,
+        proof_system::Verifier,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use rand_core::OsRng;
+
+    fn test_prove_on_thread_pool<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let pp = PC::setup(1 << 7, None, &mut OsRng).unwrap();
+        let mut prover =
+            Prover::<F, P, PC>::new(b"thread_pool").with_config(ProverConfig {
+                thread_pool: Some(pool),
+            });
+        dummy_gadget(10, prover.mut_cs());
+        let (ck, vk) = PC::trim(&pp, prover.circuit_bound(), 0, None).unwrap();
+        prover.preprocess(&ck).unwrap();
+        let public_inputs = prover.cs.get_pi().clone();
+
+        // The pool is reused across proofs.
+        let mut proofs = Vec::new();
+        for _ in 0..2 {
+            proofs.push(prover.prove(&ck).unwrap());
+            dummy_gadget(10, prover.mut_cs());
+        }
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"thread_pool");
+        dummy_gadget(10, verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        for proof in proofs {
+            assert!(verifier.verify(&proof, &vk, &public_inputs).is_ok());
+        }
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_prove_on_thread_pool],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_prove_on_thread_pool],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
+
+#[cfg(test)]
+mod fft_plan_test {
+    use super::*;
+    use crate::{
+        batch_test, constraint_system::helper::dummy_gadget,
+        proof_system::Verifier,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use rand_core::OsRng;
+
+    fn test_prove_with_fft_plan<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let pp = PC::setup(1 << 7, None, &mut OsRng).unwrap();
+        let mut prover = Prover::<F, P, PC>::new(b"fft_plan");
+        dummy_gadget(10, prover.mut_cs());
+        let (ck, vk) = PC::trim(&pp, prover.circuit_bound(), 0, None).unwrap();
+        prover.preprocess(&ck).unwrap();
+        let plan = Arc::new(FftPlan::new(prover.circuit_bound()).unwrap());
+
+        // The same plan is shared by two provers and across their proofs,
+        // and their proofs verify like the ones made without a plan.
+        let mut provers = [
+            Prover::<F, P, PC>::new(b"fft_plan").with_fft_plan(plan.clone()),
+            Prover::<F, P, PC>::new(b"fft_plan").with_fft_plan(plan.clone()),
+            prover,
+        ];
+        assert_eq!(Arc::strong_count(&plan), 3);
+        let mut proofs = Vec::new();
+        for prover in &mut provers[..2] {
+            dummy_gadget(10, prover.mut_cs());
+        }
+        for prover in &mut provers {
+            for _ in 0..2 {
+                proofs.push(prover.prove(&ck).unwrap());
+                dummy_gadget(10, prover.mut_cs());
+            }
+        }
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"fft_plan");
+        dummy_gadget(10, verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        let public_inputs = provers[2].cs.get_pi().clone();
+        for proof in &proofs {
+            assert!(verifier.verify(proof, &vk, &public_inputs).is_ok());
+        }
+
+        // A plan of another size is rejected.
+        let plan = FftPlan::new(2 * provers[2].circuit_bound()).unwrap();
+        let mut prover =
+            Prover::<F, P, PC>::new(b"fft_plan").with_fft_plan(Arc::new(plan));
+        dummy_gadget(10, prover.mut_cs());
+        assert!(matches!(
+            prover.prove(&ck),
+            Err(Error::FftPlanSizeMismatch { .. })
+        ));
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_prove_with_fft_plan],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_prove_with_fft_plan],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
+
+#[cfg(test)]
+mod timings_test {
+    use super::*;
+    use crate::{
+        batch_test, constraint_system::helper::dummy_gadget,
+        proof_system::Verifier,
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use rand_core::OsRng;
+
+    fn test_prove_timed<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let pp = PC::setup(1 << 7, None, &mut OsRng).unwrap();
+        let mut prover = Prover::<F, P, PC>::new(b"timings");
+        dummy_gadget(10, prover.mut_cs());
+        let (ck, vk) = PC::trim(&pp, prover.circuit_bound(), 0, None).unwrap();
+        let public_inputs = prover.cs.get_pi().clone();
+        let (proof, timings) = prover.prove_timed(&ck).unwrap();
+
+        assert!(!timings.preprocessing.is_zero());
+        assert!(!timings.witness_polynomials.is_zero());
+        assert!(!timings.commitments.is_zero());
+        assert!(!timings.quotient.is_zero());
+        assert!(!timings.opening.is_zero());
+
+        let mut verifier = Verifier::<F, P, PC>::new(b"timings");
+        dummy_gadget(10, verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify(&proof, &vk, &public_inputs).is_ok());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_prove_timed],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_prove_timed],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
+}
@@ -51,6 +51,7 @@ pub fn compute<F, P>(
     delta: &F,
     epsilon: &F,
     zeta: &F,
+    theta: &F,
     range_challenge: &F,
     logic_challenge: &F,
     fixed_base_challenge: &F,
@@ -172,6 +173,7 @@ where
         *alpha,
         *beta,
         *gamma,
+        (*zeta, *theta),
     )?;
 
     let lookup = prover_key.lookup.compute_lookup_quotient_term(
@@ -311,6 +313,7 @@ fn compute_permutation_checks<F>(
     alpha: F,
     beta: F,
     gamma: F,
+    multiset_challenges: (F, F),
 ) -> Result<Vec<F>, Error>
 where
     F: PrimeField,
@@ -338,6 +341,7 @@ where
                 l1_alpha_sq_evals[i],
                 beta,
                 gamma,
+                multiset_challenges,
             )
         })
         .collect())
@@ -0,0 +1,308 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-Garage. All rights reserved.
+
+//! Solidity Verifier Parameters
+//!
+//! Export of a [`VerifierKey`] in the layout expected by the verifiers
+//! deployed on EVM chains, where every field element is a big-endian integer
+//! padded to a whole number of 32-byte words and every point is given by its
+//! uncompressed affine coordinates.
+
+use crate::{
+    commitment::KZG10,
+    permutation::constants::{K1, K2, K3},
+    proof_system::VerifierKey,
+    util::EvaluationDomainExt,
+};
+use ark_ec::{
+    short_weierstrass_jacobian::GroupAffine as SWGroupAffine, PairingEngine,
+    SWModelParameters,
+};
+use ark_ff::{BigInteger, Fp2, Fp2Parameters, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_poly_commit::sonic_pc;
+
+/// Point of `G1` as its uncompressed affine coordinates, each one encoded as
+/// a big-endian integer padded to a whole number of 32-byte words.
+///
+/// The point at infinity is encoded as `(0, 0)`, as done by the EVM
+/// precompiles.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SolidityG1Point {
+    /// `X`-coordinate
+    pub x: Vec<u8>,
+
+    /// `Y`-coordinate
+    pub y: Vec<u8>,
+}
+
+/// Point of `G2` as its uncompressed affine coordinates, whose coefficients
+/// `c0` and `c1` over the quadratic extension `c0 + c1 * u` are encoded like
+/// the coordinates of a [`SolidityG1Point`], in that order.
+///
+/// The point at infinity is encoded with zero coefficients.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SolidityG2Point {
+    /// `X`-coordinate
+    pub x: [Vec<u8>; 2],
+
+    /// `Y`-coordinate
+    pub y: [Vec<u8>; 2],
+}
+
+/// Parameters of a [`VerifierKey`] for a verifier written in Solidity, as
+/// computed by [`VerifierKey::to_solidity_params`].
+///
+/// The scalars are encoded like the coordinates of a [`SolidityG1Point`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SolidityVerifierParams {
+    /// Size of the evaluation domain, i.e. the circuit size padded to the
+    /// next power of two.
+    pub domain_size: u64,
+
+    /// Number of public input positions declared by the circuit.
+    pub num_public_inputs: u64,
+
+    /// Generator of the evaluation domain.
+    pub domain_generator: Vec<u8>,
+
+    /// Constants `k1`, `k2` and `k3` shifting the evaluation domain into the
+    /// cosets of the right, output and fourth wires in the permutation
+    /// argument.
+    pub coset_constants: [Vec<u8>; 3],
+
+    /// Selector, sigma and lookup table commitments together with their
+    /// labels, such as `q_m` or `left_sigma`.
+    pub commitments: Vec<(&'static str, SolidityG1Point)>,
+
+    /// Generator `h` of `G2` of the opening key, which the KZG opening check
+    /// pairs with the opening proofs.
+    pub h: SolidityG2Point,
+
+    /// Element `beta * h` of the opening key, which the KZG opening check
+    /// pairs with the evaluation points times the opening proofs.
+    pub beta_h: SolidityG2Point,
+}
+
+impl<E, P, P2, Q> VerifierKey<E::Fr, KZG10<E>>
+where
+    E: PairingEngine<G1Affine = SWGroupAffine<P>, G2Affine = SWGroupAffine<P2>>,
+    P: SWModelParameters<BaseField = E::Fq>,
+    P2: SWModelParameters<BaseField = Fp2<Q>>,
+    Q: Fp2Parameters<Fp = E::Fq>,
+{
+    /// Returns the parameters of this key in the big-endian, uncompressed
+    /// layout of the verifiers written in Solidity, together with the `G2`
+    /// elements of the `opening_key` the KZG opening check needs.
+    pub fn to_solidity_params(
+        &self,
+        opening_key: &sonic_pc::VerifierKey<E>,
+    ) -> SolidityVerifierParams {
+        let domain =
+            GeneralEvaluationDomain::<E::Fr>::new(self.padded_circuit_size())
+                .unwrap();
+        SolidityVerifierParams {
+            domain_size: domain.size() as u64,
+            num_public_inputs: self.num_public_inputs as u64,
+            domain_generator: to_solidity_word(domain.group_gen()),
+            coset_constants: [K1::<E::Fr>(), K2(), K3()].map(to_solidity_word),
+            commitments: self
+                .labeled_commitments()
+                .iter()
+                .map(|(label, commitment)| {
+                    (*label, to_solidity_point(&commitment.0))
+                })
+                .collect(),
+            h: to_solidity_g2_point(&opening_key.h),
+            beta_h: to_solidity_g2_point(&opening_key.beta_h),
+        }
+    }
+}
+
+/// Encodes `value` as a big-endian integer padded to a whole number of
+/// 32-byte words.
+fn to_solidity_word<F>(value: F) -> Vec<u8>
+where
+    F: PrimeField,
+{
+    let bytes = value.into_repr().to_bytes_be();
+    let mut word = vec![0u8; (bytes.len() + 31) / 32 * 32 - bytes.len()];
+    word.extend(bytes);
+    word
+}
+
+/// Encodes `point` as a [`SolidityG1Point`].
+fn to_solidity_point<P>(point: &SWGroupAffine<P>) -> SolidityG1Point
+where
+    P: SWModelParameters,
+    P::BaseField: PrimeField,
+{
+    if point.infinity {
+        let zero = to_solidity_word(P::BaseField::zero());
+        return SolidityG1Point {
+            x: zero.clone(),
+            y: zero,
+        };
+    }
+    SolidityG1Point {
+        x: to_solidity_word(point.x),
+        y: to_solidity_word(point.y),
+    }
+}
+
+/// Encodes `point` as a [`SolidityG2Point`].
+fn to_solidity_g2_point<P, Q>(point: &SWGroupAffine<P>) -> SolidityG2Point
+where
+    P: SWModelParameters<BaseField = Fp2<Q>>,
+    Q: Fp2Parameters,
+{
+    let coefficients = |value: Fp2<Q>| {
+        [value.c0, value.c1].map(|coefficient| {
+            to_solidity_word(if point.infinity {
+                Q::Fp::zero()
+            } else {
+                coefficient
+            })
+        })
+    };
+    SolidityG2Point {
+        x: coefficients(point.x),
+        y: coefficients(point.y),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        constraint_system::helper::dummy_gadget, proof_system::Verifier,
+    };
+    use ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine};
+    use ark_ec::AffineCurve;
+    use ark_ed_on_bls12_381::EdwardsParameters;
+    use ark_ff::{FftField, One, Zero};
+    use ark_poly_commit::{kzg10::Commitment, PolynomialCommitment};
+    use rand_core::OsRng;
+
+    /// Decodes a hexadecimal string into bytes.
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_to_solidity_params() {
+        let pp = KZG10::<Bls12_381>::setup(1 << 5, None, &mut OsRng).unwrap();
+        let mut verifier =
+            Verifier::<Fr, EdwardsParameters, KZG10<Bls12_381>>::new(
+                b"solidity",
+            );
+        dummy_gadget(7, verifier.mut_cs());
+        let (ck, mut opening_key) =
+            KZG10::trim(&pp, verifier.circuit_bound(), 0, None).unwrap();
+        verifier.preprocess(&ck).unwrap();
+
+        let generator = G1Affine::prime_subgroup_generator();
+        let mut verifier_key = verifier.verifier_key.unwrap();
+        verifier_key.n = 12;
+        verifier_key.num_public_inputs = 2;
+        verifier_key.arithmetic.q_m = Commitment(generator);
+        verifier_key.arithmetic.q_l = Commitment(G1Affine::zero());
+        verifier_key.permutation.left_sigma = Commitment(-generator);
+        opening_key.h = G2Affine::prime_subgroup_generator();
+        opening_key.beta_h = -opening_key.h;
+        let params = verifier_key.to_solidity_params(&opening_key);
+        let commitment = |label| {
+            let (_, point) = params
+                .commitments
+                .iter()
+                .find(|(name, _)| *name == label)
+                .unwrap();
+            point
+        };
+
+        assert_eq!(params.domain_size, 16);
+        assert_eq!(params.num_public_inputs, 2);
+        let domain = GeneralEvaluationDomain::<Fr>::new(16).unwrap();
+        assert_eq!(
+            params.domain_generator,
+            to_solidity_word(domain.group_gen())
+        );
+        assert_eq!(params.domain_generator.len(), 32);
+        let mut seventeen = [0u8; 32];
+        seventeen[31] = 17;
+        assert_eq!(params.coset_constants[2], seventeen);
+
+        // The coordinates of the generator of the BLS12-381 `G1`, on two
+        // 32-byte words each.
+        let x = from_hex(concat!(
+            "00000000000000000000000000000000",
+            "17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905",
+            "a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+        ));
+        let y = from_hex(concat!(
+            "00000000000000000000000000000000",
+            "08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af6",
+            "00db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"
+        ));
+        assert_eq!(params.commitments.len(), 24);
+        assert_eq!(*commitment("q_m"), SolidityG1Point { x, y });
+
+        let left_sigma = commitment("left_sigma");
+        assert_eq!(left_sigma.x, commitment("q_m").x);
+        assert_ne!(left_sigma.y, commitment("q_m").y);
+
+        assert_eq!(
+            *commitment("q_l"),
+            SolidityG1Point {
+                x: vec![0; 64],
+                y: vec![0; 64]
+            }
+        );
+        assert_eq!(
+            *commitment("right_sigma"),
+            to_solidity_point(&verifier_key.permutation.right_sigma.0)
+        );
+
+        // The coordinates of the generator of the BLS12-381 `G2`, with the
+        // coefficients `c0` and `c1` on two 32-byte words each.
+        let x = [
+            concat!(
+                "00000000000000000000000000000000",
+                "024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02",
+                "b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"
+            ),
+            concat!(
+                "00000000000000000000000000000000",
+                "13e02b6052719f607dacd3a088274f65596bd0d09920b61a",
+                "b5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e"
+            ),
+        ]
+        .map(from_hex);
+        let y = [
+            concat!(
+                "00000000000000000000000000000000",
+                "0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a7",
+                "6d429a695160d12c923ac9cc3baca289e193548608b82801"
+            ),
+            concat!(
+                "00000000000000000000000000000000",
+                "0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af",
+                "267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be"
+            ),
+        ]
+        .map(from_hex);
+        assert_eq!(params.h, SolidityG2Point { x, y });
+        assert_eq!(params.beta_h.x, params.h.x);
+        assert_ne!(params.beta_h.y, params.h.y);
+
+        // Scalars fitting in a word aren't padded further.
+        assert_eq!(to_solidity_word(Fr::one()).len(), 32);
+        assert_eq!(to_solidity_word(Fr::multiplicative_generator()).len(), 32);
+    }
+}
@@ -11,7 +11,10 @@ use crate::{
     commitment::HomomorphicCommitment,
     constraint_system::StandardComposer,
     error::Error,
-    proof_system::{widget::VerifierKey as PlonkVerifierKey, Proof},
+    proof_system::{
+        widget::VerifierKey as PlonkVerifierKey, CommittedPublicInputs, Proof,
+    },
+    transcript::TranscriptProtocol,
 };
 use ark_ec::TEModelParameters;
 use ark_ff::PrimeField;
@@ -21,11 +24,15 @@ use merlin::Transcript;
 use super::pi::PublicInputs;
 
 /// Abstraction structure designed verify [`Proof`]s.
-pub struct Verifier<F, P, PC>
+///
+/// The transcript `T` must match the one used by the
+/// [`Prover`](super::Prover) that generated the [`Proof`]s.
+pub struct Verifier<F, P, PC, T = Transcript>
 where
     F: PrimeField,
     P: TEModelParameters<BaseField = F>,
     PC: HomomorphicCommitment<F>,
+    T: TranscriptProtocol,
 {
     /// VerificationKey which is used to verify a specific PLONK circuit
     pub verifier_key: Option<PlonkVerifierKey<F, PC>>,
@@ -39,7 +46,7 @@ where
     /// verifier to verify multiple proofs from the same circuit. If this is
     /// not copied, then the verification procedure will modify the transcript,
     /// making it unusable for future proofs.
-    pub preprocessed_transcript: Transcript,
+    pub preprocessed_transcript: T,
 }
 
 impl<F, P, PC> Verifier<F, P, PC>
@@ -50,11 +57,7 @@ where
 {
     /// Creates a new `Verifier` instance.
     pub fn new(label: &'static [u8]) -> Self {
-        Self {
-            verifier_key: None,
-            cs: StandardComposer::new(),
-            preprocessed_transcript: Transcript::new(label),
-        }
+        Self::with_transcript(label)
     }
 
     /// Creates a new `Verifier` instance with some expected size.
@@ -65,6 +68,23 @@ where
             preprocessed_transcript: Transcript::new(label),
         }
     }
+}
+
+impl<F, P, PC, T> Verifier<F, P, PC, T>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    PC: HomomorphicCommitment<F>,
+    T: TranscriptProtocol,
+{
+    /// Creates a new `Verifier` instance using the transcript `T`.
+    pub fn with_transcript(label: &'static [u8]) -> Self {
+        Self {
+            verifier_key: None,
+            cs: StandardComposer::new(),
+            preprocessed_transcript: T::new(label),
+        }
+    }
 
     /// Returns the smallest power of two needed for the curcuit
     pub fn circuit_bound(&self) -> usize {
@@ -93,29 +113,58 @@ where
         Ok(())
     }
 
-    /// Keys the [`Transcript`] with additional seed information
-    /// Wrapper around [`Transcript::append_message`].
-    ///
-    /// [`Transcript`]: merlin::Transcript
-    /// [`Transcript::append_message`]: merlin::Transcript::append_message
+    /// Keys the transcript with additional seed information
+    /// Wrapper around [`TranscriptProtocol::append_message`].
     pub fn key_transcript(&mut self, label: &'static [u8], message: &[u8]) {
         self.preprocessed_transcript.append_message(label, message);
     }
 
     /// Verifies a [`Proof`] using `pc_verifier_key` and `public_inputs`.
+    ///
+    /// Returns [`Error::SrsMismatch`] if `pc_verifier_key` was trimmed from
+    /// another SRS than the one the circuit was preprocessed against.
     pub fn verify(
         &self,
         proof: &Proof<F, PC>,
         pc_verifier_key: &PC::VerifierKey,
         public_inputs: &PublicInputs<F>,
     ) -> Result<(), Error> {
-        proof.verify::<P>(
+        proof.verify::<P, T>(
             self.verifier_key.as_ref().unwrap(),
             &mut self.preprocessed_transcript.clone(),
             pc_verifier_key,
             public_inputs,
         )
     }
+
+    /// Verifies a [`Proof`] using `pc_verifier_key` and a commitment to its
+    /// public inputs instead of their values.
+    ///
+    /// `expected_pi_commitment` is the statement being verified and must come
+    /// from a trusted source. The [`Proof`] and `committed_pi` must come from
+    /// [`Prover::prove_with_committed_pi`](super::Prover::prove_with_committed_pi),
+    /// and [`Error::PublicInputsCommitmentMismatch`] is returned if
+    /// `committed_pi` opens another commitment than `expected_pi_commitment`.
+    pub fn verify_with_committed_pi(
+        &self,
+        proof: &Proof<F, PC>,
+        pc_verifier_key: &PC::VerifierKey,
+        expected_pi_commitment: &PC::Commitment,
+        committed_pi: &CommittedPublicInputs<F, PC>,
+    ) -> Result<(), Error>
+    where
+        PC::Commitment: PartialEq,
+    {
+        if committed_pi.commitment != *expected_pi_commitment {
+            return Err(Error::PublicInputsCommitmentMismatch);
+        }
+        proof.verify_with_committed_pi::<P, T>(
+            self.verifier_key.as_ref().unwrap(),
+            &mut self.preprocessed_transcript.clone(),
+            pc_verifier_key,
+            committed_pi,
+        )
+    }
 }
 
 impl<F, P, PC> Default for Verifier<F, P, PC>
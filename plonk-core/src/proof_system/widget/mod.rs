@@ -22,9 +22,13 @@ use crate::{
     transcript::TranscriptProtocol,
 };
 use ark_ff::PrimeField;
-use ark_poly::{univariate::DensePolynomial, Evaluations};
+use ark_poly::{univariate::DensePolynomial, Evaluations, Polynomial};
 use ark_serialize::*;
 
+/// Number of pieces the quotient polynomial is split into before being
+/// committed to.
+pub const QUOTIENT_PIECES: usize = 8;
+
 /// Set of values needed for a custom gate
 pub trait CustomValues<F>
 where
@@ -153,6 +157,9 @@ where
     /// Circuit size (not padded to a power of two).
     pub(crate) n: usize,
 
+    /// Number of public input positions declared by the circuit.
+    pub(crate) num_public_inputs: usize,
+
     /// Arithmetic Verifier Key
     pub(crate) arithmetic: arithmetic::VerifierKey<F, PC>,
 
@@ -173,6 +180,10 @@ where
 
     /// VerifierKey for Lookup Gate
     pub(crate) lookup: lookup::VerifierKey<F, PC>,
+
+    /// Binding to the SRS the circuit was preprocessed against, see
+    /// [`HomomorphicCommitment::srs_binding`].
+    pub(crate) srs_binding: PC::Commitment,
 }
 
 impl<F, PC> VerifierKey<F, PC>
@@ -185,6 +196,7 @@ where
     /// sigma polynomial commitments.
     pub(crate) fn from_polynomial_commitments(
         n: usize,
+        num_public_inputs: usize,
         q_m: PC::Commitment,
         q_l: PC::Commitment,
         q_r: PC::Commitment,
@@ -204,13 +216,16 @@ where
         right_sigma: PC::Commitment,
         out_sigma: PC::Commitment,
         fourth_sigma: PC::Commitment,
+        q_multiset: PC::Commitment,
         table_1: PC::Commitment,
         table_2: PC::Commitment,
         table_3: PC::Commitment,
         table_4: PC::Commitment,
+        srs_binding: PC::Commitment,
     ) -> Self {
         Self {
             n,
+            num_public_inputs,
             arithmetic: arithmetic::VerifierKey {
                 q_m,
                 q_l,
@@ -232,6 +247,7 @@ where
                 right_sigma,
                 out_sigma,
                 fourth_sigma,
+                q_multiset,
             },
             lookup: lookup::VerifierKey {
                 q_lookup,
@@ -240,6 +256,7 @@ where
                 table_3,
                 table_4,
             },
+            srs_binding,
         }
     }
 
@@ -247,6 +264,122 @@ where
     pub fn padded_circuit_size(&self) -> usize {
         self.n.next_power_of_two()
     }
+
+    /// Returns the number of public input positions the circuit declared when
+    /// it was preprocessed.
+    ///
+    /// Verifiers can use this to reject a wrongly sized set of public inputs
+    /// before doing any expensive work.
+    pub fn num_public_inputs(&self) -> usize {
+        self.num_public_inputs
+    }
+
+    /// Returns the maximum degree of the wire polynomials and of the
+    /// permutation polynomial committed to in a proof for this circuit.
+    pub fn witness_degree_bound(&self) -> usize {
+        self.padded_circuit_size() - 1
+    }
+
+    /// Returns the maximum degree of the quotient polynomial, which is
+    /// committed to in a proof as [`QUOTIENT_PIECES`] pieces of degree at most
+    /// [`VerifierKey::witness_degree_bound`] each.
+    pub fn quotient_degree_bound(&self) -> usize {
+        QUOTIENT_PIECES * self.padded_circuit_size() - 1
+    }
+}
+
+impl<F, PC> VerifierKey<F, PC>
+where
+    F: PrimeField,
+    PC: HomomorphicCommitment<F>,
+{
+    /// Compares the preprocessed commitments of `self` with the ones of
+    /// `other`, typically the key of a new version of the same circuit, and
+    /// reports which of them changed.
+    ///
+    /// The proofs made for one of the keys only verify against the other one
+    /// if the report is [`CompatibilityReport::is_compatible`].
+    pub fn is_compatible_with(&self, other: &Self) -> CompatibilityReport
+    where
+        PC::Commitment: PartialEq,
+    {
+        CompatibilityReport {
+            size_changed: self.n != other.n,
+            public_inputs_changed: self.num_public_inputs
+                != other.num_public_inputs,
+            changed_commitments: self
+                .labeled_commitments()
+                .iter()
+                .zip(other.labeled_commitments())
+                .filter(|((_, lhs), (_, rhs))| lhs != rhs)
+                .map(|((label, _), _)| *label)
+                .collect(),
+        }
+    }
+
+    /// Returns the selector, sigma and lookup table commitments together
+    /// with their labels.
+    pub(crate) fn labeled_commitments(
+        &self,
+    ) -> [(&'static str, &PC::Commitment); 24] {
+        [
+            ("q_m", &self.arithmetic.q_m),
+            ("q_l", &self.arithmetic.q_l),
+            ("q_r", &self.arithmetic.q_r),
+            ("q_o", &self.arithmetic.q_o),
+            ("q_4", &self.arithmetic.q_4),
+            ("q_c", &self.arithmetic.q_c),
+            ("q_hl", &self.arithmetic.q_hl),
+            ("q_hr", &self.arithmetic.q_hr),
+            ("q_h4", &self.arithmetic.q_h4),
+            ("q_arith", &self.arithmetic.q_arith),
+            ("q_range", &self.range_selector_commitment),
+            ("q_logic", &self.logic_selector_commitment),
+            (
+                "q_fixed_group_add",
+                &self.fixed_group_add_selector_commitment,
+            ),
+            (
+                "q_variable_group_add",
+                &self.variable_group_add_selector_commitment,
+            ),
+            ("q_lookup", &self.lookup.q_lookup),
+            ("left_sigma", &self.permutation.left_sigma),
+            ("right_sigma", &self.permutation.right_sigma),
+            ("out_sigma", &self.permutation.out_sigma),
+            ("fourth_sigma", &self.permutation.fourth_sigma),
+            ("q_multiset", &self.permutation.q_multiset),
+            ("table_1", &self.lookup.table_1),
+            ("table_2", &self.lookup.table_2),
+            ("table_3", &self.lookup.table_3),
+            ("table_4", &self.lookup.table_4),
+        ]
+    }
+}
+
+/// Differences between two [`VerifierKey`]s, as computed by
+/// [`VerifierKey::is_compatible_with`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompatibilityReport {
+    /// Whether the circuit sizes differ.
+    pub size_changed: bool,
+
+    /// Whether the numbers of public inputs differ.
+    pub public_inputs_changed: bool,
+
+    /// Labels of the selector, sigma and lookup table commitments which
+    /// differ, such as `q_m`, `left_sigma` or `table_1`.
+    pub changed_commitments: Vec<&'static str>,
+}
+
+impl CompatibilityReport {
+    /// Returns `true` if nothing changed between the two keys, in which case
+    /// they verify the same proofs.
+    pub fn is_compatible(&self) -> bool {
+        !self.size_changed
+            && !self.public_inputs_changed
+            && self.changed_commitments.is_empty()
+    }
 }
 
 impl<F, PC> VerifierKey<F, PC>
@@ -283,6 +416,7 @@ where
         transcript.append(b"right_sigma", &self.permutation.right_sigma);
         transcript.append(b"out_sigma", &self.permutation.out_sigma);
         transcript.append(b"fourth_sigma", &self.permutation.fourth_sigma);
+        transcript.append(b"q_multiset", &self.permutation.q_multiset);
         transcript.circuit_domain_sep(self.n as u64);
     }
 }
@@ -344,6 +478,23 @@ where
         &self.v_h_coset_8n
     }
 
+    /// Returns the Circuit size padded to the next power of two.
+    pub fn padded_circuit_size(&self) -> usize {
+        self.n.next_power_of_two()
+    }
+
+    /// Returns the degrees of the left, right, output and fourth sigma
+    /// polynomials, which are at most one less than the padded circuit size.
+    pub fn sigma_degrees(&self) -> [usize; 4] {
+        [
+            &self.permutation.left_sigma,
+            &self.permutation.right_sigma,
+            &self.permutation.out_sigma,
+            &self.permutation.fourth_sigma,
+        ]
+        .map(|(sigma, _)| sigma.degree())
+    }
+
     /// Constructs a [`ProverKey`] from the widget ProverKey's that are
     /// constructed based on the selector polynomials and the
     /// sigma polynomials and it's evaluations.
@@ -368,6 +519,7 @@ where
         right_sigma: (DensePolynomial<F>, Evaluations<F>),
         out_sigma: (DensePolynomial<F>, Evaluations<F>),
         fourth_sigma: (DensePolynomial<F>, Evaluations<F>),
+        q_multiset: (DensePolynomial<F>, Evaluations<F>),
         linear_evaluations: Evaluations<F>,
         v_h_coset_8n: Evaluations<F>,
         table_1: MultiSet<F>,
@@ -405,6 +557,7 @@ where
                 right_sigma,
                 out_sigma,
                 fourth_sigma,
+                q_multiset,
                 linear_evaluations,
             },
             v_h_coset_8n,
@@ -415,12 +568,16 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::batch_test;
+    use crate::{
+        batch_test, constraint_system::StandardComposer, error::to_pc_error,
+    };
     use ark_bls12_377::Bls12_377;
     use ark_bls12_381::Bls12_381;
     use ark_ec::models::TEModelParameters;
     use ark_poly::polynomial::univariate::DensePolynomial;
     use ark_poly::{EvaluationDomain, GeneralEvaluationDomain, UVPolynomial};
+    use core::marker::PhantomData;
+    use merlin::Transcript;
     use rand_core::OsRng;
 
     fn rand_poly_eval<F>(n: usize) -> (DensePolynomial<F>, Evaluations<F>)
@@ -475,6 +632,7 @@ mod test {
         let right_sigma = rand_poly_eval(n);
         let out_sigma = rand_poly_eval(n);
         let fourth_sigma = rand_poly_eval(n);
+        let q_multiset = rand_poly_eval(n);
 
         let linear_evaluations = rand_evaluations(n);
         let v_h_coset_8n = rand_evaluations(n);
@@ -504,6 +662,7 @@ mod test {
             right_sigma,
             out_sigma,
             fourth_sigma,
+            q_multiset,
             linear_evaluations,
             v_h_coset_8n,
             table_1,
@@ -556,6 +715,7 @@ mod test {
         let right_sigma = PC::Commitment::default();
         let out_sigma = PC::Commitment::default();
         let fourth_sigma = PC::Commitment::default();
+        let q_multiset = PC::Commitment::default();
 
         let table_1 = PC::Commitment::default();
         let table_2 = PC::Commitment::default();
@@ -564,6 +724,7 @@ mod test {
 
         let verifier_key = VerifierKey::<F, PC>::from_polynomial_commitments(
             n,
+            3,
             q_m,
             q_l,
             q_r,
@@ -583,10 +744,12 @@ mod test {
             right_sigma,
             out_sigma,
             fourth_sigma,
+            q_multiset,
             table_1,
             table_2,
             table_3,
             table_4,
+            PC::Commitment::default(),
         );
 
         let mut verifier_key_bytes = vec![];
@@ -601,16 +764,156 @@ mod test {
         assert!(verifier_key == obtained_vk);
     }
 
+    fn test_num_public_inputs<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let one = composer.add_input(F::one());
+        for pi in [F::one(), F::from(2u64), F::from(3u64)] {
+            composer.arithmetic_gate(|gate| {
+                gate.witness(one, one, None).add(F::one(), F::zero()).pi(pi)
+            });
+        }
+        composer.constrain_to_constant(one, F::zero(), Some(F::one()));
+        composer.arithmetic_gate(|gate| {
+            gate.witness(one, one, None).add(F::one(), F::zero())
+        });
+
+        let pp = PC::setup(composer.circuit_bound(), None, &mut OsRng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let (ck, _) = PC::trim(&pp, composer.circuit_bound(), 0, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let verifier_key = composer
+            .preprocess_verifier(
+                &ck,
+                &mut Transcript::new(b"num_public_inputs"),
+                PhantomData::<PC>,
+            )
+            .unwrap();
+
+        assert_eq!(verifier_key.num_public_inputs(), 4);
+    }
+
+    fn test_polynomial_degrees<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+    {
+        let mut composer = StandardComposer::<F, P>::new();
+        let one = composer.add_input(F::one());
+        for _ in 0..7 {
+            composer.arithmetic_gate(|gate| {
+                gate.witness(one, one, None).add(F::one(), F::one())
+            });
+        }
+        let domain =
+            GeneralEvaluationDomain::<F>::new(composer.circuit_bound())
+                .unwrap();
+        assert_eq!(domain.size(), 16);
+
+        let pp = PC::setup(composer.circuit_bound(), None, &mut OsRng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let (ck, _) = PC::trim(&pp, composer.circuit_bound(), 0, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let prover_key = composer
+            .preprocess_prover(
+                &ck,
+                &mut Transcript::new(b"degrees"),
+                PhantomData::<PC>,
+            )
+            .unwrap();
+        let verifier_key = composer
+            .preprocess_verifier(
+                &ck,
+                &mut Transcript::new(b"degrees"),
+                PhantomData::<PC>,
+            )
+            .unwrap();
+
+        assert_eq!(prover_key.padded_circuit_size(), domain.size());
+        assert_eq!(prover_key.sigma_degrees(), [domain.size() - 1; 4]);
+        assert_eq!(verifier_key.witness_degree_bound(), domain.size() - 1);
+        assert_eq!(
+            verifier_key.quotient_degree_bound(),
+            QUOTIENT_PIECES * domain.size() - 1
+        );
+    }
+
+    fn test_is_compatible_with<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: HomomorphicCommitment<F>,
+        PC::Commitment: PartialEq,
+    {
+        // The versions only differ in the right selector of the second gate.
+        let circuit = |q_r: u64| {
+            let mut composer = StandardComposer::<F, P>::new();
+            let one = composer.add_input(F::one());
+            composer.arithmetic_gate(|gate| {
+                gate.witness(one, one, None).mul(F::one())
+            });
+            composer.arithmetic_gate(|gate| {
+                gate.witness(one, one, None).add(F::one(), F::from(q_r))
+            });
+            composer
+        };
+
+        let pp = PC::setup(circuit(1).circuit_bound(), None, &mut OsRng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let (ck, _) = PC::trim(&pp, circuit(1).circuit_bound(), 0, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        let verifier_key = |q_r: u64| {
+            circuit(q_r)
+                .preprocess_verifier(
+                    &ck,
+                    &mut Transcript::new(b"compatibility"),
+                    PhantomData::<PC>,
+                )
+                .unwrap()
+        };
+
+        let report = verifier_key(1).is_compatible_with(&verifier_key(1));
+        assert!(report.is_compatible());
+        assert_eq!(report, CompatibilityReport::default());
+
+        let report = verifier_key(1).is_compatible_with(&verifier_key(2));
+        assert!(!report.is_compatible());
+        assert!(!report.size_changed);
+        assert!(!report.public_inputs_changed);
+        assert_eq!(report.changed_commitments, ["q_r"]);
+    }
+
     // Test for Bls12_381
     batch_test!(
-        [test_serialise_deserialise_verifier_key],
+        [
+            test_serialise_deserialise_verifier_key,
+            test_num_public_inputs,
+            test_polynomial_degrees,
+            test_is_compatible_with
+        ],
         [] => (
             Bls12_381, ark_ed_on_bls12_381::EdwardsParameters      )
     );
 
     // Test for Bls12_377
     batch_test!(
-        [test_serialise_deserialise_verifier_key],
+        [
+            test_serialise_deserialise_verifier_key,
+            test_num_public_inputs,
+            test_polynomial_degrees,
+            test_is_compatible_with
+        ],
         [] => (
             Bls12_377, ark_ed_on_bls12_377::EdwardsParameters       )
     );
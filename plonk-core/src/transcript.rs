@@ -13,24 +13,25 @@ use merlin::Transcript;
 
 /// Transcript adds an abstraction over the Merlin transcript
 /// For convenience
-pub(crate) trait TranscriptProtocol {
-    /// Append an `item` with the given `label`.
-    fn append(&mut self, label: &'static [u8], item: &impl CanonicalSerialize);
+pub trait TranscriptProtocol: Clone {
+    /// Creates a new transcript with the given domain separation `label`.
+    fn new(label: &'static [u8]) -> Self;
 
-    /// Compute a `label`ed challenge variable.
-    fn challenge_scalar<F: PrimeField>(&mut self, label: &'static [u8]) -> F;
+    /// Appends a `message` with the given `label`.
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]);
 
-    /// Append domain separator for the circuit size.
-    fn circuit_domain_sep(&mut self, n: u64);
-}
+    /// Fills `dest` with challenge bytes derived from the transcript so far
+    /// and the given `label`.
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]);
 
-impl TranscriptProtocol for Transcript {
+    /// Append an `item` with the given `label`.
     fn append(&mut self, label: &'static [u8], item: &impl CanonicalSerialize) {
         let mut bytes = Vec::new();
         item.serialize(&mut bytes).unwrap();
         self.append_message(label, &bytes)
     }
 
+    /// Compute a `label`ed challenge variable.
     fn challenge_scalar<F>(&mut self, label: &'static [u8]) -> F
     where
         F: PrimeField,
@@ -43,8 +44,155 @@ impl TranscriptProtocol for Transcript {
         F::from_random_bytes(&buf).unwrap()
     }
 
+    /// Append domain separator for the circuit size.
     fn circuit_domain_sep(&mut self, n: u64) {
         self.append_message(b"dom-sep", b"circuit_size");
-        self.append_u64(b"n", n);
+        self.append_message(b"n", &n.to_le_bytes());
+    }
+}
+
+impl TranscriptProtocol for Transcript {
+    fn new(label: &'static [u8]) -> Self {
+        Transcript::new(label)
+    }
+
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        Transcript::append_message(self, label, message)
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        Transcript::challenge_bytes(self, label, dest)
+    }
+}
+
+/// Transcript built on a cheap non-cryptographic hash, meant to speed up
+/// local development and testing of circuits.
+///
+/// # Warning
+///
+/// **This transcript is insecure and must never be used in production.** Its
+/// challenges are trivially predictable and malleable, so an adversary can
+/// forge [`Proof`](crate::proof_system::Proof)s for false statements. Proofs
+/// made with it are only meant to check that a circuit is satisfied and that
+/// the full proving and verifying flow runs. Use [`Transcript`] otherwise.
+#[derive(Clone, Debug)]
+pub struct FastTranscript {
+    /// Running FNV-1a state.
+    state: u64,
+}
+
+impl FastTranscript {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// Absorbs `bytes` into the running state.
+    fn absorb(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(Self::FNV_PRIME);
+        }
     }
+
+    /// Absorbs a length-prefixed `label` and `message`.
+    fn absorb_labeled(&mut self, label: &[u8], message: &[u8]) {
+        self.absorb(&(label.len() as u64).to_le_bytes());
+        self.absorb(label);
+        self.absorb(&(message.len() as u64).to_le_bytes());
+        self.absorb(message);
+    }
+}
+
+impl TranscriptProtocol for FastTranscript {
+    fn new(label: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            state: Self::FNV_OFFSET_BASIS,
+        };
+        transcript.absorb_labeled(b"dom-sep", label);
+        transcript
+    }
+
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.absorb_labeled(label, message);
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        self.absorb_labeled(label, &(dest.len() as u64).to_le_bytes());
+        for chunk in dest.chunks_mut(8) {
+            // SplitMix64 step to spread the state over the output.
+            self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = self.state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            chunk.copy_from_slice(&z.to_le_bytes()[..chunk.len()]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        batch_test,
+        constraint_system::helper::dummy_gadget,
+        error::to_pc_error,
+        proof_system::{Prover, ProverCommitment, Verifier},
+    };
+    use ark_bls12_377::Bls12_377;
+    use ark_bls12_381::Bls12_381;
+    use ark_ec::TEModelParameters;
+    use rand_core::OsRng;
+
+    fn test_prove_verify_fast_transcript<F, P, PC>()
+    where
+        F: PrimeField,
+        P: TEModelParameters<BaseField = F>,
+        PC: ProverCommitment<F>,
+    {
+        let pp = PC::setup(1 << 7, None, &mut OsRng)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+
+        let mut prover =
+            Prover::<F, P, PC, FastTranscript>::with_transcript(b"fast");
+        dummy_gadget(20, prover.mut_cs());
+        let (ck, _) = PC::trim(&pp, prover.circuit_bound(), 0, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        prover.preprocess(&ck).unwrap();
+        let public_inputs = prover.cs.get_pi().clone();
+        let proof = prover.prove(&ck).unwrap();
+
+        let mut verifier =
+            Verifier::<F, P, PC, FastTranscript>::with_transcript(b"fast");
+        dummy_gadget(20, verifier.mut_cs());
+        let (ck, vk) = PC::trim(&pp, verifier.circuit_bound(), 0, None)
+            .map_err(to_pc_error::<F, PC>)
+            .unwrap();
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify(&proof, &vk, &public_inputs).is_ok());
+
+        // The challenges differ from the Merlin ones, so the proof must not
+        // verify with the default transcript.
+        let mut verifier = Verifier::<F, P, PC>::new(b"fast");
+        dummy_gadget(20, verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify(&proof, &vk, &public_inputs).is_err());
+    }
+
+    // Bls12-381 tests
+    batch_test!(
+        [test_prove_verify_fast_transcript],
+        [] => (
+            Bls12_381, ark_ed_on_bls12_381::EdwardsParameters
+        )
+    );
+
+    // Bls12-377 tests
+    batch_test!(
+        [test_prove_verify_fast_transcript],
+        [] => (
+            Bls12_377, ark_ed_on_bls12_377::EdwardsParameters
+        )
+    );
 }
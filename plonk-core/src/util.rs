@@ -39,6 +39,73 @@ where
 
     /// Returns a fixed multiplicative generator of the finite field.
     fn generator_inv(&self) -> F;
+
+    /// Returns the shift of the coset used by [`EvaluationDomain::coset_fft`]
+    /// and [`EvaluationDomain::coset_ifft`].
+    fn coset_shift(&self) -> F {
+        F::multiplicative_generator()
+    }
+
+    /// Computes the evaluations of the polynomial with coefficients `coeffs`
+    /// over the coset `shift * H` of the domain `H`.
+    ///
+    /// With `shift` set to [`EvaluationDomainExt::coset_shift`] this is
+    /// [`EvaluationDomain::coset_fft`].
+    fn coset_fft_with_shift(&self, coeffs: &[F], shift: F) -> Vec<F> {
+        let shifted = coeffs
+            .iter()
+            .zip(powers_of(shift))
+            .map(|(c, power)| *c * power)
+            .collect::<Vec<_>>();
+        self.fft(&shifted)
+    }
+
+    /// Computes the coefficients of the polynomial with evaluations `evals`
+    /// over the coset `shift * H` of the domain `H`, inverting
+    /// [`EvaluationDomainExt::coset_fft_with_shift`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shift` is zero.
+    fn coset_ifft_with_shift(&self, evals: &[F], shift: F) -> Vec<F> {
+        let shift_inv =
+            shift.inverse().expect("The coset shift must be non-zero");
+        self.ifft(evals)
+            .into_iter()
+            .zip(powers_of(shift_inv))
+            .map(|(c, power)| c * power)
+            .collect()
+    }
+
+    /// Splits the evaluations `evals` over the domain `H = <w>` of size `n`
+    /// into the `n / coset_size` cosets of its subgroup `K` of size
+    /// `coset_size`, as needed to fold the evaluations of FRI-style
+    /// protocols.
+    ///
+    /// `K` is generated by `z = w^(n / coset_size)`, and the `j`-th returned
+    /// vector holds the evaluations over the coset `w^j * K`, in the order
+    /// `w^j, w^j * z, w^j * z^2, ...`, so that its `i`-th element is
+    /// `evals[j + i * n / coset_size]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `evals` doesn't hold one evaluation per element of the
+    /// domain or if `coset_size` doesn't divide the size of the domain.
+    fn reindex_by_coset(&self, evals: &[F], coset_size: usize) -> Vec<Vec<F>> {
+        assert_eq!(
+            evals.len(),
+            self.size(),
+            "There must be one evaluation per element of the domain"
+        );
+        assert!(
+            coset_size != 0 && self.size() % coset_size == 0,
+            "The coset size must divide the size of the domain"
+        );
+        let num_cosets = self.size() / coset_size;
+        (0..num_cosets)
+            .map(|j| evals[j..].iter().step_by(num_cosets).copied().collect())
+            .collect()
+    }
 }
 
 impl<F> EvaluationDomainExt<F> for GeneralEvaluationDomain<F>
@@ -211,6 +278,27 @@ macro_rules! get_label {
     };
 }
 
+/// Enters a `tracing` span named `$name` which stays entered until the matching
+/// [`exit_span`] call, or the end of the enclosing scope. Expands to nothing
+/// unless the `tracing` feature is enabled.
+macro_rules! enter_span {
+    ($guard:ident, $name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        let $guard =
+            tracing::info_span!($name $(, $field = $value)*).entered();
+    };
+}
+
+/// Exits a span previously entered with [`enter_span`].
+macro_rules! exit_span {
+    ($guard:ident) => {
+        #[cfg(feature = "tracing")]
+        drop($guard);
+    };
+}
+
+pub(crate) use {enter_span, exit_span};
+
 #[cfg(test)]
 mod test {
     use crate::batch_field_test;
@@ -219,6 +307,7 @@ mod test {
     use ark_bls12_377::Fr as Bls12_377_scalar_field;
     use ark_bls12_381::Fr as Bls12_381_scalar_field;
     use ark_ff::Field;
+    use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
     use rand_core::OsRng;
 
     fn test_correct_lc<F: Field>() {
@@ -261,20 +350,112 @@ mod test {
             assert_eq!(result, expected)
         }
     }
+    fn test_domain_elements<F: PrimeField>() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let domain = GeneralEvaluationDomain::<F>::new(16).unwrap();
+        let mut elements = domain.elements();
+        assert_send(&elements);
+
+        // The iterator is lazy, so it can be consumed one element at a time.
+        assert_eq!(elements.next(), Some(F::one()));
+        assert_eq!(elements.next(), Some(domain.group_gen()));
+
+        let elements = domain.elements().collect::<Vec<_>>();
+        assert_eq!(elements.len(), domain.size());
+        for (i, element) in elements.iter().enumerate() {
+            assert_eq!(*element, domain.group_gen().pow([i as u64]));
+        }
+        assert_eq!(*elements.last().unwrap() * domain.group_gen(), F::one());
+    }
+
+    fn test_coset_fft_with_shift<F: PrimeField>() {
+        let domain = GeneralEvaluationDomain::<F>::new(16).unwrap();
+        let coeffs = (0..16).map(|_| F::rand(&mut OsRng)).collect::<Vec<_>>();
+
+        let shift = F::rand(&mut OsRng);
+        let evals = domain.coset_fft_with_shift(&coeffs, shift);
+        assert_eq!(domain.coset_ifft_with_shift(&evals, shift), coeffs);
+
+        // The evaluations are the ones of the polynomial over `shift * H`.
+        let polynomial = DensePolynomial::from_coefficients_slice(&coeffs);
+        for (element, eval) in domain.elements().zip(&evals) {
+            assert_eq!(polynomial.evaluate(&(shift * element)), *eval);
+        }
+
+        // The default shift gives the coset FFT of the domain.
+        let default_shift = domain.coset_shift();
+        assert_eq!(
+            domain.coset_fft_with_shift(&coeffs, default_shift),
+            domain.coset_fft(&coeffs)
+        );
+        assert_eq!(
+            domain.coset_ifft_with_shift(&evals, default_shift),
+            domain.coset_ifft(&evals)
+        );
+    }
+
+    fn test_reindex_by_coset<F: PrimeField>() {
+        let domain = GeneralEvaluationDomain::<F>::new(16).unwrap();
+        let coeffs = (0..16).map(|_| F::rand(&mut OsRng)).collect::<Vec<_>>();
+        let evals = domain.fft(&coeffs);
+        let polynomial = DensePolynomial::from_coefficients_slice(&coeffs);
+
+        let cosets = domain.reindex_by_coset(&evals, 4);
+        assert_eq!(cosets.len(), 4);
+        let subgroup_gen = domain.group_gen().pow([4u64]);
+        for (j, coset) in cosets.iter().enumerate() {
+            let coset_shift = domain.group_gen().pow([j as u64]);
+            for (element, eval) in powers_of(subgroup_gen).zip(coset) {
+                assert_eq!(
+                    polynomial.evaluate(&(coset_shift * element)),
+                    *eval
+                );
+            }
+        }
+
+        // Reading the cosets back element by element gives the evaluations
+        // in the order of the domain.
+        let read_back = (0..4)
+            .flat_map(|i| cosets.iter().map(move |coset| coset[i]))
+            .collect::<Vec<_>>();
+        assert_eq!(read_back, evals);
+
+        // The trivial subgroup and the whole domain.
+        assert_eq!(
+            domain.reindex_by_coset(&evals, 1),
+            evals.iter().map(|eval| vec![*eval]).collect::<Vec<_>>()
+        );
+        assert_eq!(domain.reindex_by_coset(&evals, 16), vec![evals]);
+    }
+
+    fn test_reindex_by_coset_bad_size<F: PrimeField>() {
+        let domain = GeneralEvaluationDomain::<F>::new(16).unwrap();
+        domain.reindex_by_coset(&[F::zero(); 16], 3);
+    }
+
     batch_field_test!(
         [
-        test_correct_lc
+        test_correct_lc,
+        test_domain_elements,
+        test_coset_fft_with_shift,
+        test_reindex_by_coset
         ],
         [
-        test_incorrect_lc
+        test_incorrect_lc,
+        test_reindex_by_coset_bad_size
         ] => Bls12_381_scalar_field
     );
     batch_field_test!(
         [
-        test_correct_lc
+        test_correct_lc,
+        test_domain_elements,
+        test_coset_fft_with_shift,
+        test_reindex_by_coset
         ],
         [
-        test_incorrect_lc
+        test_incorrect_lc,
+        test_reindex_by_coset_bad_size
         ] => Bls12_377_scalar_field
     );
 }
@@ -13,4 +13,6 @@
 
 pub extern crate alloc;
 
+pub mod merkle;
 pub mod poseidon;
+pub mod rescue;
@@ -0,0 +1,567 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-GARAGE. All rights reserved.
+
+//! Binary Merkle tree gadgets.
+
+use crate::poseidon::{
+    constants::PoseidonConstants,
+    poseidon_ref::{PlonkSpecRef, PoseidonRef},
+};
+use ark_ec::{
+    twisted_edwards_extended::GroupAffine as TEGroupAffine, ProjectiveCurve,
+    TEModelParameters,
+};
+use ark_ff::{PrimeField, Zero};
+use core::marker::PhantomData;
+use plonk_core::{constraint_system::StandardComposer, prelude::Variable};
+
+/// Width of the Poseidon permutation hashing two children into their parent.
+pub const MERKLE_WIDTH: usize = 3;
+
+/// Two-to-one hash function used to hash the children of a Merkle tree node
+/// into their parent inside a circuit.
+pub trait HashGadget<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    /// Adds the gates hashing `left` and `right` to the circuit description
+    /// and returns the digest.
+    fn hash_two(
+        &self,
+        composer: &mut StandardComposer<F, P>,
+        left: Variable,
+        right: Variable,
+    ) -> Variable;
+}
+
+/// [`HashGadget`] hashing with a width-3 Poseidon.
+#[derive(Clone, Debug)]
+pub struct PoseidonHashGadget<F>
+where
+    F: PrimeField,
+{
+    /// Constants of the Poseidon permutation.
+    pub constants: PoseidonConstants<F>,
+}
+
+impl<F> Default for PoseidonHashGadget<F>
+where
+    F: PrimeField,
+{
+    fn default() -> Self {
+        Self {
+            constants: PoseidonConstants::generate::<MERKLE_WIDTH>(),
+        }
+    }
+}
+
+impl<F, P> HashGadget<F, P> for PoseidonHashGadget<F>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    fn hash_two(
+        &self,
+        composer: &mut StandardComposer<F, P>,
+        left: Variable,
+        right: Variable,
+    ) -> Variable {
+        let mut hasher = PoseidonRef::<_, PlonkSpecRef, MERKLE_WIDTH>::new(
+            composer,
+            self.constants.clone(),
+        );
+        // A fresh hasher always has room for two inputs.
+        hasher.input(left).unwrap();
+        hasher.input(right).unwrap();
+        hasher.output_hash(composer)
+    }
+}
+
+/// [`HashGadget`] hashing with the Pedersen hash `x(left * G + right * H)`
+/// over the embedded curve of the circuit.
+///
+/// The generators `G` and `H` are the first two points of the prime order
+/// subgroup found by incrementing the x-coordinate from one and clearing the
+/// cofactor, so that nobody knows the discrete logarithm of one with respect
+/// to the other.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct PedersenHashGadget<F>
+where
+    F: PrimeField,
+{
+    _field: PhantomData<F>,
+}
+
+impl<F> PedersenHashGadget<F>
+where
+    F: PrimeField,
+{
+    /// Returns the generators `G` and `H` of the Pedersen hash over the curve
+    /// with parameters `P`.
+    pub fn generators<P>() -> [TEGroupAffine<P>; 2]
+    where
+        P: TEModelParameters<BaseField = F>,
+    {
+        let mut x = F::zero();
+        let mut points = core::iter::from_fn(|| loop {
+            x += F::one();
+            if let Some(point) = TEGroupAffine::<P>::get_point_from_x(x, false)
+            {
+                let point = point.scale_by_cofactor().into_affine();
+                if !point.is_zero() {
+                    return Some(point);
+                }
+            }
+        });
+        [points.next().unwrap(), points.next().unwrap()]
+    }
+}
+
+impl<F, P> HashGadget<F, P> for PedersenHashGadget<F>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    fn hash_two(
+        &self,
+        composer: &mut StandardComposer<F, P>,
+        left: Variable,
+        right: Variable,
+    ) -> Variable {
+        let [g, h] = Self::generators::<P>();
+        let left = composer.fixed_base_scalar_mul(left, g);
+        let right = composer.fixed_base_scalar_mul(right, h);
+        *composer.point_addition_gate(left, right).x()
+    }
+}
+
+#[cfg(all(feature = "pedersen", not(feature = "rescue")))]
+use self::PedersenHashGadget as SelectedHashGadget;
+#[cfg(not(any(feature = "pedersen", feature = "rescue")))]
+use self::PoseidonHashGadget as SelectedHashGadget;
+#[cfg(feature = "rescue")]
+use crate::rescue::RescueHashGadget as SelectedHashGadget;
+
+/// [`HashGadget`] selected by the `poseidon`, `pedersen` and `rescue`
+/// features, for the callers which don't need a specific hash function.
+///
+/// It's [`RescueHashGadget`](crate::rescue::RescueHashGadget) with the
+/// `rescue` feature, [`PedersenHashGadget`] with the `pedersen` feature if
+/// `rescue` isn't enabled, and [`PoseidonHashGadget`] otherwise, as with the
+/// `poseidon` feature which is enabled by default. It's the hash of
+/// [`MerkleGadget`] unless another [`HashGadget`] is given explicitly.
+pub type DefaultHashGadget<F> = SelectedHashGadget<F>;
+
+/// Merkle tree gadgets of the [`StandardComposer`], generic over the
+/// [`HashGadget`] `H` hashing the children of a node into their parent,
+/// which is [`DefaultHashGadget`] unless given explicitly.
+pub trait MerkleGadget<F, P, H = DefaultHashGadget<F>>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    H: HashGadget<F, P>,
+{
+    /// Computes the root of the full binary Merkle tree over `leaves`,
+    /// hashing every pair of children with `hasher`.
+    ///
+    /// The leaves are padded with the zero variable up to the next power of
+    /// two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `leaves` is empty.
+    fn merkle_root(&mut self, hasher: &H, leaves: &[Variable]) -> Variable;
+}
+
+impl<F, P, H> MerkleGadget<F, P, H> for StandardComposer<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    H: HashGadget<F, P>,
+{
+    fn merkle_root(&mut self, hasher: &H, leaves: &[Variable]) -> Variable {
+        assert!(
+            !leaves.is_empty(),
+            "Cannot build a Merkle tree without leaves"
+        );
+        let mut layer = leaves.to_vec();
+        layer.resize(leaves.len().next_power_of_two(), self.zero_var());
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| hasher.hash_two(self, pair[0], pair[1]))
+                .collect();
+        }
+        layer[0]
+    }
+}
+
+/// Constrains `leaf` to be a leaf of the Merkle tree with the given `root`.
+///
+/// The `path` goes from the leaf up to the root, and every step holds the
+/// sibling of the current node together with a bit which is `1` if the
+/// current node is the right child and `0` if it's the left one. The bits are
+/// constrained to be boolean.
+pub fn merkle_verify<F, P, H>(
+    composer: &mut StandardComposer<F, P>,
+    hasher: &H,
+    leaf: Variable,
+    path: &[(Variable, Variable)],
+    root: Variable,
+) where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    H: HashGadget<F, P>,
+{
+    for &(_, is_right) in path {
+        composer.boolean_gate(is_right);
+    }
+    let computed_root = path_root(composer, hasher, leaf, path);
+    composer.assert_equal(computed_root, root);
+}
+
+/// Hashes `leaf` up to the root along `path`, without constraining the bits
+/// of the path to be boolean.
+fn path_root<F, P, H>(
+    composer: &mut StandardComposer<F, P>,
+    hasher: &H,
+    leaf: Variable,
+    path: &[(Variable, Variable)],
+) -> Variable
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    H: HashGadget<F, P>,
+{
+    path.iter().fold(leaf, |node, &(sibling, is_right)| {
+        let left = composer.conditional_select(is_right, sibling, node);
+        let right = composer.conditional_select(is_right, node, sibling);
+        hasher.hash_two(composer, left, right)
+    })
+}
+
+/// Constrains `value` to be the element at position `index` of the vector
+/// committed to by the Merkle tree with the given `root`.
+///
+/// This is [`merkle_verify`] with the additional constraint that the bits of
+/// the `path`, read from the leaf up as little-endian, are the binary
+/// decomposition of `index`, so that the opening can't be moved to another
+/// position of the vector.
+pub fn verify_vector_opening<F, P, H>(
+    composer: &mut StandardComposer<F, P>,
+    hasher: &H,
+    root: Variable,
+    index: Variable,
+    value: Variable,
+    path: &[(Variable, Variable)],
+) where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    H: HashGadget<F, P>,
+{
+    merkle_verify(composer, hasher, value, path, root);
+
+    // The bits are already constrained to be boolean by `merkle_verify`.
+    let mut power = F::one();
+    let terms = path
+        .iter()
+        .map(|&(_, is_right)| {
+            let term = (power, is_right);
+            power.double_in_place();
+            term
+        })
+        .collect::<Vec<_>>();
+    let path_index = composer.weighted_sum(&terms, F::zero());
+    composer.assert_equal(path_index, index);
+}
+
+/// Replaces the leaf at position `index` of the Merkle tree with the given
+/// `old_root`, constrained to be `old_leaf`, with `new_leaf` and returns the
+/// root of the updated tree.
+///
+/// The opening of `old_leaf` is checked with [`verify_vector_opening`] and
+/// the new root is computed along the same `path`, whose siblings are left
+/// untouched by the update.
+pub fn merkle_update<F, P, H>(
+    composer: &mut StandardComposer<F, P>,
+    hasher: &H,
+    old_root: Variable,
+    index: Variable,
+    old_leaf: Variable,
+    new_leaf: Variable,
+    path: &[(Variable, Variable)],
+) -> Variable
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+    H: HashGadget<F, P>,
+{
+    verify_vector_opening(composer, hasher, old_root, index, old_leaf, path);
+    path_root(composer, hasher, new_leaf, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon::poseidon_ref::NativeSpecRef;
+    use ark_ec::PairingEngine;
+    use ark_std::{test_rng, UniformRand};
+    use plonk_core::constraint_system::StandardComposer;
+
+    type E = ark_bls12_381::Bls12_381;
+    type P = ark_ed_on_bls12_381::EdwardsParameters;
+    type Fr = <E as PairingEngine>::Fr;
+
+    fn native_hash(constants: &PoseidonConstants<Fr>, l: Fr, r: Fr) -> Fr {
+        let mut poseidon =
+            PoseidonRef::<(), NativeSpecRef<Fr>, MERKLE_WIDTH>::new(
+                &mut (),
+                constants.clone(),
+            );
+        poseidon.input(l).unwrap();
+        poseidon.input(r).unwrap();
+        poseidon.output_hash(&mut ())
+    }
+
+    fn check_merkle_root(num_leaves: usize) {
+        let mut rng = test_rng();
+        let constants = PoseidonConstants::generate::<MERKLE_WIDTH>();
+        let leaves = (0..num_leaves)
+            .map(|_| Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+
+        // Reference tree built layer by layer.
+        let mut layer = leaves.clone();
+        layer.resize(num_leaves.next_power_of_two(), Fr::from(0u64));
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(2)
+                .map(|pair| native_hash(&constants, pair[0], pair[1]))
+                .collect();
+        }
+        let expected = layer[0];
+
+        let mut c = StandardComposer::<Fr, P>::new();
+        let leaves_var =
+            leaves.iter().map(|x| c.add_input(*x)).collect::<Vec<_>>();
+        let root =
+            c.merkle_root(&PoseidonHashGadget { constants }, &leaves_var);
+        assert_eq!(c.value_of_var(root), expected);
+
+        let expected_var = c.add_input(expected);
+        c.assert_equal(expected_var, root);
+        c.check_circuit_satisfied();
+    }
+
+    /// Toy hash `H(l, r) = l^2 + 3r + 1`, only meant to check that
+    /// [`merkle_verify`] doesn't depend on the hash function.
+    struct SquareSumHash;
+
+    impl HashGadget<Fr, P> for SquareSumHash {
+        fn hash_two(
+            &self,
+            composer: &mut StandardComposer<Fr, P>,
+            left: Variable,
+            right: Variable,
+        ) -> Variable {
+            let square = composer.arithmetic_gate(|gate| {
+                gate.witness(left, left, None).mul(Fr::from(1u64))
+            });
+            composer.arithmetic_gate(|gate| {
+                gate.witness(square, right, None)
+                    .add(Fr::from(1u64), Fr::from(3u64))
+                    .constant(Fr::from(1u64))
+            })
+        }
+    }
+
+    /// Builds a depth-2 tree with `hasher` and opens its third leaf, with the
+    /// sibling of the first step replaced by another leaf if `tamper` is set.
+    fn check_merkle_verify<H: HashGadget<Fr, P>>(hasher: &H, tamper: bool) {
+        let mut c = StandardComposer::<Fr, P>::new();
+        let leaves = [3u64, 5, 7, 11].map(|leaf| c.add_input(Fr::from(leaf)));
+        let n_01 = hasher.hash_two(&mut c, leaves[0], leaves[1]);
+        let n_23 = hasher.hash_two(&mut c, leaves[2], leaves[3]);
+        let root = hasher.hash_two(&mut c, n_01, n_23);
+        let root = c.add_input(c.value_of_var(root));
+
+        let zero = c.zero_var();
+        let one = c.add_input(Fr::from(1u64));
+        let sibling = if tamper { leaves[1] } else { leaves[3] };
+        merkle_verify(
+            &mut c,
+            hasher,
+            leaves[2],
+            &[(sibling, zero), (n_01, one)],
+            root,
+        );
+        c.check_circuit_satisfied();
+    }
+
+    /// Opens the third leaf of a depth-2 tree at the claimed `index`.
+    fn check_vector_opening(index: u64) {
+        let hasher = PoseidonHashGadget::default();
+        let mut c = StandardComposer::<Fr, P>::new();
+        let leaves = [3u64, 5, 7, 11].map(|leaf| c.add_input(Fr::from(leaf)));
+        let n_01 = hasher.hash_two(&mut c, leaves[0], leaves[1]);
+        let n_23 = hasher.hash_two(&mut c, leaves[2], leaves[3]);
+        let root = hasher.hash_two(&mut c, n_01, n_23);
+        let root = c.add_input(c.value_of_var(root));
+
+        let zero = c.zero_var();
+        let one = c.add_input(Fr::from(1u64));
+        let index = c.add_input(Fr::from(index));
+        verify_vector_opening(
+            &mut c,
+            &hasher,
+            root,
+            index,
+            leaves[2],
+            &[(leaves[3], zero), (n_01, one)],
+        );
+        c.check_circuit_satisfied();
+    }
+
+    /// Updates the leaf at `index` of a depth-4 tree, opening it with
+    /// `old_leaf` as the claimed value.
+    fn check_merkle_update(index: usize, old_leaf: u64) {
+        let constants = PoseidonConstants::generate::<MERKLE_WIDTH>();
+        let mut leaves = (0..16u64).map(Fr::from).collect::<Vec<_>>();
+
+        // Reference layers, from the leaves up to the root.
+        let layers = |leaves: &[Fr]| {
+            let mut layers = vec![leaves.to_vec()];
+            while layers.last().unwrap().len() > 1 {
+                let layer = layers
+                    .last()
+                    .unwrap()
+                    .chunks(2)
+                    .map(|pair| native_hash(&constants, pair[0], pair[1]))
+                    .collect();
+                layers.push(layer);
+            }
+            layers
+        };
+        let old_layers = layers(&leaves);
+        leaves[index] = Fr::from(100u64);
+        let new_root = layers(&leaves)[4][0];
+
+        let mut c = StandardComposer::<Fr, P>::new();
+        let path = (0..4)
+            .map(|level| {
+                let position = index >> level;
+                let sibling = c.add_input(old_layers[level][position ^ 1]);
+                let is_right = c.add_input(Fr::from((position & 1) as u64));
+                (sibling, is_right)
+            })
+            .collect::<Vec<_>>();
+        let old_root = c.add_input(old_layers[4][0]);
+        let index = c.add_input(Fr::from(index as u64));
+        let old_leaf = c.add_input(Fr::from(old_leaf));
+        let new_leaf = c.add_input(Fr::from(100u64));
+        let root = merkle_update(
+            &mut c,
+            &PoseidonHashGadget::default(),
+            old_root,
+            index,
+            old_leaf,
+            new_leaf,
+            &path,
+        );
+        assert_eq!(c.value_of_var(root), new_root);
+        c.check_circuit_satisfied();
+    }
+
+    #[test]
+    fn merkle_update_depth_4() {
+        check_merkle_update(11, 11);
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn merkle_update_wrong_old_leaf() {
+        check_merkle_update(11, 12);
+    }
+
+    #[test]
+    fn vector_opening_at_index() {
+        check_vector_opening(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn vector_opening_at_wrong_index() {
+        check_vector_opening(3);
+    }
+
+    #[test]
+    fn merkle_verify_with_poseidon() {
+        check_merkle_verify(&PoseidonHashGadget::default(), false);
+    }
+
+    #[test]
+    fn merkle_verify_with_rescue() {
+        check_merkle_verify(&crate::rescue::RescueHashGadget::default(), false);
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "poseidon",
+        not(any(feature = "pedersen", feature = "rescue"))
+    ))]
+    fn default_hash_gadget_is_poseidon() {
+        let constants = PoseidonConstants::generate::<MERKLE_WIDTH>();
+        let leaves = [3u64, 5, 7, 11].map(Fr::from);
+        let expected = native_hash(
+            &constants,
+            native_hash(&constants, leaves[0], leaves[1]),
+            native_hash(&constants, leaves[2], leaves[3]),
+        );
+
+        // The hasher type is left to the default of `MerkleGadget`.
+        let mut c = StandardComposer::<Fr, P>::new();
+        let leaves = leaves.map(|leaf| c.add_input(leaf));
+        let root =
+            <StandardComposer<Fr, P> as MerkleGadget<Fr, P>>::merkle_root(
+                &mut c,
+                &Default::default(),
+                &leaves,
+            );
+        assert_eq!(c.value_of_var(root), expected);
+
+        let expected = c.add_input(expected);
+        c.assert_equal(root, expected);
+        c.check_circuit_satisfied();
+    }
+
+    #[test]
+    fn merkle_verify_with_pedersen() {
+        check_merkle_verify(&PedersenHashGadget::default(), false);
+    }
+
+    #[test]
+    fn merkle_verify_with_other_hash() {
+        check_merkle_verify(&SquareSumHash, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Check failed at gate")]
+    fn merkle_verify_wrong_sibling() {
+        check_merkle_verify(&PoseidonHashGadget::default(), true);
+    }
+
+    #[test]
+    fn merkle_root_four_leaves() {
+        check_merkle_root(4);
+    }
+
+    #[test]
+    fn merkle_root_five_leaves() {
+        check_merkle_root(5);
+    }
+}
@@ -0,0 +1,688 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) ZK-GARAGE. All rights reserved.
+
+//! Rescue-Prime permutation and hash gadget.
+//!
+//! Every round of Rescue-Prime applies the S-box `x -> x^alpha` to the whole
+//! state, multiplies it by an MDS matrix and adds round constants, then does
+//! the same with the inverse S-box `x -> x^(1/alpha)`. In-circuit the inverse
+//! S-box is computed out of circuit and checked by raising it to `alpha`.
+//!
+//! The parameters are generated as in the reference implementation of
+//! [Rescue-Prime](https://eprint.iacr.org/2020/1143): the number of rounds
+//! from the cost of Gröbner basis attacks, the MDS matrix from the echelon
+//! form of a Vandermonde matrix and the round constants from SHAKE256.
+
+use crate::{merkle::HashGadget, poseidon::matrix::Matrix};
+use ark_ec::TEModelParameters;
+use ark_ff::{FpParameters, PrimeField};
+use num_bigint::BigUint;
+use num_traits::One;
+use plonk_core::{constraint_system::StandardComposer, prelude::Variable};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
+
+/// Security level in bits of the default [`RescueHashGadget`].
+pub const DEFAULT_RESCUE_SECURITY_LEVEL: usize = 128;
+
+/// Parameters of a Rescue-Prime permutation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RescueParameters<F>
+where
+    F: PrimeField,
+{
+    /// Number of field elements of the state.
+    pub width: usize,
+    /// Number of field elements of the state which aren't absorbed into or
+    /// squeezed from by the sponge.
+    pub capacity: usize,
+    /// Number of rounds, each made of a forward and an inverse S-box layer.
+    pub rounds: usize,
+    /// Degree of the S-box, the smallest prime coprime with `p - 1`.
+    pub alpha: u64,
+    /// Little-endian limbs of the inverse of `alpha` modulo `p - 1`.
+    pub alpha_inv: Vec<u64>,
+    /// MDS matrix applied after every S-box layer.
+    pub mds: Matrix<F>,
+    /// Constants added after every MDS multiplication, `2 * rounds * width`
+    /// in total.
+    pub round_constants: Vec<F>,
+}
+
+impl<F> RescueParameters<F>
+where
+    F: PrimeField,
+{
+    /// Creates the parameters of a Rescue-Prime permutation of `width` field
+    /// elements, `capacity` of which are reserved by the sponge, with the
+    /// given number of `rounds`, `mds` matrix and `round_constants`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` isn't smaller than `width`, if `mds` isn't a
+    /// `width * width` matrix or if there aren't `2 * rounds * width` round
+    /// constants.
+    pub fn new(
+        width: usize,
+        capacity: usize,
+        rounds: usize,
+        mds: Matrix<F>,
+        round_constants: Vec<F>,
+    ) -> Self {
+        assert!(
+            capacity < width,
+            "The capacity must be smaller than the width"
+        );
+        assert!(
+            mds.is_square() && mds.num_rows() == width,
+            "The MDS matrix must be {} x {}",
+            width,
+            width
+        );
+        assert_eq!(
+            round_constants.len(),
+            2 * rounds * width,
+            "Wrong number of round constants"
+        );
+        let (alpha, alpha_inv) = sbox_exponents::<F>();
+        Self {
+            width,
+            capacity,
+            rounds,
+            alpha,
+            alpha_inv,
+            mds,
+            round_constants,
+        }
+    }
+
+    /// Generates the parameters of the Rescue-Prime permutation of `width`
+    /// field elements with the given `capacity` and `security_level` in bits,
+    /// as specified by the reference implementation.
+    ///
+    /// The primitive element the MDS matrix is built from is the
+    /// multiplicative generator of `F`, which arkworks fields define as the
+    /// smallest one as the specification requires.
+    pub fn generate(
+        width: usize,
+        capacity: usize,
+        security_level: usize,
+    ) -> Self {
+        let (alpha, _) = sbox_exponents::<F>();
+        let rounds =
+            number_of_rounds(width, capacity, security_level, alpha as usize);
+        Self::new(
+            width,
+            capacity,
+            rounds,
+            mds_matrix(width),
+            round_constants(width, capacity, security_level, rounds),
+        )
+    }
+
+    /// Returns the number of field elements absorbed or squeezed by every
+    /// permutation of the sponge.
+    pub fn rate(&self) -> usize {
+        self.width - self.capacity
+    }
+}
+
+/// Returns the number of rounds resisting Gröbner basis attacks on a
+/// permutation of `width` elements with S-box degree `alpha`, at least five,
+/// with a 50% security margin.
+fn number_of_rounds(
+    width: usize,
+    capacity: usize,
+    security_level: usize,
+    alpha: usize,
+) -> usize {
+    let binomial = |n: usize, k: usize| {
+        (0..k).fold(BigUint::one(), |acc, i| acc * (n - i) / (i + 1))
+    };
+    let target = BigUint::one() << security_level;
+    let rounds = (1..25)
+        .find(|rounds| {
+            let degree = (alpha - 1) * width * (rounds - 1) / 2 + 2;
+            let variables = width * (rounds - 1) + width - capacity;
+            binomial(variables + degree, variables).pow(2) > target
+        })
+        .unwrap_or(24);
+    (3 * rounds.max(5) + 1) / 2
+}
+
+/// Returns the transpose of the right half of the echelon form of the
+/// `width * 2 width` Vandermonde matrix `(g^(i * j))` of the multiplicative
+/// generator `g` of `F`.
+fn mds_matrix<F>(width: usize) -> Matrix<F>
+where
+    F: PrimeField,
+{
+    let vandermonde = |columns: core::ops::Range<usize>| {
+        (0..width)
+            .map(|i| {
+                columns
+                    .clone()
+                    .map(|j| {
+                        F::multiplicative_generator().pow([(i * j) as u64])
+                    })
+                    .collect()
+            })
+            .collect::<Matrix<F>>()
+    };
+    // The echelon form of `[left | right]` is `[I | left^-1 * right]`, as
+    // `left` is an invertible Vandermonde matrix.
+    vandermonde(0..width)
+        .invert()
+        .expect("Vandermonde matrices of distinct elements are invertible")
+        .matmul(&vandermonde(width..2 * width))
+        .unwrap()
+        .transpose()
+}
+
+/// Derives the `2 * rounds * width` round constants from the SHAKE256 output
+/// for the seed `Rescue-XLIX(p,width,capacity,security_level)`, reducing
+/// little-endian chunks one byte longer than the modulus.
+fn round_constants<F>(
+    width: usize,
+    capacity: usize,
+    security_level: usize,
+    rounds: usize,
+) -> Vec<F>
+where
+    F: PrimeField,
+{
+    let modulus: BigUint = F::Params::MODULUS.into();
+    let bytes_per_int = (F::Params::MODULUS_BITS as usize + 7) / 8 + 1;
+    let mut shake = Shake256::default();
+    shake.update(
+        format!(
+            "Rescue-XLIX({},{},{},{})",
+            modulus, width, capacity, security_level
+        )
+        .as_bytes(),
+    );
+    let mut reader = shake.finalize_xof();
+    let mut chunk = vec![0u8; bytes_per_int];
+    (0..2 * rounds * width)
+        .map(|_| {
+            reader.read(&mut chunk);
+            let value = BigUint::from_bytes_le(&chunk) % &modulus;
+            F::BigInt::try_from(value)
+                .ok()
+                .and_then(F::from_repr)
+                .expect("The value is reduced modulo p")
+        })
+        .collect()
+}
+
+/// Returns the smallest prime `alpha` such that `x -> x^alpha` is a
+/// permutation of `F`, together with the little-endian limbs of its inverse
+/// modulo `p - 1`.
+fn sbox_exponents<F>() -> (u64, Vec<u64>)
+where
+    F: PrimeField,
+{
+    let mut p_minus_one = F::Params::MODULUS.as_ref().to_vec();
+    // The modulus is odd, so there is no borrow.
+    p_minus_one[0] -= 1;
+
+    let is_prime =
+        |n: u64| (2..).take_while(|d| d * d <= n).all(|d| n % d != 0);
+    let alpha = (3..)
+        .filter(|a| is_prime(*a))
+        .find(|a| div_small(&p_minus_one, *a).1 != 0)
+        .unwrap();
+
+    // alpha_inv = (k * (p - 1) + 1) / alpha for the k making it an integer.
+    let rem = div_small(&p_minus_one, alpha).1 as u128;
+    let k = (1..alpha)
+        .find(|k| (*k as u128 * rem + 1) % alpha as u128 == 0)
+        .unwrap();
+    let (alpha_inv, _) = div_small(&mul_small_add(&p_minus_one, k, 1), alpha);
+    (alpha, alpha_inv)
+}
+
+/// Computes `limbs * k + add` over little-endian `u64` limbs.
+fn mul_small_add(limbs: &[u64], k: u64, add: u64) -> Vec<u64> {
+    let mut carry = add as u128;
+    let mut result = limbs
+        .iter()
+        .map(|limb| {
+            let value = *limb as u128 * k as u128 + carry;
+            carry = value >> 64;
+            value as u64
+        })
+        .collect::<Vec<_>>();
+    if carry != 0 {
+        result.push(carry as u64);
+    }
+    result
+}
+
+/// Divides little-endian `u64` limbs by `d`, returning the quotient and the
+/// remainder.
+fn div_small(limbs: &[u64], d: u64) -> (Vec<u64>, u64) {
+    let mut rem = 0u128;
+    let mut quotient = vec![0u64; limbs.len()];
+    for (q, limb) in quotient.iter_mut().zip(limbs).rev() {
+        let current = (rem << 64) | *limb as u128;
+        *q = (current / d as u128) as u64;
+        rem = current % d as u128;
+    }
+    (quotient, rem as u64)
+}
+
+/// Applies the Rescue-Prime permutation to `state` natively.
+///
+/// # Panics
+///
+/// Panics if the length of `state` isn't the width of the permutation.
+pub fn rescue_permutation_native<F>(
+    params: &RescueParameters<F>,
+    state: &mut [F],
+) where
+    F: PrimeField,
+{
+    assert_eq!(state.len(), params.width, "Wrong state width");
+    for (step, constants) in
+        params.round_constants.chunks(params.width).enumerate()
+    {
+        for x in state.iter_mut() {
+            *x = if step % 2 == 0 {
+                x.pow([params.alpha])
+            } else {
+                x.pow(&params.alpha_inv)
+            };
+        }
+        let mixed = params.mds.mul_col_vec(state);
+        for ((x, m), c) in state.iter_mut().zip(mixed).zip(constants) {
+            *x = m + c;
+        }
+    }
+}
+
+/// Adds the gates applying the Rescue-Prime permutation to `state` to the
+/// circuit description.
+///
+/// Both S-box layers cost the gates of an exponentiation by `alpha`, and each
+/// MDS multiplication with the round constants is one
+/// [`StandardComposer::weighted_sum`] per state element.
+///
+/// # Panics
+///
+/// Panics if the length of `state` isn't the width of the permutation.
+pub fn rescue_permutation<F, P>(
+    composer: &mut StandardComposer<F, P>,
+    params: &RescueParameters<F>,
+    state: &mut [Variable],
+) where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    assert_eq!(state.len(), params.width, "Wrong state width");
+    for (step, constants) in
+        params.round_constants.chunks(params.width).enumerate()
+    {
+        for x in state.iter_mut() {
+            *x = if step % 2 == 0 {
+                pow_gadget(composer, *x, params.alpha)
+            } else {
+                // y = x^(1/alpha) is the only solution of y^alpha = x.
+                let root = composer.add_input(
+                    composer.value_of_var(*x).pow(&params.alpha_inv),
+                );
+                let check = pow_gadget(composer, root, params.alpha);
+                composer.assert_equal(check, *x);
+                root
+            };
+        }
+        let mixed = params
+            .mds
+            .iter_rows()
+            .zip(constants)
+            .map(|(row, c)| {
+                let terms = row
+                    .iter()
+                    .zip(state.iter())
+                    .map(|(m, x)| (*m, *x))
+                    .collect::<Vec<_>>();
+                composer.weighted_sum(&terms, *c)
+            })
+            .collect::<Vec<_>>();
+        state.copy_from_slice(&mixed);
+    }
+}
+
+/// Hashes `inputs` natively with the Rescue-Prime sponge, returning
+/// [`RescueParameters::rate`] field elements.
+///
+/// The inputs are padded with a one and then zeros up to a multiple of the
+/// rate, and every block is added to the rate part of the state, which starts
+/// at zero, before a permutation.
+pub fn rescue_hash_native<F>(
+    params: &RescueParameters<F>,
+    inputs: &[F],
+) -> Vec<F>
+where
+    F: PrimeField,
+{
+    let rate = params.rate();
+    let mut padded = inputs.to_vec();
+    padded.push(F::one());
+    padded.resize((padded.len() + rate - 1) / rate * rate, F::zero());
+
+    let mut state = vec![F::zero(); params.width];
+    for block in padded.chunks(rate) {
+        for (x, input) in state.iter_mut().zip(block) {
+            *x += input;
+        }
+        rescue_permutation_native(params, &mut state);
+    }
+    state.truncate(rate);
+    state
+}
+
+/// Adds the gates hashing `inputs` with the Rescue-Prime sponge to the
+/// circuit description and returns the digest, as computed by
+/// [`rescue_hash_native`].
+pub fn rescue_hash<F, P>(
+    composer: &mut StandardComposer<F, P>,
+    params: &RescueParameters<F>,
+    inputs: &[Variable],
+) -> Vec<Variable>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    let rate = params.rate();
+    let mut padded = inputs.to_vec();
+    padded.push(composer.add_witness_to_circuit_description(F::one()));
+    padded.resize((padded.len() + rate - 1) / rate * rate, composer.zero_var());
+
+    let mut state = vec![composer.zero_var(); params.width];
+    for (i, block) in padded.chunks(rate).enumerate() {
+        for (x, input) in state.iter_mut().zip(block) {
+            // The state is still zero while absorbing the first block.
+            *x = if i == 0 {
+                *input
+            } else {
+                composer.arithmetic_gate(|gate| {
+                    gate.witness(*x, *input, None).add(F::one(), F::one())
+                })
+            };
+        }
+        rescue_permutation(composer, params, &mut state);
+    }
+    state.truncate(rate);
+    state
+}
+
+/// Raises `x` to the power `exp` with square-and-multiply.
+fn pow_gadget<F, P>(
+    composer: &mut StandardComposer<F, P>,
+    x: Variable,
+    exp: u64,
+) -> Variable
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    let mut acc = x;
+    for i in (0..63 - exp.leading_zeros()).rev() {
+        acc = composer
+            .arithmetic_gate(|gate| gate.witness(acc, acc, None).mul(F::one()));
+        if (exp >> i) & 1 == 1 {
+            acc = composer.arithmetic_gate(|gate| {
+                gate.witness(acc, x, None).mul(F::one())
+            });
+        }
+    }
+    acc
+}
+
+/// [`HashGadget`] hashing with the Rescue-Prime sponge of width 3 and
+/// capacity 1.
+///
+/// The digest is the first element of the [`rescue_hash`] of the children.
+#[derive(Clone, Debug)]
+pub struct RescueHashGadget<F>
+where
+    F: PrimeField,
+{
+    /// Parameters of the Rescue-Prime permutation.
+    pub params: RescueParameters<F>,
+}
+
+impl<F> Default for RescueHashGadget<F>
+where
+    F: PrimeField,
+{
+    fn default() -> Self {
+        Self {
+            params: RescueParameters::generate(
+                3,
+                1,
+                DEFAULT_RESCUE_SECURITY_LEVEL,
+            ),
+        }
+    }
+}
+
+impl<F, P> HashGadget<F, P> for RescueHashGadget<F>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    fn hash_two(
+        &self,
+        composer: &mut StandardComposer<F, P>,
+        left: Variable,
+        right: Variable,
+    ) -> Variable {
+        rescue_hash(composer, &self.params, &[left, right])[0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::PairingEngine;
+    use ark_ff::{
+        biginteger::BigInteger256, FftParameters, Field, Fp256, Fp256Parameters,
+    };
+    use ark_std::{str::FromStr, test_rng, UniformRand};
+
+    type E = ark_bls12_381::Bls12_381;
+    type P = ark_ed_on_bls12_381::EdwardsParameters;
+    type Fr = <E as PairingEngine>::Fr;
+
+    /// Prime field of order `p = 407 * 2^119 + 1` the reference
+    /// implementation publishes test vectors for.
+    type Fp = Fp256<FpParameters407>;
+
+    struct FpParameters407;
+
+    impl Fp256Parameters for FpParameters407 {}
+
+    impl FftParameters for FpParameters407 {
+        type BigInt = BigInteger256;
+
+        const TWO_ADICITY: u32 = 119;
+
+        const TWO_ADIC_ROOT_OF_UNITY: BigInteger256 =
+            BigInteger256([0x5c6555befd4cc7e1, 0x0f2db3b3c7556a44, 0, 0]);
+    }
+
+    impl ark_ff::FpParameters for FpParameters407 {
+        const MODULUS: BigInteger256 =
+            BigInteger256([0x1, 0xcb80000000000000, 0, 0]);
+
+        const MODULUS_BITS: u32 = 128;
+
+        const CAPACITY: u32 = Self::MODULUS_BITS - 1;
+
+        const REPR_SHAVE_BITS: u32 = 128;
+
+        const R: BigInteger256 =
+            BigInteger256([0x5bd53a7f0e778236, 0xaaf4ad9a1a6aedc2, 0, 0]);
+
+        const R2: BigInteger256 =
+            BigInteger256([0x6bc48d9fc7ea89cc, 0x57ad601b5043cff0, 0, 0]);
+
+        const INV: u64 = 0xffffffffffffffff;
+
+        // 3 is the smallest primitive element.
+        const GENERATOR: BigInteger256 =
+            BigInteger256([0x137faf7d2b6686a0, 0x69de08ce4f40c947, 0, 0]);
+
+        const MODULUS_MINUS_ONE_DIV_TWO: BigInteger256 =
+            BigInteger256([0x0, 0x65c0000000000000, 0, 0]);
+
+        const T: BigInteger256 = BigInteger256([407, 0, 0, 0]);
+
+        const T_MINUS_ONE_DIV_TWO: BigInteger256 =
+            BigInteger256([203, 0, 0, 0]);
+    }
+
+    #[test]
+    fn rescue_prime_reference_vectors() {
+        let params = RescueParameters::<Fp>::generate(2, 1, 128);
+        assert_eq!(params.alpha, 3);
+        // 180331931428153586757283157844700080811
+        assert_eq!(
+            params.alpha_inv,
+            [0xaaaaaaaaaaaaaaab, 0x87aaaaaaaaaaaaaa, 0, 0]
+        );
+        assert_eq!(params.rounds, 27);
+        assert_eq!(
+            params.mds,
+            Matrix::from(vec![
+                vec![-Fp::from(3u64), Fp::from(4u64)],
+                vec![-Fp::from(12u64), Fp::from(13u64)],
+            ])
+        );
+        assert_eq!(
+            params.round_constants[..2],
+            [
+                Fp::from_str("174420698556543096520990950387834928928")
+                    .unwrap(),
+                Fp::from_str("109797589356993153279775383318666383471")
+                    .unwrap(),
+            ]
+        );
+
+        for (input, output) in [
+            ("1", "244180265933090377212304188905974087294"),
+            (
+                "57322816861100832358702415967512842988",
+                "89633745865384635541695204788332415101",
+            ),
+        ] {
+            let mut state = [Fp::from_str(input).unwrap(), Fp::from(0u64)];
+            rescue_permutation_native(&params, &mut state);
+            assert_eq!(state[0], Fp::from_str(output).unwrap());
+        }
+    }
+    #[test]
+    fn sbox_exponents_are_inverse() {
+        let params = RescueParameters::<Fr>::generate(3, 1, 128);
+        // p - 1 is divisible by 3 for the scalar field of BLS12-381.
+        assert_eq!(params.alpha, 5);
+        let mut rng = test_rng();
+        for _ in 0..8 {
+            let x = Fr::rand(&mut rng);
+            assert_eq!(x.pow(&params.alpha_inv).pow([params.alpha]), x);
+        }
+    }
+
+    #[test]
+    fn rescue_permutation_matches_native() {
+        let mut rng = test_rng();
+        let params = RescueParameters::<Fr>::generate(
+            3,
+            1,
+            DEFAULT_RESCUE_SECURITY_LEVEL,
+        );
+        let input = [(); 3].map(|_| Fr::rand(&mut rng));
+        let mut expected = input;
+        rescue_permutation_native(&params, &mut expected);
+        assert_ne!(expected, input);
+
+        let mut c = StandardComposer::<Fr, P>::new();
+        let mut state = input.map(|x| c.add_input(x));
+        rescue_permutation(&mut c, &params, &mut state);
+        assert_eq!(state.map(|x| c.value_of_var(x)), expected);
+        for (x, y) in state.iter().zip(expected) {
+            c.constrain_to_constant(*x, y, None);
+        }
+        c.check_circuit_satisfied();
+    }
+
+    #[test]
+    fn rescue_parameters_are_configurable() {
+        let mut rng = test_rng();
+        let input = [(); 4].map(|_| Fr::rand(&mut rng));
+        let default = RescueParameters::<Fr>::generate(4, 1, 128);
+        let custom = RescueParameters::<Fr>::new(
+            4,
+            1,
+            default.rounds,
+            Matrix::from(
+                (0..4u64)
+                    .map(|i| {
+                        (4..8u64)
+                            .map(|j| Fr::from(i + 2 * j + 1).inverse().unwrap())
+                            .collect()
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            default.round_constants.clone(),
+        );
+
+        let mut native = input;
+        rescue_permutation_native(&custom, &mut native);
+        let mut other = input;
+        rescue_permutation_native(&default, &mut other);
+        assert_ne!(native, other);
+
+        let mut c = StandardComposer::<Fr, P>::new();
+        let mut state = input.map(|x| c.add_input(x));
+        rescue_permutation(&mut c, &custom, &mut state);
+        assert_eq!(state.map(|x| c.value_of_var(x)), native);
+        c.check_circuit_satisfied();
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong number of round constants")]
+    fn rescue_parameters_check_round_constants() {
+        RescueParameters::<Fr>::new(3, 1, 2, Matrix::identity(3), vec![]);
+    }
+
+    #[test]
+    fn rescue_hash_known_answer() {
+        // Output of the reference implementation for these parameters and
+        // the inputs `[1, 2]`.
+        let params = RescueParameters::<Fr>::generate(3, 1, 128);
+        assert_eq!(params.rounds, 14);
+        let expected = [
+            "42303628707484006548021885135693419317605301127947247732044606598698050891451",
+            "5568822378828862916683630056109958946823505663626286118631195740236217058843",
+        ]
+        .map(|x| Fr::from_str(x).unwrap());
+        let input = [Fr::from(1u64), Fr::from(2u64)];
+        assert_eq!(rescue_hash_native(&params, &input), expected);
+
+        let mut c = StandardComposer::<Fr, P>::new();
+        let input = input.map(|x| c.add_input(x));
+        let digest = rescue_hash(&mut c, &params, &input);
+        for (x, y) in digest.iter().zip(expected) {
+            c.constrain_to_constant(*x, y, None);
+        }
+        c.check_circuit_satisfied();
+    }
+}